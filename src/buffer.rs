@@ -6,15 +6,17 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
+use crate::config::CONFIG;
 use crate::editor::{EditorMode, MultiCursorMode, MultiCursors, Search, SearchMode};
+use crate::encoding::Encoding;
 use crate::endings::LineEndings;
-use crate::syntax::Highlighter;
+use crate::syntax::{HighlightState, Highlighter};
 use ratatui::{
     layout::{Position, Rect},
     widgets::StatefulWidget,
 };
 use std::borrow::Cow;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::BTreeMap;
 use std::num::NonZero;
 use std::ops::Range;
@@ -23,18 +25,98 @@ use std::rc::Rc;
 use std::sync::LazyLock;
 use std::time::SystemTime;
 
-pub static SPACES_PER_TAB: LazyLock<usize> = LazyLock::new(|| {
+pub static SPACES_PER_TAB: LazyLock<usize> = LazyLock::new(|| spaces_per_tab(None));
+
+/// Spaces per tab from $VLE_SPACES_PER_TAB,
+/// or else any syntax-specific setting, or else the config file
+fn spaces_per_tab(syntax: Option<usize>) -> usize {
     std::env::var("VLE_SPACES_PER_TAB")
         .ok()
         .and_then(|s| s.parse::<usize>().ok())
-        .map(|s| s.clamp(1, 16))
+        .or(syntax)
+        .or(CONFIG.spaces_per_tab)
+        .filter(|s| *s > 0)
+        .map(|s| s.min(16))
         .unwrap_or(4)
+}
+
+// if set, the viewport only scrolls when the cursor gets
+// within this many lines of its top or bottom edge,
+// rather than keeping the cursor centered at all times
+static SCROLLOFF: LazyLock<Option<usize>> = LazyLock::new(|| {
+    std::env::var("VLE_SCROLLOFF")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .or(CONFIG.scrolloff)
+});
+
+static ALWAYS_TAB: LazyLock<bool> =
+    LazyLock::new(|| std::env::var("VLE_ALWAYS_TAB").is_ok() || CONFIG.always_tab.unwrap_or(false));
+
+/// Returns the spaces to substitute for tabs and whether to
+/// insert literal tabs instead for the given syntax
+///
+/// Environmental variables take precedence over the config file's
+/// settings for the syntax, which take precedence over global ones
+fn indentation(syntax: &dyn Highlighter) -> (String, bool) {
+    indent_with(CONFIG.indent(&syntax.to_string()), syntax)
+}
+
+/// Applies the syntax's indentation settings from the config file
+fn indent_with(indent: crate::config::Indent, syntax: &dyn Highlighter) -> (String, bool) {
+    (
+        std::iter::repeat_n(' ', spaces_per_tab(indent.spaces_per_tab)).collect(),
+        match indent.always_tab {
+            Some(always_tab) if std::env::var_os("VLE_ALWAYS_TAB").is_none() => always_tab,
+            _ => *ALWAYS_TAB || syntax.tabs_required(),
+        },
+    )
+}
+
+/// Whether to trim trailing whitespace from lines when saving
+static TRIM_TRAILING: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var("VLE_TRIM_TRAILING")
+        .ok()
+        .map(|s| s.trim() != "0")
+        .or(CONFIG.trim_trailing)
+        .unwrap_or(false)
+});
+
+static CURSOR_LINE: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var("VLE_CURSOR_LINE")
+        .map(|s| s.trim() != "0")
+        .unwrap_or(false)
+});
+
+/// Whether to ensure files end in a newline,
+/// or None to preserve whether each file did originally
+static FINAL_NEWLINE: LazyLock<Option<bool>> = LazyLock::new(|| {
+    std::env::var("VLE_FINAL_NEWLINE")
+        .ok()
+        .map(|s| s.trim() != "0")
+        .or(CONFIG.final_newline)
 });
 
-pub static TAB_SUBSTITUTION: LazyLock<String> =
-    LazyLock::new(|| std::iter::repeat_n(' ', *SPACES_PER_TAB).collect());
+/// The most undo steps to keep per buffer,
+/// beyond which the oldest are dropped
+static UNDO_LIMIT: LazyLock<usize> = LazyLock::new(|| {
+    std::env::var("VLE_UNDO_LIMIT")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .or(CONFIG.undo_limit)
+        .unwrap_or(1000)
+});
 
-static ALWAYS_TAB: LazyLock<bool> = LazyLock::new(|| std::env::var("VLE_ALWAYS_TAB").is_ok());
+/// Drops the oldest undo steps beyond the undo limit
+///
+/// Since buffers and their contexts push and pop
+/// undo steps together, trimming each to the same limit
+/// keeps their most recent steps in sync.
+fn trim_undo<T>(undo: &mut Vec<T>) {
+    if let Some(excess) = undo.len().checked_sub(*UNDO_LIMIT) {
+        undo.drain(..excess);
+    }
+}
 
 /// A buffer's source file
 pub enum Source {
@@ -44,6 +126,10 @@ pub enum Source {
         sftp: Rc<ssh2::Sftp>,
         path: PathBuf,
     },
+    Memory {
+        name: String,
+    },
+    Stdin,
     Tutorial,
     Test,
 }
@@ -53,6 +139,8 @@ impl PartialEq for Source {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Self::Local(x), Self::Local(y)) => x == y,
+            (Self::Memory { name: x }, Self::Memory { name: y }) => x == y,
+            (Self::Stdin, Self::Stdin) => true,
             (Self::Tutorial, Self::Tutorial) => true,
             (Self::Test, Self::Test) => true,
             _ => false,
@@ -68,6 +156,8 @@ impl PartialEq for Source {
             (Self::Ssh { sftp: s1, path: x }, Self::Ssh { sftp: s2, path: y }) => {
                 Rc::ptr_eq(s1, s2) && x == y
             }
+            (Self::Memory { name: x }, Self::Memory { name: y }) => x == y,
+            (Self::Stdin, Self::Stdin) => true,
             (Self::Tutorial, Self::Tutorial) => true,
             (Self::Test, Self::Test) => true,
             _ => false,
@@ -90,6 +180,8 @@ impl Source {
             Self::Local(path) => path.to_string_lossy(),
             #[cfg(feature = "ssh")]
             Self::Ssh { path, .. } => path.to_string_lossy(),
+            Self::Memory { name } => name.into(),
+            Self::Stdin => "Standard Input".into(),
             Self::Tutorial => "Welcome!".into(),
             Self::Test => "Terminal Test".into(),
         }
@@ -107,6 +199,8 @@ impl Source {
                 .file_prefix()
                 .map(|s| s.to_string_lossy())
                 .unwrap_or_else(|| "???".into()),
+            Self::Memory { name } => name.into(),
+            Self::Stdin => "Standard Input".into(),
             Self::Tutorial => "Welcome!".into(),
             Self::Test => "Terminal Test".into(),
         }
@@ -118,8 +212,7 @@ impl Source {
             Self::Local(path) => path.file_name().map(|s| s.to_string_lossy()),
             #[cfg(feature = "ssh")]
             Self::Ssh { path, .. } => path.file_name().map(|s| s.to_string_lossy()),
-            Self::Tutorial => None,
-            Self::Test => None,
+            Self::Memory { .. } | Self::Stdin | Self::Tutorial | Self::Test => None,
         }
     }
 
@@ -129,22 +222,44 @@ impl Source {
             Self::Local(path) => path.extension().and_then(|s| s.to_str()),
             #[cfg(feature = "ssh")]
             Self::Ssh { path, .. } => path.extension().and_then(|s| s.to_str()),
-            Self::Tutorial => None,
-            Self::Test => None,
+            Self::Memory { .. } | Self::Stdin | Self::Tutorial | Self::Test => None,
+        }
+    }
+
+    /// Whether the source has no file to save to,
+    /// so one must be chosen before saving
+    pub fn needs_file(&self) -> bool {
+        matches!(self, Self::Memory { .. } | Self::Stdin)
+    }
+
+    /// Whether the source already exists on disk
+    pub fn exists(&self) -> bool {
+        match self {
+            Self::Local(path) => path.exists(),
+            #[cfg(feature = "ssh")]
+            Self::Ssh { sftp, path } => sftp.stat(path).is_ok(),
+            Self::Memory { .. } | Self::Stdin | Self::Tutorial | Self::Test => false,
         }
     }
 
     /// Used for file reloading
-    fn read_string(&self, endings: LineEndings) -> std::io::Result<(Option<SystemTime>, String)> {
+    fn read_string(
+        &self,
+        endings: LineEndings,
+        encoding: Encoding,
+    ) -> std::io::Result<(Option<SystemTime>, String)> {
         match self {
             Self::Local(path) => {
-                let s = std::fs::File::open(path).and_then(|f| endings.reader_to_string(f))?;
-                Ok((path.metadata().and_then(|m| m.modified()).ok(), s))
+                let s = std::fs::File::open(path).and_then(|f| encoding.reader_to_string_as(f))?;
+                Ok((
+                    path.metadata().and_then(|m| m.modified()).ok(),
+                    endings.to_unix(s),
+                ))
             }
             #[cfg(feature = "ssh")]
             Self::Ssh { sftp, path } => match sftp.open(path) {
                 Ok(mut f) => {
-                    let s = endings.reader_to_string(&mut f)?;
+                    let s = endings.to_unix(encoding.reader_to_string_as(&mut f)?);
                     Ok((
                         f.stat().ok().and_then(|stat| stat.mtime).and_then(|secs| {
                             SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::from_secs(secs))
@@ -158,45 +273,48 @@ impl Source {
                 None,
                 include_str!("tutorial.txt").replacen("VERSION", env!("CARGO_PKG_VERSION"), 1),
             )),
+            Self::Memory { .. } => Ok((None, String::default())),
+            Self::Stdin => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "standard input cannot be reloaded",
+            )),
             Self::Test => Ok((None, include_str!("test.txt").to_string())),
         }
     }
 
     /// Used for file loading (can be based on read_string)
-    fn read_data(&self) -> std::io::Result<(Option<SystemTime>, ropey::Rope, LineEndings)> {
+    fn read_data(&self) -> std::io::Result<Loaded> {
         use std::fs::File;
 
         match self {
             Self::Local(path) => match File::open(path) {
-                Ok(mut f) => {
-                    let (endings, rope) = LineEndings::reader_to_rope(&mut f)?;
-                    Ok((f.metadata().and_then(|m| m.modified()).ok(), rope, endings))
-                }
-                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                    Ok((None, ropey::Rope::default(), LineEndings::default()))
-                }
+                Ok(mut f) => Ok(Loaded {
+                    saved: f.metadata().and_then(|m| m.modified()).ok(),
+                    ..Loaded::read(&mut f)?
+                }),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Loaded::default()),
                 Err(e) => Err(e),
             },
             #[cfg(feature = "ssh")]
             Self::Ssh { sftp, path } => match sftp.open(path) {
-                Ok(mut f) => {
-                    let (endings, rope) = LineEndings::reader_to_rope(&mut f)?;
-                    Ok((
-                        f.stat().ok().and_then(|stat| stat.mtime).and_then(|secs| {
-                            SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::from_secs(secs))
-                        }),
-                        rope,
-                        endings,
-                    ))
-                }
-                Err(e) if e.code() == ssh2::ErrorCode::SFTP(2) => {
-                    Ok((None, ropey::Rope::default(), LineEndings::default()))
-                }
+                Ok(mut f) => Ok(Loaded {
+                    saved: f.stat().ok().and_then(|stat| stat.mtime).and_then(|secs| {
+                        SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::from_secs(secs))
+                    }),
+                    ..Loaded::read(&mut f)?
+                }),
+                Err(e) if e.code() == ssh2::ErrorCode::SFTP(2) => Ok(Loaded::default()),
                 Err(e) => Err(e.into()),
             },
+            Self::Memory { .. } => Ok(Loaded::default()),
+            Self::Stdin => Loaded::read(std::io::stdin().lock()),
             Self::Tutorial | Self::Test => self
-                .read_string(LineEndings::default())
-                .map(|(t, s)| (t, ropey::Rope::from(s), LineEndings::default())),
+                .read_string(LineEndings::default(), Encoding::default())
+                .map(|(saved, s)| Loaded {
+                    saved,
+                    rope: ropey::Rope::from(s),
+                    ..Loaded::default()
+                }),
         }
     }
 
@@ -205,20 +323,21 @@ impl Source {
         &self,
         data: &ropey::Rope,
         endings: LineEndings,
+        encoding: Encoding,
     ) -> std::io::Result<Option<SystemTime>> {
         use std::fs::File;
         use std::io::{BufWriter, Write};
 
         match self {
             Self::Local(path) => File::create(path).map(BufWriter::new).and_then(|mut f| {
-                endings.rope_to_writer(data, &mut f)?;
+                encoding.rope_to_writer(endings, data, &mut f)?;
                 f.flush()?;
                 Ok(f.get_mut().metadata().and_then(|m| m.modified()).ok())
             }),
             #[cfg(feature = "ssh")]
             Self::Ssh { sftp, path } => match sftp.create(path) {
                 Ok(mut f) => {
-                    endings.rope_to_writer(data, &mut f)?;
+                    encoding.rope_to_writer(endings, data, &mut f)?;
                     f.flush()?;
                     Ok(f.stat().ok().and_then(|stat| stat.mtime).and_then(|secs| {
                         SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::from_secs(secs))
@@ -226,6 +345,14 @@ impl Source {
                 }
                 Err(e) => Err(e.into()),
             },
+            Self::Memory { name } => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                format!("{name} has no file to save to"),
+            )),
+            Self::Stdin => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "standard input has no file to save to",
+            )),
             Self::Tutorial | Self::Test => Ok(None),
         }
     }
@@ -243,22 +370,48 @@ impl Source {
                         SystemTime::UNIX_EPOCH.checked_add(std::time::Duration::from_secs(secs))
                     })
             }
-            Self::Tutorial | Self::Test => None,
+            Self::Memory { .. } | Self::Stdin | Self::Tutorial | Self::Test => None,
         }
     }
 }
 
+/// A source's data, and how it was stored
+#[derive(Default)]
+struct Loaded {
+    saved: Option<SystemTime>, // when the source was last saved
+    rope: ropey::Rope,         // the source's data
+    endings: LineEndings,      // the source's line endings
+    encoding: Encoding,        // the source's text encoding
+    mixed_endings: bool,       // whether mixed endings were normalized
+}
+
+impl Loaded {
+    /// Reads and decodes data from reader
+    fn read(r: impl std::io::Read) -> std::io::Result<Self> {
+        let (encoding, s) = Encoding::reader_to_string(r)?;
+        let (endings, rope, mixed_endings) = LineEndings::string_to_rope(s);
+        Ok(Self {
+            saved: None,
+            rope,
+            endings,
+            encoding,
+            mixed_endings,
+        })
+    }
+}
+
 mod private {
     use crate::buffer::{AltCursor, Buffer, MainCursor, Toggle};
-    use std::cell::{Ref, RefCell, RefMut};
+    use std::cell::{Cell, Ref, RefCell, RefMut};
     use std::collections::BTreeMap;
     use std::ops::{Deref, DerefMut};
     use std::rc::Rc;
 
     pub struct Rope {
-        rope: ropey::Rope,  // the primary data rope
-        saved: ropey::Rope, // the rope's contents on disk
-        modified: bool,     // whether the rope has been modified
+        rope: ropey::Rope,           // the primary data rope
+        saved: ropey::Rope,          // the rope's contents on disk
+        modified: bool,              // whether the rope has been modified
+        edited: Cell<Option<usize>>, // earliest line edited since last taken
     }
 
     impl From<ropey::Rope> for Rope {
@@ -267,6 +420,7 @@ mod private {
                 saved: rope.clone(),
                 rope,
                 modified: false,
+                edited: Cell::default(),
             }
         }
     }
@@ -286,11 +440,18 @@ mod private {
         /// Mutable handle to data rope
         pub fn get_mut(&mut self) -> RopeHandle<'_> {
             RopeHandle {
+                original: self.rope.clone(),
                 rope: &mut self.rope,
                 saved: &mut self.saved,
                 modified: &mut self.modified,
+                edited: self.edited.get_mut(),
             }
         }
+
+        /// The earliest line edited since this was last called, if any
+        pub fn take_edited(&self) -> Option<usize> {
+            self.edited.take()
+        }
     }
 
     /// If we're not modifying the rope, its modified state can't be changed
@@ -304,9 +465,11 @@ mod private {
 
     /// A handle to guarantee the "modified buffer" flag is calculated correctly
     pub struct RopeHandle<'r> {
+        original: ropey::Rope,
         rope: &'r mut ropey::Rope,
         saved: &'r mut ropey::Rope,
         modified: &'r mut bool,
+        edited: &'r mut Option<usize>,
     }
 
     impl Deref for RopeHandle<'_> {
@@ -328,6 +491,50 @@ mod private {
             // log whether the rope value has been changed
             // from the version that exists on disk
             *self.modified = self.rope != self.saved;
+
+            // and log the earliest line which may have changed
+            // so that anything cached about the lines above it is kept
+            let unchanged = common_prefix(&self.original, self.rope);
+            if unchanged < self.original.len_bytes().max(self.rope.len_bytes()) {
+                let line = self.rope.byte_to_line(unchanged);
+                *self.edited = Some(self.edited.map_or(line, |edited| edited.min(line)));
+            }
+        }
+    }
+
+    /// Number of leading bytes two ropes have in common,
+    /// compared a chunk at a time
+    fn common_prefix(a: &ropey::Rope, b: &ropey::Rope) -> usize {
+        let (mut a_chunks, mut b_chunks) = (a.chunks(), b.chunks());
+        let (mut a_chunk, mut b_chunk): (&[u8], &[u8]) = (&[], &[]);
+        let mut common = 0;
+
+        loop {
+            if a_chunk.is_empty() {
+                match a_chunks.next() {
+                    Some(chunk) => a_chunk = chunk.as_bytes(),
+                    None => return common,
+                }
+            }
+            if b_chunk.is_empty() {
+                match b_chunks.next() {
+                    Some(chunk) => b_chunk = chunk.as_bytes(),
+                    None => return common,
+                }
+            }
+            let len = a_chunk.len().min(b_chunk.len());
+            let (a_head, b_head) = (&a_chunk[..len], &b_chunk[..len]);
+            if a_head != b_head {
+                return common
+                    + a_head
+                        .iter()
+                        .zip(b_head)
+                        .take_while(|(a, b)| a == b)
+                        .count();
+            }
+            common += len;
+            a_chunk = &a_chunk[len..];
+            b_chunk = &b_chunk[len..];
         }
     }
 
@@ -678,17 +885,24 @@ use private::Secondary;
 ///
 /// May be shared between panes
 pub struct Buffer {
-    source: Source,                // the source file
-    endings: LineEndings,          // the source file's line endings
-    saved: Option<SystemTime>,     // when the file was last saved
-    rope: private::Rope,           // the data rope
-    undo: Vec<BufferState>,        // the undo stack
-    undo_finished: bool,           // whether cursor moved since last undo
-    redo: Vec<BufferState>,        // the redo stack
-    syntax: Box<dyn Highlighter>,  // the syntax highlighting to use
-    tabs_required: bool,           // whether the format demands actual tabs
-    tab_substitution: String,      // spaces to substitute for tabs
-    bookmarks: private::Bookmarks, // saved bookmark positions
+    source: Source,                           // the source file
+    endings: LineEndings,                     // the source file's line endings
+    saved_endings: LineEndings,               // the line endings on disk
+    encoding: Encoding,                       // the source file's text encoding
+    mixed_endings: bool,                      // whether mixed endings were normalized on load
+    final_newline: bool,                      // whether file ended with newline on load
+    saved: Option<SystemTime>,                // when the file was last saved
+    noticed: Option<SystemTime>,              // the on-disk change last reported
+    rope: private::Rope,                      // the data rope
+    undo: Vec<BufferState>,                   // the undo stack
+    undo_start: Option<ropey::Rope>,          // rope before latest undo step, until logged
+    undo_finished: bool,                      // whether cursor moved since last undo
+    redo: Vec<BufferState>,                   // the redo stack
+    syntax: Box<dyn Highlighter>,             // the syntax highlighting to use
+    highlights: RefCell<Vec<HighlightState>>, // highlighting state entering each line
+    tabs_required: bool,                      // whether the format demands actual tabs
+    tab_substitution: String,                 // spaces to substitute for tabs
+    bookmarks: private::Bookmarks,            // saved bookmark positions
 }
 
 impl Buffer {
@@ -699,18 +913,36 @@ impl Buffer {
 
     /// Opens file from source, either local or remote
     fn open(source: Source) -> std::io::Result<Self> {
-        let (saved, rope, endings) = source.read_data()?;
-        let syntax = crate::syntax::syntax(&source);
+        let Loaded {
+            saved,
+            rope,
+            endings,
+            encoding,
+            mixed_endings,
+        } = source.read_data()?;
+        let syntax = crate::syntax::syntax(&source, &rope);
+        let (tab_substitution, tabs_required) = indentation(&syntax);
+        let final_newline = match rope.len_chars().checked_sub(1) {
+            Some(last_char) => rope.char(last_char) == '\n',
+            None => true, // empty files should get one
+        };
 
         Ok(Self {
-            tab_substitution: std::iter::repeat_n(' ', *SPACES_PER_TAB).collect(),
+            tab_substitution,
             rope: rope.into(),
             endings,
+            saved_endings: endings,
+            encoding,
+            mixed_endings,
+            final_newline,
             saved,
-            tabs_required: *ALWAYS_TAB || syntax.tabs_required(),
+            noticed: None,
+            tabs_required,
             syntax,
             source,
             undo: vec![],
+            undo_start: None,
+            highlights: RefCell::default(),
             undo_finished: true,
             redo: vec![],
             bookmarks: private::Bookmarks::default(),
@@ -719,6 +951,8 @@ impl Buffer {
 
     /// Builds fresh tutorial buffer
     fn tutorial() -> Self {
+        let (tab_substitution, tabs_required) = indentation(&crate::syntax::Tutorial);
+
         Self {
             rope: ropey::Rope::from(include_str!("tutorial.txt").replacen(
                 "VERSION",
@@ -727,26 +961,38 @@ impl Buffer {
             ))
             .into(),
             endings: LineEndings::default(),
+            saved_endings: LineEndings::default(),
+            encoding: Encoding::default(),
+            mixed_endings: false,
+            final_newline: true,
             saved: None,
+            noticed: None,
             syntax: Box::new(crate::syntax::Tutorial),
-            tab_substitution: std::iter::repeat_n(' ', *SPACES_PER_TAB).collect(),
-            tabs_required: *ALWAYS_TAB || crate::syntax::Tutorial.tabs_required(),
+            tab_substitution,
+            tabs_required,
             source: Source::Tutorial,
             undo: vec![],
+            undo_start: None,
+            highlights: RefCell::default(),
             undo_finished: true,
             redo: vec![],
             bookmarks: private::Bookmarks::default(),
         }
     }
 
-    /// Attempts to reload buffer from disk
+    /// Reads buffer's contents from disk, along with when they were saved
+    fn read_source(&self) -> std::io::Result<(Option<SystemTime>, String)> {
+        self.source.read_string(self.endings, self.encoding)
+    }
+
+    /// Replaces buffer's contents with those reloaded from disk
     fn reload(
         &mut self,
+        (saved, reloaded): (Option<SystemTime>, String),
         cursor: &mut usize,
         selection: &mut Option<usize>,
         alt: Vec<AltCursor<'_>>,
-    ) -> std::io::Result<()> {
-        let (saved, reloaded) = self.source.read_string(self.endings)?;
+    ) {
         patch_rope(
             &mut self.rope.get_mut(),
             reloaded,
@@ -757,38 +1003,101 @@ impl Buffer {
         self.rope.save();
         self.saved = saved;
         self.undo_finished = true;
-        Ok(())
     }
 
     /// Attempts to save buffer to disk
     fn save(&mut self) -> std::io::Result<()> {
-        self.saved = {
-            // if the file is non-empty and doesn't end
-            // with a newline, append one
-            // (needs to be in its own block because we
-            //  have to drop RopeHandle before saving)
-            let mut rope = self.rope.get_mut();
-            let len_chars = rope.len_chars();
-            if let Some(last_char) = len_chars.checked_sub(1)
-                && rope.get_char(last_char) != Some('\n')
-            {
-                rope.insert_char(len_chars, '\n');
-            }
-            self.source.save_data(&rope, self.endings)?
-        };
+        // if the file is non-empty and doesn't end
+        // with a newline, append one (unless disabled,
+        // or unless the file didn't originally end with one)
+        if FINAL_NEWLINE.unwrap_or(self.final_newline)
+            && let Some(last_char) = self.rope.len_chars().checked_sub(1)
+            && self.rope.get_char(last_char) != Some('\n')
+        {
+            // the newline reverts along with the latest undo step
+            self.reopen_undo();
+            self.rope.get_mut().insert_char(last_char + 1, '\n');
+        }
+        self.saved = self
+            .source
+            .save_data(&self.rope, self.endings, self.encoding)?;
         self.rope.save();
+        self.saved_endings = self.endings;
         self.undo_finished = true;
         Ok(())
     }
 
+    /// Attempts to save buffer to new source,
+    /// which becomes the buffer's source if successful
+    fn save_as(&mut self, source: Source) -> std::io::Result<()> {
+        let original = std::mem::replace(&mut self.source, source);
+        if let Err(err) = self.save() {
+            self.source = original;
+            return Err(err);
+        }
+
+        // the new name may well imply some other syntax
+        self.syntax = crate::syntax::syntax(&self.source, &self.rope);
+        (self.tab_substitution, self.tabs_required) = indentation(&self.syntax);
+        self.highlights.get_mut().clear();
+        Ok(())
+    }
+
     /// Total lines in buffer
     fn total_lines(&self) -> usize {
         self.rope.len_lines()
     }
 
+    /// Highlighting state entering the given line
+    ///
+    /// States are cached per line as they're calculated
+    /// and only those after the earliest edit are recalculated,
+    /// so scrolling needn't re-highlight everything above the view.
+    fn highlight_state(&self, line: usize) -> HighlightState {
+        use crate::syntax::MultiCommentType;
+
+        let mut states = self.highlights.borrow_mut();
+        if let Some(edited) = self.rope.take_edited() {
+            states.truncate(edited + 1);
+        }
+
+        // formats without multi-line comments carry no state between lines
+        let Some(multicomment) = self.syntax.multicomment() else {
+            return HighlightState::default();
+        };
+
+        if states.is_empty() {
+            states.push(HighlightState::default());
+        }
+
+        let computed = states.len() - 1;
+        for text in self
+            .rope
+            .lines_at(computed)
+            .take(line.saturating_sub(computed))
+        {
+            let text = Cow::from(text);
+            let mut state = states.last().cloned().unwrap_or_default();
+            states.push(match multicomment {
+                MultiCommentType::Bidirectional => {
+                    self.syntax.highlight(&text, &mut state).for_each(drop);
+                    state
+                }
+                MultiCommentType::Unidirectional(f) => f(state, &text),
+            });
+        }
+
+        states.get(line).cloned().unwrap_or_default()
+    }
+
+    /// Display width of a literal tab in this buffer
+    fn tab_width(&self) -> usize {
+        self.tab_substitution.len()
+    }
+
     /// Whether the buffer has been modified
     pub fn modified(&self) -> bool {
-        self.rope.modified()
+        self.rope.modified() || self.endings != self.saved_endings
     }
 
     /// When the buffer was last modified, according to the filesystem
@@ -801,6 +1110,18 @@ impl Buffer {
         self.saved
     }
 
+    /// Whether the file has changed on disk since we last
+    /// loaded or saved it, reporting each change only once
+    fn newly_changed(&mut self) -> bool {
+        match (self.saved, self.source.last_modified()) {
+            (Some(saved), Some(modified)) if modified > saved && self.noticed != Some(modified) => {
+                self.noticed = Some(modified);
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// A simple rope / bookmarks split borrow
     pub fn rope_bookmarks_mut(
         &mut self,
@@ -813,19 +1134,49 @@ impl Buffer {
         !self.bookmarks.is_empty()
     }
 
+    /// Locks down an undo point, as if the cursor had moved
+    pub fn finish_undo(&mut self) {
+        self.undo_finished = true;
+    }
+
     /// Adjust internal state for a move, returns true if undo stack added
     pub fn perform_update(&mut self) -> bool {
         if std::mem::take(&mut self.undo_finished) {
+            self.log_undo();
             self.undo.push(BufferState {
-                rope: self.rope.clone(),
+                edit: Edit::default(),
                 bookmarks: self.bookmarks.clone(),
             });
+            self.undo_start = Some(self.rope.clone());
+            trim_undo(&mut self.undo);
             self.redo.clear();
             true
         } else {
             false
         }
     }
+
+    /// Logs the edit which reverts the latest undo step,
+    /// now that all its changes have been made
+    fn log_undo(&mut self) {
+        if let Some(start) = self.undo_start.take()
+            && let Some(state) = self.undo.last_mut()
+        {
+            state.edit = Edit::between(&self.rope, &start);
+        }
+    }
+
+    /// Reopens the latest undo step so that changes made
+    /// outside of any step, like a newline added on save, revert along with it
+    fn reopen_undo(&mut self) {
+        if self.undo_start.is_none()
+            && let Some(state) = self.undo.last()
+        {
+            let mut start = self.rope.clone();
+            state.edit.clone().apply(&mut start);
+            self.undo_start = Some(start);
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -846,6 +1197,18 @@ impl std::hash::Hash for BufferId {
     }
 }
 
+impl BufferId {
+    /// Buffer's name as shown in lists, marked if modified
+    pub fn list_name(&self) -> String {
+        let buf = self.0.borrow();
+        if buf.modified() {
+            format!("{} *", buf.source().name())
+        } else {
+            buf.source().name().to_string()
+        }
+    }
+}
+
 impl std::fmt::Display for BufferId {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         self.0.borrow().source().name().fmt(f)
@@ -860,11 +1223,95 @@ pub enum FindMode {
 
 impl From<FindMode> for crate::help::Keybinding {
     fn from(mode: FindMode) -> Self {
-        crate::help::keybind::<crate::key::Find>(match mode {
-            FindMode::WholeFile => "Find in File",
-            FindMode::Selected => "Find Selected Text",
-            FindMode::InSelection => "Find in Selected Lines",
-        })
+        crate::help::keybind(
+            crate::key::Action::Find,
+            match mode {
+                FindMode::WholeFile => "Find in File",
+                FindMode::Selected => "Find Selected Text",
+                FindMode::InSelection => "Find in Selected Lines",
+            },
+        )
+    }
+}
+
+/// A change of case to apply to some text
+#[derive(Copy, Clone)]
+pub enum Case {
+    Upper,  // all characters to uppercase
+    Lower,  // all characters to lowercase
+    Toggle, // uppercase to lowercase, and vice-versa
+}
+
+impl Case {
+    fn transform(self, chars: impl Iterator<Item = char>) -> String {
+        match self {
+            Self::Upper => chars.flat_map(char::to_uppercase).collect(),
+            Self::Lower => chars.flat_map(char::to_lowercase).collect(),
+            Self::Toggle => chars.fold(String::new(), |mut s, c| {
+                if c.is_uppercase() {
+                    s.extend(c.to_lowercase());
+                } else {
+                    s.extend(c.to_uppercase());
+                }
+                s
+            }),
+        }
+    }
+}
+
+/// Translates \n, \t, \r and \\ escape sequences
+/// into the characters they represent,
+/// leaving any other escape sequences as-is
+fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('n')) => unescaped.push('\n'),
+            ('\\', Some('t')) => unescaped.push('\t'),
+            ('\\', Some('r')) => unescaped.push('\r'),
+            ('\\', Some('\\')) => unescaped.push('\\'),
+            (c, _) => {
+                unescaped.push(c);
+                continue;
+            }
+        }
+        chars.next();
+    }
+
+    unescaped
+}
+
+/// Returns replacement with its case adjusted to follow original,
+/// if original is all lowercase, all uppercase or capitalized,
+/// otherwise returns replacement as-is
+fn match_case(original: &str, replacement: &str) -> String {
+    let mut letters = original.chars().filter(|c| c.is_alphabetic());
+
+    match letters.next() {
+        None => replacement.to_string(),
+        Some(first) if first.is_lowercase() => {
+            if letters.all(|c| c.is_lowercase()) {
+                replacement.to_lowercase()
+            } else {
+                replacement.to_string()
+            }
+        }
+        Some(_) => {
+            let rest = letters.collect::<Vec<_>>();
+            if !rest.is_empty() && rest.iter().all(|c| c.is_uppercase()) {
+                replacement.to_uppercase()
+            } else if rest.iter().all(|c| c.is_lowercase()) {
+                let mut chars = replacement.chars();
+                chars
+                    .next()
+                    .map(|c| c.to_uppercase().chain(chars).collect())
+                    .unwrap_or_default()
+            } else {
+                replacement.to_string()
+            }
+        }
     }
 }
 
@@ -897,6 +1344,13 @@ pub struct BufferContext {
     message: Option<BufferMessage>, // some user-facing message
     undo: Vec<BufferContextState>,  // the cursor undo stack
     redo: Vec<BufferContextState>,  // the cursor redo stack
+    overwrite: bool,                // whether typed text replaces existing text
+    unhighlighted: bool,            // whether syntax highlighting is disabled
+    viewport_line: Cell<usize>,     // first line of viewport, if not centered
+    line_numbers: LineNumbers,      // how to display the line number gutter
+    whitespace: bool,               // whether to display whitespace glyphs
+    wrapped: bool,                  // whether to soft-wrap long lines
+    wrap_width: Cell<usize>,        // width long lines were last soft-wrapped to
 }
 
 // moving the cursor vertically should preserve the cursor column
@@ -919,10 +1373,25 @@ impl BufferContext {
         Buffer::open(source).map(|b| b.into())
     }
 
-    pub fn reload(&mut self, alt: Vec<AltCursor<'_>>) -> std::io::Result<()> {
+    pub fn reload(&mut self, mut alt: Vec<AltCursor<'_>>) -> std::io::Result<()> {
+        let reloaded = self.buffer.borrow().read_source()?;
+
+        // the reload gets an undo step of its own
+        // rather than reverting along with the latest edit
+        self.buffer.borrow_mut().finish_undo();
         self.buffer
-            .borrow_mut()
-            .reload(&mut self.cursor, &mut self.selection, alt)
+            .borrow_update(
+                MainCursor {
+                    cursor: self.cursor,
+                    cursor_column: self.cursor_column,
+                    selection: self.selection,
+                    undo: &mut self.undo,
+                    redo: &mut self.redo,
+                },
+                &mut alt,
+            )
+            .reload(reloaded, &mut self.cursor, &mut self.selection, alt);
+        Ok(())
     }
 
     // Ok(Ok(()))    - buffer not modified, reload successful
@@ -939,32 +1408,130 @@ impl BufferContext {
         }
     }
 
-    pub fn save(&mut self) -> std::io::Result<()> {
+    /// Readies buffer's contents for saving by trimming
+    /// trailing whitespace from its lines, if enabled,
+    /// and running them through its syntax's formatter, if configured
+    ///
+    /// Should the formatter fail, the buffer is still saved
+    /// and the formatter's complaint is shown.
+    fn tidy(&mut self, mut alt: Vec<AltCursor<'_>>) {
+        let tidied = {
+            let buf = self.buffer.borrow();
+            let text = buf.rope.to_string();
+            let trimmed = match *TRIM_TRAILING {
+                true => trim_trailing_whitespace(&text),
+                false => text.clone(),
+            };
+            let tidied = match CONFIG.formatter(&buf.syntax.to_string()) {
+                Some(command) => match format_with(command, &trimmed) {
+                    Ok(formatted) => formatted,
+                    Err(err) => {
+                        drop(buf);
+                        self.set_error(err);
+                        trimmed
+                    }
+                },
+                None => trimmed,
+            };
+            if tidied == text {
+                return;
+            }
+            tidied
+        };
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+        // diffing keeps the cursor on its line wherever
+        // that line survives tidying unchanged
+        patch_rope(
+            &mut rope,
+            tidied,
+            &mut self.cursor,
+            &mut self.selection,
+            Secondary::new(alt, bookmarks),
+        );
+        self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
+    }
+
+    /// Whether the buffer has no file to save to yet
+    pub fn needs_file(&self) -> bool {
+        self.buffer.borrow().source().needs_file()
+    }
+
+    pub fn save(&mut self, alt: Vec<AltCursor<'_>>) -> std::io::Result<()> {
+        self.tidy(alt);
         self.buffer.borrow_mut().save().inspect_err(|err| {
             self.message = Some(BufferMessage::Error(err.to_string().into()));
         })
     }
 
+    /// Whether the file has changed on disk since we last
+    /// loaded or saved it, and that change hasn't been reported yet
+    pub fn newly_changed(&mut self) -> bool {
+        self.buffer.borrow_mut().newly_changed()
+    }
+
+    pub fn save_as(&mut self, source: Source, alt: Vec<AltCursor<'_>>) -> std::io::Result<()> {
+        self.tidy(alt);
+        self.buffer.borrow_mut().save_as(source).inspect_err(|err| {
+            self.message = Some(BufferMessage::Error(err.to_string().into()));
+        })
+    }
+
     // Ok(Ok(()))    - buffer not modified, save successful
     // Ok(Err(err))  - buffer not modified, save to disk failed
     // Err(Modified) - buffer modified on disk since last save
-    pub fn verified_save(&mut self) -> Result<std::io::Result<()>, Modified> {
-        let mut buf = self.buffer.borrow_mut();
+    pub fn verified_save(
+        &mut self,
+        alt: Vec<AltCursor<'_>>,
+    ) -> Result<std::io::Result<()>, Modified> {
+        let buf = self.buffer.borrow();
         if let Some(saved) = buf.last_saved()
             && let Some(modified) = buf.last_modified()
             && modified > saved
         {
             Err(Modified)
         } else {
-            Ok(buf.save().inspect_err(|err| {
-                self.message = Some(BufferMessage::Error(err.to_string().into()));
-            }))
+            drop(buf);
+            Ok(self.save(alt))
         }
     }
 
+    /// Moves cursor to position, up to the end of the buffer
     pub fn set_cursor(&mut self, cursor: usize) {
-        self.cursor = cursor;
-        self.cursor_column = cursor_column(&self.buffer.borrow_move().rope, self.cursor);
+        let buf = self.buffer.borrow_move();
+        self.cursor = cursor.min(buf.rope.len_chars());
+        self.cursor_column = cursor_column(&buf.rope, self.cursor, buf.tab_width());
+    }
+
+    /// Moves cursor to position, up to the end of the buffer,
+    /// and drops any selection
+    pub fn jump_to(&mut self, cursor: usize) {
+        self.selection = None;
+        self.set_cursor(cursor);
+    }
+
+    /// Cursor's absolute position in buffer, in characters
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// The buffer's file on the local filesystem, if any
+    pub fn local_path(&self) -> Option<PathBuf> {
+        match self.buffer.borrow().source() {
+            Source::Local(path) => Some(path.clone()),
+            _ => None,
+        }
     }
 
     /// Returns cursor position in rope as (row, col), if possible
@@ -975,7 +1542,8 @@ impl BufferContext {
     fn cursor_position(&self) -> Option<(usize, usize)> {
         use unicode_width::UnicodeWidthChar;
 
-        let rope = &self.buffer.borrow().rope;
+        let buffer = self.buffer.borrow();
+        let rope = &buffer.rope;
         let line = rope.try_char_to_line(self.cursor).ok()?;
         let line_start = rope.try_line_to_char(line).ok()?;
 
@@ -984,18 +1552,117 @@ impl BufferContext {
             rope.chars_at(line_start)
                 .take(self.cursor.checked_sub(line_start)?)
                 .map(|c| match c {
-                    '\t' => *SPACES_PER_TAB,
+                    '\t' => buffer.tab_width(),
                     c => c.width().unwrap_or(0),
                 })
                 .sum(),
         ))
     }
 
+    /// Given the viewport's height in lines,
+    /// returns the first line in the viewport
+    /// and the number of blank lines above it
+    ///
+    /// By default, the cursor is kept centered in the viewport.
+    /// If VLE_SCROLLOFF is set, the viewport only scrolls when
+    /// the cursor gets within that many lines of its edges.
+    fn viewport(&self, viewport_height: usize) -> (usize, usize) {
+        let Ok(line) = self.buffer.borrow().rope.try_char_to_line(self.cursor) else {
+            return (0, 0);
+        };
+
+        match *SCROLLOFF {
+            None => match line.checked_sub(viewport_height / 2) {
+                Some(start) => (start, 0),
+                None => (0, viewport_height / 2 - line),
+            },
+            Some(scrolloff) => {
+                let scrolloff = scrolloff.min(viewport_height.saturating_sub(1) / 2);
+                let top = self.viewport_line.get();
+                let top = if line < top + scrolloff {
+                    line.saturating_sub(scrolloff)
+                } else if line + scrolloff >= top + viewport_height {
+                    (line + scrolloff + 1).saturating_sub(viewport_height)
+                } else {
+                    top
+                };
+                self.viewport_line.set(top);
+                (top, 0)
+            }
+        }
+    }
+
+    /// Width of the line number gutter, including its trailing space,
+    /// or 0 if line numbers are not being displayed
+    fn gutter_width(&self, viewport_height: usize) -> u16 {
+        if matches!(self.line_numbers, LineNumbers::Off) {
+            return 0;
+        }
+
+        let (viewport_line, top_margin) = self.viewport(viewport_height);
+        let last_line = (viewport_line + viewport_height.saturating_sub(top_margin))
+            .min(self.buffer.borrow().rope.len_lines());
+
+        last_line.max(1).ilog10() as u16 + 2
+    }
+
+    /// Width soft-wrapped rows are fit to, given the viewport's size
+    fn wrap_width(&self, viewport_width: usize, viewport_height: usize) -> usize {
+        viewport_width
+            .saturating_sub(usize::from(self.gutter_width(viewport_height)))
+            .saturating_sub(BufferWidget::RIGHT_MARGIN.into())
+    }
+
+    /// Given the viewport's height and wrap width,
+    /// returns soft-wrapped rows starting from the viewport's first line,
+    /// the number of those rows scrolled off the top of the viewport,
+    /// and the number of blank lines above the remaining rows
+    ///
+    /// The cursor's row stays where it would be if unwrapped,
+    /// with rows above it scrolled off as necessary.
+    fn wrapped_rows(
+        &self,
+        viewport_height: usize,
+        width: usize,
+    ) -> (Vec<WrappedRow>, usize, usize) {
+        let (viewport_line, top_margin) = self.viewport(viewport_height);
+        let Some((cursor_line, cursor_col)) = self.cursor_position() else {
+            return (vec![], 0, top_margin);
+        };
+        let buffer = self.buffer.borrow();
+
+        let mut rows = vec![];
+        let mut cursor_row = 0;
+        for (line, text) in (viewport_line..).zip(buffer.rope.lines_at(viewport_line)) {
+            for start in line_wrap_points(text, buffer.tab_width(), width) {
+                if line == cursor_line && start <= cursor_col {
+                    cursor_row = rows.len();
+                }
+                rows.push(WrappedRow { line, start });
+            }
+
+            if line + 1 >= viewport_line + viewport_height {
+                break;
+            }
+        }
+
+        // how many more rows are above the cursor than would be unwrapped
+        let excess = cursor_row.saturating_sub(cursor_line.saturating_sub(viewport_line));
+        let top_margin_removed = excess.min(top_margin);
+        (
+            rows,
+            excess - top_margin_removed,
+            top_margin - top_margin_removed,
+        )
+    }
+
     /// This is the inverse of cursor_position
     ///
     /// Given some mouse-selected position, attempt to place focus
     /// in the document where the cursor should be.
-    fn set_cursor_focus(&mut self, area: Rect, position: Position) {
+    /// If selecting, text from the previous cursor position
+    /// (or existing selection anchor) is selected.
+    fn set_cursor_focus(&mut self, area: Rect, position: Position, selecting: bool) {
         use ratatui::{
             layout::{
                 Constraint::{Length, Min},
@@ -1012,37 +1679,52 @@ impl BufferContext {
             return;
         }
 
-        let buffer = self.buffer.borrow();
-        let rope = &buffer.rope;
-        let row = position.y.saturating_sub(text_area.y);
-        let col = position.x.saturating_sub(text_area.x);
+        let (viewport_line, top_margin) = self.viewport(text_area.height.into());
 
-        let current_line = rope.try_char_to_line(self.cursor).ok();
-        let viewport_height: usize = text_area.height.into();
+        let [_, text_area] =
+            Layout::horizontal([Length(self.gutter_width(text_area.height.into())), Min(0)])
+                .areas(text_area);
 
-        let (viewport_line, top_margin): (usize, usize) = current_line
-            .map(|line| match line.checked_sub(viewport_height / 2) {
-                Some(start) => (start, 0),
-                None => (0, viewport_height / 2 - line),
-            })
-            .unwrap_or_default();
+        let row = usize::from(position.y.saturating_sub(text_area.y));
+        let col = usize::from(position.x.saturating_sub(text_area.x));
 
-        let line = viewport_line + usize::from(row).saturating_sub(top_margin);
-
-        let starting_col = self
-            .cursor_position()
-            .map(|(_, col)| {
-                col.saturating_sub(
-                    text_area
-                        .width
-                        .saturating_sub(BufferWidget::RIGHT_MARGIN)
-                        .into(),
-                ) as u16
-            })
-            .unwrap_or(0);
+        let (line, mut desired_col) = match self.wrapped {
+            false => (
+                viewport_line + row.saturating_sub(top_margin),
+                self.cursor_position()
+                    .map(|(_, col)| {
+                        col.saturating_sub(
+                            text_area
+                                .width
+                                .saturating_sub(BufferWidget::RIGHT_MARGIN)
+                                .into(),
+                        )
+                    })
+                    .unwrap_or(0)
+                    + col,
+            ),
+            true => {
+                let (rows, skipped, top_margin) = self.wrapped_rows(
+                    text_area.height.into(),
+                    usize::from(text_area.width.saturating_sub(BufferWidget::RIGHT_MARGIN)),
+                );
+                match rows
+                    .get(skipped + row.saturating_sub(top_margin))
+                    .or(rows.last())
+                {
+                    Some(WrappedRow { line, start: 0 }) => (*line, col),
+                    Some(WrappedRow { line, start }) => {
+                        (*line, start + col.saturating_sub(WRAP_INDENT))
+                    }
+                    None => (viewport_line, col),
+                }
+            }
+        };
+
+        let buffer = self.buffer.borrow();
+        let rope = &buffer.rope;
 
         // the column we're aiming for, in onscreen characters
-        let mut desired_col: usize = (starting_col + col).into();
         self.cursor_column = desired_col;
 
         let col_chars = rope
@@ -1053,7 +1735,7 @@ impl BufferContext {
                         use unicode_width::UnicodeWidthChar;
 
                         desired_col = match desired_col.checked_sub(match c {
-                            '\t' => *SPACES_PER_TAB,
+                            '\t' => buffer.tab_width(),
                             c => c.width().unwrap_or(0),
                         }) {
                             Some(col) => col,
@@ -1066,41 +1748,127 @@ impl BufferContext {
             .unwrap_or(0);
 
         // ensure cursor doesn't walk past desired line
-        self.cursor = (rope.try_line_to_char(line).unwrap_or(rope.len_chars()) + col_chars).min(
+        let cursor = (rope.try_line_to_char(line).unwrap_or(rope.len_chars()) + col_chars).min(
             rope.try_line_to_char(line + 1)
                 .unwrap_or(rope.len_chars())
                 .saturating_sub(1),
         );
 
-        self.selection = None;
+        self.selection = match selecting {
+            true => self.selection.or(Some(self.cursor)),
+            false => None,
+        };
+        self.cursor = cursor;
+    }
+
+    /// Moves the cursor by onscreen rows of soft-wrapped lines,
+    /// staying in the same onscreen column where possible
+    fn cursor_rows(&mut self, rows: usize, up: bool, selecting: bool) {
+        let width = self.wrap_width.get();
+        let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
+        let rope = &buf.rope;
+        let row_starts = |line| line_wrap_points(rope.line(line), tab_width, width);
+        // rows after a line's first are indented onscreen
+        let indent = |row| if row > 0 { WRAP_INDENT } else { 0 };
+
+        let Ok(mut line) = rope.try_char_to_line(self.cursor) else {
+            return;
+        };
+        let mut starts = row_starts(line);
+        let col = cursor_column(rope, self.cursor, tab_width);
+        let mut row = starts.iter().rposition(|start| *start <= col).unwrap_or(0);
+        let onscreen_column = (self.cursor_column + indent(row)).saturating_sub(starts[row]);
+
+        for _ in 0..rows {
+            if up {
+                match row.checked_sub(1) {
+                    Some(prev) => row = prev,
+                    None if line > 0 => {
+                        line -= 1;
+                        starts = row_starts(line);
+                        row = starts.len() - 1;
+                    }
+                    None => break,
+                }
+            } else if row + 1 < starts.len() {
+                row += 1;
+            } else if line + 1 < rope.len_lines() {
+                line += 1;
+                starts = row_starts(line);
+                row = 0;
+            } else {
+                break;
+            }
+        }
+
+        if let Some((line_start, line_end)) = line_char_range(rope, line) {
+            // the desired column may fall within the row's indent,
+            // but the cursor itself shouldn't walk off the row
+            let column = (starts[row] + onscreen_column).saturating_sub(indent(row));
+            let row_end = starts.get(row + 1).map(|next| next - 1);
+            update_selection(&mut self.selection, self.cursor, selecting);
+            self.cursor = apply_cursor_column(
+                rope,
+                row_end
+                    .map(|end| column.min(end))
+                    .unwrap_or(column)
+                    .max(starts[row]),
+                line_start,
+                line_end,
+                tab_width,
+            );
+            self.cursor_column = column;
+        }
     }
 
     pub fn cursor_up(&mut self, lines: usize, selecting: bool) {
+        if self.wrapped && self.wrap_width.get() > 0 {
+            return self.cursor_rows(lines, true, selecting);
+        }
+
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         if let Ok(current_line) = buf.rope.try_char_to_line(self.cursor) {
             let previous_line = current_line.saturating_sub(lines);
             if let Some((prev_start, prev_end)) = line_char_range(&buf.rope, previous_line) {
                 update_selection(&mut self.selection, self.cursor, selecting);
-                self.cursor =
-                    apply_cursor_column(&buf.rope, self.cursor_column, prev_start, prev_end);
+                self.cursor = apply_cursor_column(
+                    &buf.rope,
+                    self.cursor_column,
+                    prev_start,
+                    prev_end,
+                    tab_width,
+                );
             }
         }
     }
 
     pub fn cursor_down(&mut self, lines: usize, selecting: bool) {
+        if self.wrapped && self.wrap_width.get() > 0 {
+            return self.cursor_rows(lines, false, selecting);
+        }
+
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         if let Ok(current_line) = buf.rope.try_char_to_line(self.cursor) {
             let next_line = (current_line + lines).min(buf.rope.len_lines().saturating_sub(1));
             if let Some((next_start, next_end)) = line_char_range(&buf.rope, next_line) {
                 update_selection(&mut self.selection, self.cursor, selecting);
-                self.cursor =
-                    apply_cursor_column(&buf.rope, self.cursor_column, next_start, next_end);
+                self.cursor = apply_cursor_column(
+                    &buf.rope,
+                    self.cursor_column,
+                    next_start,
+                    next_end,
+                    tab_width,
+                );
             }
         }
     }
 
     pub fn cursor_back(&mut self, selecting: bool) {
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         update_selection(&mut self.selection, self.cursor, selecting);
         self.cursor = self.cursor.saturating_sub(
             buf.rope
@@ -1110,11 +1878,12 @@ impl BufferContext {
                 .count()
                 + 1,
         );
-        self.cursor_column = cursor_column(&buf.rope, self.cursor);
+        self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
     }
 
     pub fn cursor_forward(&mut self, selecting: bool) {
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         update_selection(&mut self.selection, self.cursor, selecting);
         if self.cursor < buf.rope.len_chars() {
             self.cursor = self.cursor
@@ -1125,7 +1894,23 @@ impl BufferContext {
                     .count()
                 + 1;
         }
-        self.cursor_column = cursor_column(&buf.rope, self.cursor);
+        self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
+    }
+
+    pub fn cursor_prev_word(&mut self, selecting: bool) {
+        let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
+        update_selection(&mut self.selection, self.cursor, selecting);
+        self.cursor = prev_word_boundary(&buf.rope, self.cursor);
+        self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
+    }
+
+    pub fn cursor_next_word(&mut self, selecting: bool) {
+        let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
+        update_selection(&mut self.selection, self.cursor, selecting);
+        self.cursor = next_word_boundary(&buf.rope, self.cursor);
+        self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
     }
 
     pub fn cursor_home(&mut self, selecting: bool) {
@@ -1151,7 +1936,7 @@ impl BufferContext {
                     while let Some(c) = iter.next_if(|c| *c == indent_char) {
                         indent += 1;
                         cursor_column += match c {
-                            '\t' => *SPACES_PER_TAB,
+                            '\t' => buf.tab_width(),
                             c => c.width().unwrap_or(1),
                         };
                     }
@@ -1174,12 +1959,13 @@ impl BufferContext {
 
     pub fn cursor_end(&mut self, selecting: bool) {
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         if let Ok(current_line) = buf.rope.try_char_to_line(self.cursor)
             && let Some((_, end)) = line_char_range(&buf.rope, current_line)
         {
             update_selection(&mut self.selection, self.cursor, selecting);
             self.cursor = end;
-            self.cursor_column = cursor_column(&buf.rope, self.cursor);
+            self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
         }
     }
 
@@ -1203,6 +1989,7 @@ impl BufferContext {
 
     pub fn select_line_and_column(&mut self, line: usize, column: usize) {
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         if let Ok(line_start) = buf.rope.try_line_to_char(line)
             && let Ok(next_line_start) = buf.rope.try_line_to_char(line + 1)
         {
@@ -1213,7 +2000,7 @@ impl BufferContext {
                     .chars_at(start)
                     .take_while(|c| is_grapheme_part(*c))
                     .count();
-            self.cursor_column = cursor_column(&buf.rope, self.cursor);
+            self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
             self.selection = None;
         } else {
             self.message = Some(BufferMessage::Error("invalid line".into()));
@@ -1233,6 +2020,7 @@ impl BufferContext {
             },
             &mut alt,
         );
+        let tab_width = buf.tab_width();
         let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
 
         match &mut self.selection {
@@ -1245,7 +2033,7 @@ impl BufferContext {
                         &mut Secondary::new(alt, bookmarks),
                         ['(', ')'],
                     );
-                    self.cursor_column = cursor_column(&rope, self.cursor);
+                    self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                 }
                 '[' => {
                     perform_surround(
@@ -1255,7 +2043,7 @@ impl BufferContext {
                         &mut Secondary::new(alt, bookmarks),
                         ['[', ']'],
                     );
-                    self.cursor_column = cursor_column(&rope, self.cursor);
+                    self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                 }
                 '{' => {
                     perform_surround(
@@ -1265,7 +2053,7 @@ impl BufferContext {
                         &mut Secondary::new(alt, bookmarks),
                         ['{', '}'],
                     );
-                    self.cursor_column = cursor_column(&rope, self.cursor);
+                    self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                 }
                 '<' => {
                     perform_surround(
@@ -1275,7 +2063,7 @@ impl BufferContext {
                         &mut Secondary::new(alt, bookmarks),
                         ['<', '>'],
                     );
-                    self.cursor_column = cursor_column(&rope, self.cursor);
+                    self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                 }
                 '\"' => {
                     perform_surround(
@@ -1285,7 +2073,7 @@ impl BufferContext {
                         &mut Secondary::new(alt, bookmarks),
                         ['\"', '\"'],
                     );
-                    self.cursor_column = cursor_column(&rope, self.cursor);
+                    self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                 }
                 '\'' => {
                     perform_surround(
@@ -1295,7 +2083,7 @@ impl BufferContext {
                         &mut Secondary::new(alt, bookmarks),
                         ['\'', '\''],
                     );
-                    self.cursor_column = cursor_column(&rope, self.cursor);
+                    self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                 }
                 _ => {
                     let mut alt = Secondary::ge(alt, bookmarks, self.cursor.min(*selection));
@@ -1303,6 +2091,7 @@ impl BufferContext {
                         &mut rope,
                         &mut self.cursor,
                         &mut self.cursor_column,
+                        tab_width,
                         *selection,
                         &mut alt,
                     );
@@ -1312,6 +2101,21 @@ impl BufferContext {
                     self.cursor_column += c.width().unwrap_or(1);
                 }
             },
+            None if self.overwrite => {
+                // replacing a character leaves the positions after it as-is
+                let mut alt = Secondary::ge(alt, bookmarks, self.cursor);
+                match rope.get_char(self.cursor) {
+                    Some(replaced) if replaced != '\n' => {
+                        rope.remove(self.cursor..self.cursor + 1);
+                    }
+                    _ => {
+                        alt += 1;
+                    }
+                }
+                rope.insert_char(self.cursor, c);
+                self.cursor += 1;
+                self.cursor_column += c.width().unwrap_or(1);
+            }
             None => {
                 insert_char_or_pair(
                     &mut rope,
@@ -1323,6 +2127,13 @@ impl BufferContext {
                 self.cursor_column += c.width().unwrap_or(1);
             }
         }
+
+        // typing a non-word character ends the current undo group
+        // so that undo removes typed text a word at a time
+        if !is_word(c) {
+            drop(rope);
+            buf.finish_undo();
+        }
     }
 
     pub fn paste(&mut self, mut alt: Vec<AltCursor<'_>>, cut_buffer: &mut Option<EditorCutBuffer>) {
@@ -1340,6 +2151,7 @@ impl BufferContext {
                     },
                     &mut alt,
                 );
+                let tab_width = buf.tab_width();
                 let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
                 let mut alt = Secondary::ge(alt, bookmarks, self.cursor);
 
@@ -1350,7 +2162,7 @@ impl BufferContext {
                             let old_cursor = self.cursor;
                             self.cursor += alt.inc(pasted.chars_len);
                             alt.add_bookmarks(pasted.bookmarks.iter().map(|b| old_cursor + b));
-                            self.cursor_column = cursor_column(&rope, self.cursor);
+                            self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                         }
                     }
                     None => { /* nothing in cut buffer, so nothing to do */ }
@@ -1368,6 +2180,7 @@ impl BufferContext {
                         },
                         &mut alt,
                     );
+                    let tab_width = buf.tab_width();
                     let (selection_start, selection_end) = reorder(self.cursor, *selection);
                     let cut_range = selection_start..selection_end;
                     let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
@@ -1401,7 +2214,7 @@ impl BufferContext {
                             alt.add_bookmarks(pasted.bookmarks.iter().map(|b| old_cursor + b));
                             self.selection = Some(selection_start);
                             self.cursor = selection_start + pasted.chars_len;
-                            self.cursor_column = cursor_column(&rope, self.cursor);
+                            self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                         }
 
                         // display indicator
@@ -1425,7 +2238,12 @@ impl BufferContext {
             },
             &mut alt,
         );
+        let tab_width = buf.tab_width();
         let indent_char = if buf.tabs_required { '\t' } else { ' ' };
+        let indent_level = match buf.tabs_required {
+            false => buf.tab_substitution.clone(),
+            true => "\t".to_string(),
+        };
         let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
 
         let mut alt = match self.selection.take() {
@@ -1436,6 +2254,7 @@ impl BufferContext {
                     &mut rope,
                     &mut self.cursor,
                     &mut self.cursor_column,
+                    tab_width,
                     selection,
                     &mut secondary,
                 );
@@ -1463,14 +2282,40 @@ impl BufferContext {
             rope.insert_char(self.cursor - indent, '\n');
             self.cursor += alt.inc(1);
         } else {
+            // if the line ends with an opening pair,
+            // the new line gets an additional level of indentation
+            let closer = line_start_to_cursor(&rope, self.cursor)
+                .and_then(|iter| iter.filter(|c| !c.is_whitespace()).last())
+                .and_then(|c| match c {
+                    '{' => Some('}'),
+                    '(' => Some(')'),
+                    '[' => Some(']'),
+                    _ => None,
+                });
+
             rope.insert_char(self.cursor, '\n');
             self.cursor += alt.inc(1);
-            self.cursor_column = 0;
             for _ in 0..indent {
                 rope.insert_char(self.cursor, indent_char);
                 self.cursor += alt.inc(1);
-                self.cursor_column += 1;
             }
+
+            if let Some(closer) = closer {
+                rope.insert(self.cursor, &indent_level);
+                self.cursor += alt.inc(indent_level.chars().count());
+
+                // and if the cursor was just before the closing pair,
+                // it gets moved to its own line at the original indentation
+                if rope.get_char(self.cursor) == Some(closer) {
+                    let dedented = std::iter::once('\n')
+                        .chain(std::iter::repeat_n(indent_char, indent))
+                        .collect::<String>();
+                    rope.insert(self.cursor, &dedented);
+                    alt += dedented.chars().count();
+                }
+            }
+
+            self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
         }
     }
 
@@ -1485,6 +2330,7 @@ impl BufferContext {
             },
             &mut alt,
         );
+        let tab_width = buf.tab_width();
         let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
 
         match self.selection.take() {
@@ -1495,7 +2341,7 @@ impl BufferContext {
                     &mut Secondary::new(alt, bookmarks),
                 ) {
                     self.cursor -= removed;
-                    self.cursor_column = cursor_column(&rope, self.cursor);
+                    self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                 }
             }
             Some(current_selection) => {
@@ -1504,6 +2350,7 @@ impl BufferContext {
                     &mut rope,
                     &mut self.cursor,
                     &mut self.cursor_column,
+                    tab_width,
                     current_selection,
                     &mut alt,
                 );
@@ -1511,6 +2358,400 @@ impl BufferContext {
         }
     }
 
+    /// Removes text from cursor back to start of previous word,
+    /// or the current selection if any
+    pub fn backspace_word(&mut self, mut alt: Vec<AltCursor<'_>>) {
+        if self.selection.is_some() {
+            return self.backspace(alt);
+        }
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+
+        let start = prev_word_boundary(&rope, self.cursor);
+        if start < self.cursor {
+            zap_selection(
+                &mut rope,
+                &mut self.cursor,
+                &mut self.cursor_column,
+                tab_width,
+                start,
+                &mut Secondary::ge(alt, bookmarks, start),
+            );
+        }
+    }
+
+    /// Removes text from cursor forward to end of next word,
+    /// or the current selection if any
+    pub fn delete_word(&mut self, mut alt: Vec<AltCursor<'_>>) {
+        if self.selection.is_some() {
+            return self.delete(alt);
+        }
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+
+        let end = next_word_boundary(&rope, self.cursor);
+        if end > self.cursor {
+            let mut alt = Secondary::ge(alt, bookmarks, self.cursor);
+            zap_selection(
+                &mut rope,
+                &mut self.cursor,
+                &mut self.cursor_column,
+                tab_width,
+                end,
+                &mut alt,
+            );
+        }
+    }
+
+    /// Swaps selected lines with the line above them
+    pub fn move_lines_up(&mut self, mut alt: Vec<AltCursor<'_>>) {
+        let Some((first_line, last_line)) = self.selected_line_span() else {
+            return;
+        };
+        let Some(prev_line) = first_line.checked_sub(1) else {
+            return;
+        };
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+
+        let prev_start = rope.line_to_char(prev_line);
+        let block_start = rope.line_to_char(first_line);
+        let block_end = rope.line_to_char(last_line + 1);
+        let mut alt = Secondary::ge(alt, bookmarks, prev_start);
+        // a selection reaching the end of a buffer ending with a newline
+        // follows the block to the end of its last line,
+        // rather than taking the line now below the block along with it
+        let terminated = rope.char(block_end - 1) == '\n';
+        let moved_end = block_end - (block_start - prev_start) - 1;
+        let remap = swap_line_blocks(&mut rope, prev_start, block_start, block_end);
+        for pos in std::iter::once(&mut self.cursor).chain(&mut self.selection) {
+            match terminated && *pos == block_end {
+                true => *pos = moved_end,
+                false => remap(pos),
+            }
+        }
+        alt.update(remap);
+        self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
+    }
+
+    /// Swaps selected lines with the line below them
+    pub fn move_lines_down(&mut self, mut alt: Vec<AltCursor<'_>>) {
+        let Some((first_line, last_line)) = self.selected_line_span() else {
+            return;
+        };
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+
+        let block_start = rope.line_to_char(first_line);
+        let block_end = rope.line_to_char(last_line + 1);
+        if block_end < rope.len_chars() {
+            let next_end = rope.line_to_char(last_line + 2);
+            let mut alt = Secondary::ge(alt, bookmarks, block_start);
+            let remap = swap_line_blocks(&mut rope, block_start, block_end, next_end);
+            remap(&mut self.cursor);
+            if let Some(selection) = &mut self.selection {
+                remap(selection);
+            }
+            alt.update(remap);
+            self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
+        }
+    }
+
+    /// Comments out selected lines, or current line,
+    /// using the syntax's line comment marker.
+    /// If all lines are already commented, uncomments them.
+    pub fn toggle_comment(&mut self, mut alt: Vec<AltCursor<'_>>) {
+        let Some(marker) = self.buffer.borrow().syntax.line_comment() else {
+            self.set_error("Line Comments Not Supported");
+            return;
+        };
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+
+        // the start of each non-blank line's text
+        // after its indentation, along with that text
+        let lines = selected_lines(&rope, self.cursor, self.selection)
+            .filter_map(|SelectedLine { start, .. }| {
+                let indent = rope
+                    .chars_at(start)
+                    .take_while(|c| *c != '\n' && c.is_whitespace())
+                    .count();
+                let text = rope
+                    .chars_at(start + indent)
+                    .take_while(|c| *c != '\n')
+                    .collect::<String>();
+                (!text.is_empty()).then_some((start + indent, text))
+            })
+            .collect::<Vec<_>>();
+
+        let Some((first, _)) = lines.first() else {
+            return;
+        };
+        let mut alt = Secondary::ge(alt, bookmarks, *first);
+
+        if lines.iter().all(|(_, text)| text.starts_with(marker)) {
+            for (start, text) in lines.iter().rev() {
+                let len =
+                    marker.chars().count() + usize::from(text[marker.len()..].starts_with(' '));
+                let removed = start + len;
+                let remap = |pos: &mut usize| {
+                    if *pos >= removed {
+                        *pos -= len;
+                    } else if *pos > *start {
+                        *pos = *start;
+                    }
+                };
+                rope.remove(*start..removed);
+                remap(&mut self.cursor);
+                if let Some(selection) = &mut self.selection {
+                    remap(selection);
+                }
+                alt.update(remap);
+            }
+        } else {
+            let len = marker.chars().count() + 1;
+            for (start, _) in lines.iter().rev() {
+                let remap = |pos: &mut usize| {
+                    if *pos >= *start {
+                        *pos += len;
+                    }
+                };
+                rope.insert(*start, marker);
+                rope.insert_char(*start + marker.chars().count(), ' ');
+                remap(&mut self.cursor);
+                if let Some(selection) = &mut self.selection {
+                    remap(selection);
+                }
+                alt.update(remap);
+            }
+        }
+
+        self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
+    }
+
+    /// Rewraps the selected lines, or the paragraph around the cursor,
+    /// to fit within the given width, keeping the first line's
+    /// indentation and line comment marker as a prefix on each line
+    pub fn reflow(&mut self, mut alt: Vec<AltCursor<'_>>, width: usize) {
+        use unicode_width::UnicodeWidthStr;
+
+        let marker = self.buffer.borrow().syntax.line_comment();
+
+        let Some((first_line, last_line)) = self.paragraph_span(marker) else {
+            self.set_error("No Paragraph to Reflow");
+            return;
+        };
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+
+        let (Ok(start), Ok(end)) = (
+            rope.try_line_to_char(first_line),
+            rope.try_line_to_char(last_line + 1),
+        ) else {
+            return;
+        };
+        // leave the paragraph's final newline in place
+        let end = match end.checked_sub(1) {
+            Some(newline) if newline >= start && rope.char(newline) == '\n' => newline,
+            _ => end,
+        };
+
+        let text = rope.slice(start..end).to_string();
+
+        let prefix = {
+            let indent = text
+                .find(|c: char| c == '\n' || !c.is_whitespace())
+                .unwrap_or(text.len());
+            match marker.and_then(|m| text[indent..].strip_prefix(m)) {
+                Some(commented) => {
+                    let spaces = commented
+                        .find(|c: char| c == '\n' || !c.is_whitespace())
+                        .unwrap_or(commented.len());
+                    &text[..text.len() - commented.len() + spaces]
+                }
+                None => &text[..indent],
+            }
+        };
+        let strip_marker = marker.filter(|m| prefix.contains(m));
+        let prefix_width = prefix
+            .chars()
+            .map(|c| match c {
+                '\t' => tab_width,
+                c => unicode_width::UnicodeWidthChar::width(c).unwrap_or(1),
+            })
+            .sum::<usize>();
+
+        // words are only broken at whitespace,
+        // so anything like a URL stays intact, even if too wide
+        let mut reflowed = String::new();
+        let mut line_width = None;
+        for word in text
+            .lines()
+            .map(|line| {
+                let line = line.trim_start();
+                strip_marker
+                    .and_then(|m| line.strip_prefix(m))
+                    .unwrap_or(line)
+            })
+            .flat_map(|line| line.split_whitespace())
+        {
+            line_width = Some(match line_width {
+                Some(line_width) if line_width + 1 + word.width() <= width => {
+                    reflowed.push(' ');
+                    reflowed.push_str(word);
+                    line_width + 1 + word.width()
+                }
+                Some(_) => {
+                    reflowed.push('\n');
+                    reflowed.push_str(prefix);
+                    reflowed.push_str(word);
+                    prefix_width + word.width()
+                }
+                None => {
+                    reflowed.push_str(prefix);
+                    reflowed.push_str(word);
+                    prefix_width + word.width()
+                }
+            });
+        }
+
+        if reflowed.is_empty() {
+            return;
+        }
+
+        let reflowed_end = start + reflowed.chars().count();
+        let mut alt = Secondary::ge(alt, bookmarks, start);
+        rope.remove(alt.remove(start..end));
+        rope.insert(start, &reflowed);
+        alt.update(|pos| {
+            if *pos >= end {
+                *pos = *pos - (end - start) + (reflowed_end - start);
+            } else {
+                *pos = start;
+            }
+        });
+
+        self.selection = None;
+        self.cursor = reflowed_end;
+        self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
+    }
+
+    /// Returns first and last line of selection, or the lines
+    /// of the paragraph around the cursor, delimited by blank lines
+    /// (or lines containing nothing but a line comment marker)
+    fn paragraph_span(&self, marker: Option<&str>) -> Option<(usize, usize)> {
+        if self.selection.is_some() {
+            return self.selected_line_span();
+        }
+
+        let rope = &self.buffer.borrow().rope;
+        let is_blank = |line: usize| {
+            let text = rope.line(line).to_string();
+            let text = text.trim();
+            text.is_empty() || marker == Some(text)
+        };
+
+        let line = rope.try_char_to_line(self.cursor).ok()?;
+        if is_blank(line) {
+            return None;
+        }
+
+        Some((
+            (0..line)
+                .rev()
+                .take_while(|l| !is_blank(*l))
+                .last()
+                .unwrap_or(line),
+            (line + 1..rope.len_lines())
+                .take_while(|l| !is_blank(*l))
+                .last()
+                .unwrap_or(line),
+        ))
+    }
+
+    /// Returns first and last line of selection, or current line
+    /// provided that span isn't the empty line at the end of the rope
+    fn selected_line_span(&self) -> Option<(usize, usize)> {
+        let rope = &self.buffer.borrow().rope;
+        let mut lines = selected_lines(rope, self.cursor, self.selection);
+        let first = lines.next()?.start;
+        let last = lines.next_back().map(|l| l.start).unwrap_or(first);
+        (first < rope.len_chars())
+            .then(|| {
+                Some((
+                    rope.try_char_to_line(first).ok()?,
+                    rope.try_char_to_line(last).ok()?,
+                ))
+            })
+            .flatten()
+    }
+
     pub fn delete(&mut self, mut alt: Vec<AltCursor<'_>>) {
         let buf = &mut self.buffer.borrow_update(
             MainCursor {
@@ -1522,6 +2763,7 @@ impl BufferContext {
             },
             &mut alt,
         );
+        let tab_width = buf.tab_width();
         let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
 
         match &mut self.selection {
@@ -1553,13 +2795,14 @@ impl BufferContext {
                     |_| true,
                 ) {
                     Ok(()) => {
-                        self.cursor_column = cursor_column(&rope, self.cursor);
+                        self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                     }
                     Err(()) => {
                         zap_selection(
                             &mut rope,
                             &mut self.cursor,
                             &mut self.cursor_column,
+                            tab_width,
                             *selection,
                             &mut secondary,
                         );
@@ -1570,6 +2813,12 @@ impl BufferContext {
         }
     }
 
+    /// Whether any text is selected
+    pub fn has_selection(&self) -> bool {
+        self.selection
+            .is_some_and(|selection| selection != self.cursor)
+    }
+
     /// Returns selection without clearing it, if any
     pub fn selection_range(&self) -> Option<SelectionType> {
         let (selection_start, selection_end) = reorder(self.cursor, self.selection?);
@@ -1614,12 +2863,14 @@ impl BufferContext {
             MainCursor {
                 cursor: self.cursor,
                 cursor_column: self.cursor_column,
-                selection: self.selection,
+                // so that undoing the cut selects the text again
+                selection: Some(selection),
                 undo: &mut self.undo,
                 redo: &mut self.redo,
             },
             &mut alt,
         );
+        let tab_width = buf.tab_width();
         let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
         let mut alt = Secondary::ge(alt, bookmarks, selection_start);
 
@@ -1634,7 +2885,7 @@ impl BufferContext {
             .inspect(|_| {
                 rope.remove(selection_start..selection_end);
                 self.cursor = selection_start;
-                self.cursor_column = cursor_column(&rope, self.cursor);
+                self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                 alt.update(|pos| {
                     if (selection_start..selection_end).contains(pos) {
                         *pos = selection_start;
@@ -1645,6 +2896,25 @@ impl BufferContext {
             })
     }
 
+    /// Cuts from cursor to end of line, or joins the next line
+    /// if the cursor is already at the end of the line.
+    /// Cuts the current selection instead, if any.
+    pub fn kill_line(&mut self, alt: Vec<AltCursor<'_>>) -> Option<CutBuffer> {
+        if self.selection.is_none() {
+            let rope = &self.buffer.borrow().rope;
+            self.selection = match rope
+                .get_chars_at(self.cursor)?
+                .take_while(|c| *c != '\n')
+                .count()
+            {
+                0 if self.cursor < rope.len_chars() => Some(self.cursor + 1),
+                0 => None,
+                line => Some(self.cursor + line),
+            };
+        }
+        self.take_selection(alt)
+    }
+
     /// Returns Ok((current_idx, matches)) on success
     /// Returns Err(term) if no matches found
     pub fn all_matches<S: SearchTerm>(
@@ -1680,17 +2950,37 @@ impl BufferContext {
             None => self.cursor,
         };
 
-        let (idx, next_match) = matches
+        // with no matches after the cursor, wrap around to the first
+        let (idx, next_match) = match matches
             .iter()
             .enumerate()
             .find(|(_, m)| m.range.start >= start)
-            .or_else(|| matches.first().map(|m| (0, m)))
-            .ok_or(term)?;
+        {
+            Some(found) => found,
+            None => {
+                let first = matches.first().ok_or(term)?;
+                self.message = Some(BufferMessage::Notice("Search Wrapped".into()));
+                (0, first)
+            }
+        };
         self.cursor = next_match.range.end;
         self.selection = None;
         Ok((idx, matches))
     }
 
+    /// Counts matches of the term without moving the cursor
+    /// and reports that count as a message
+    pub fn count_matches<S: SearchTerm>(&mut self, range: Option<&SelectionRange>, term: S) {
+        let count = search_area(&self.buffer.borrow().rope, range)
+            .map(|(line, _)| term.match_ranges(&line).count())
+            .sum::<usize>();
+
+        self.set_message(match count {
+            1 => "1 Match".to_string(),
+            count => format!("{} Matches", Thousands(count)),
+        });
+    }
+
     pub fn all_multiline_matches<S: SearchTerm>(
         &mut self,
         range: Option<&SelectionRange>,
@@ -1699,28 +2989,22 @@ impl BufferContext {
         let buf = self.buffer.borrow_move();
         let rope = &buf.rope;
 
-        let (whole_rope, offset) = match range {
-            None => (
-                rope.chunks()
-                    .fold(String::with_capacity(rope.len_bytes()), |mut acc, s| {
-                        acc.push_str(s);
-                        acc
-                    }),
-                0,
-            ),
+        let (area, offset) = match range {
+            None => (rope.slice(..), 0),
             Some(SelectionRange { start, lines }) => (
-                rope.lines_at(*start)
-                    .take(lines.get())
-                    .fold(String::new(), |mut acc, l| {
-                        acc.push_str(&Cow::from(l));
-                        acc
-                    }),
+                rope.slice(
+                    rope.line_to_char(*start)
+                        ..rope
+                            .try_line_to_char(*start + lines.get())
+                            .unwrap_or(rope.len_chars()),
+                ),
                 rope.line_to_byte(*start),
             ),
         };
 
         let matches = term
-            .match_ranges(&whole_rope)
+            .rope_match_ranges(area)
+            .into_iter()
             .map(|m| m + offset)
             .filter_map(|SearchMatch { start, end, groups }| {
                 // convert ranges in bytes (from SearchTerm)
@@ -1741,12 +3025,19 @@ impl BufferContext {
             None => self.cursor,
         };
 
-        let (idx, next_match) = matches
+        // with no matches after the cursor, wrap around to the first
+        let (idx, next_match) = match matches
             .iter()
             .enumerate()
             .find(|(_, m)| m.range.start >= start)
-            .or_else(|| matches.first().map(|m| (0, m)))
-            .ok_or(term)?;
+        {
+            Some(found) => found,
+            None => {
+                let first = matches.first().ok_or(term)?;
+                self.message = Some(BufferMessage::Notice("Search Wrapped".into()));
+                (0, first)
+            }
+        };
         self.cursor = next_match.range.end;
         self.selection = None;
         Ok((idx, matches))
@@ -1755,11 +3046,13 @@ impl BufferContext {
     /// Performs undo on the active BufferContext, returns Ok(()) on success
     pub fn perform_undo_active(&mut self) -> Result<(), ()> {
         let mut buf = self.buffer.borrow_mut();
+        buf.log_undo();
         match (buf.undo.pop(), self.undo.pop()) {
             (Some(mut buf_state), Some(mut ctx_state)) => {
                 use std::ops::DerefMut;
 
-                std::mem::swap(buf.rope.get_mut().deref_mut(), &mut buf_state.rope);
+                buf_state.edit =
+                    std::mem::take(&mut buf_state.edit).apply(buf.rope.get_mut().deref_mut());
                 std::mem::swap(&mut buf.bookmarks, &mut buf_state.bookmarks);
                 buf.redo.push(buf_state);
                 buf.undo_finished = true;
@@ -1770,7 +3063,11 @@ impl BufferContext {
                 self.redo.push(ctx_state);
                 Ok(())
             }
-            _ => {
+            (buf_state, ctx_state) => {
+                // keep the stacks intact, since each edit
+                // depends on those after it having been reverted
+                buf.undo.extend(buf_state);
+                self.undo.extend(ctx_state);
                 self.message = Some(BufferMessage::Notice("nothing left to undo".into()));
                 Err(())
             }
@@ -1790,11 +3087,13 @@ impl BufferContext {
     /// Performs redo on the active BufferContext, returns Ok(()) on success
     pub fn perform_redo_active(&mut self) -> Result<(), ()> {
         let mut buf = self.buffer.borrow_mut();
+        buf.log_undo();
         match (buf.redo.pop(), self.redo.pop()) {
             (Some(mut buf_state), Some(mut ctx_state)) => {
                 use std::ops::DerefMut;
 
-                std::mem::swap(buf.rope.get_mut().deref_mut(), &mut buf_state.rope);
+                buf_state.edit =
+                    std::mem::take(&mut buf_state.edit).apply(buf.rope.get_mut().deref_mut());
                 std::mem::swap(&mut buf.bookmarks, &mut buf_state.bookmarks);
                 buf.undo.push(buf_state);
                 buf.undo_finished = true;
@@ -1806,7 +3105,9 @@ impl BufferContext {
 
                 Ok(())
             }
-            _ => {
+            (buf_state, ctx_state) => {
+                buf.redo.extend(buf_state);
+                self.redo.extend(ctx_state);
                 self.message = Some(BufferMessage::Notice("nothing left to redo".into()));
                 Err(())
             }
@@ -1842,6 +3143,7 @@ impl BufferContext {
                     },
                     &mut alt,
                 );
+                let tab_width = buf.tab_width();
                 let indent = match buf.tabs_required {
                     false => buf.tab_substitution.clone(),
                     true => "\t".to_string(),
@@ -1854,6 +3156,7 @@ impl BufferContext {
                     let mut alt = Secondary::ge(alt, bookmarks, line_start);
                     rope.insert(line_start, &indent);
                     self.cursor += indent.len();
+                    self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                     alt += indent.len();
                 }
                 None
@@ -1921,6 +3224,7 @@ impl BufferContext {
                     },
                     &mut alt,
                 );
+                let tab_width = buf.tab_width();
                 let indent = match buf.tabs_required {
                     false => buf.tab_substitution.clone(),
                     true => "\t".to_string(),
@@ -1944,7 +3248,7 @@ impl BufferContext {
                         self.cursor_column = 0;
                     } else {
                         self.cursor -= to_remove.end - to_remove.start;
-                        self.cursor_column = cursor_column(&rope, self.cursor);
+                        self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
                     }
 
                     alt.update(|pos| {
@@ -2024,6 +3328,55 @@ impl BufferContext {
         lines
     }
 
+    /// Treats the cursor and selection as opposite corners of a block
+    /// and returns a cursor for each line of it, with each cursor
+    /// selecting the block's columns on its line
+    pub fn block_cursors(&mut self) -> Vec<MultiCursor> {
+        let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
+        let rope = &buf.rope;
+
+        let Some(selection) = self.selection.take() else {
+            return vec![];
+        };
+
+        let (cursor_col, selection_col) = (
+            cursor_column(rope, self.cursor, tab_width),
+            cursor_column(rope, selection, tab_width),
+        );
+        let (left, right) = reorder(cursor_col, selection_col);
+        let (start, end) = reorder(self.cursor, selection);
+
+        let blocks = match (rope.try_char_to_line(start), rope.try_char_to_line(end)) {
+            (Ok(start_line), Ok(end_line)) => (start_line..=end_line)
+                .filter_map(|line| {
+                    let start = rope.try_line_to_char(line).ok()?;
+                    let end = match rope.try_line_to_char(line + 1) {
+                        Ok(next) if next > start && rope.char(next - 1) == '\n' => next - 1,
+                        Ok(next) => next,
+                        Err(_) => return None,
+                    };
+                    let block_start = apply_cursor_column(rope, left, start, end, tab_width);
+                    let block_end = apply_cursor_column(rope, right, start, end, tab_width);
+                    Some(MultiCursor {
+                        range: start..end,
+                        cursor: block_end,
+                        selection: (block_start != block_end).then_some(block_start),
+                        groups: vec![],
+                    })
+                })
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        };
+
+        self.cursor = match blocks.last() {
+            Some(MultiCursor { cursor, .. }) => *cursor,
+            None => return vec![],
+        };
+
+        blocks
+    }
+
     pub fn select_inside(&mut self, (start, end): (char, char), stack: Option<(char, char)>) {
         let buf = self.buffer.borrow();
         let (stack_back, stack_forward) = match stack {
@@ -2054,23 +3407,73 @@ impl BufferContext {
         }
     }
 
+    pub fn toggle_overwrite(&mut self) {
+        self.overwrite = !self.overwrite;
+    }
+
+    pub fn toggle_highlighting(&mut self) {
+        self.unhighlighted = !self.unhighlighted;
+    }
+
+    pub fn toggle_whitespace(&mut self) {
+        self.whitespace = !self.whitespace;
+    }
+
+    pub fn toggle_wrap(&mut self) {
+        self.wrapped = !self.wrapped;
+    }
+
+    /// Cycles line endings to use when buffer is next saved
+    pub fn cycle_endings(&mut self) {
+        let mut buf = self.buffer.borrow_mut();
+        buf.endings = buf.endings.next();
+        self.message = Some(BufferMessage::Notice(buf.endings.description().into()));
+    }
+
+    /// Cycles line numbers from off, to absolute, to relative
+    pub fn toggle_line_numbers(&mut self) {
+        self.line_numbers = match self.line_numbers {
+            LineNumbers::Off => LineNumbers::Absolute,
+            LineNumbers::Absolute => LineNumbers::Relative,
+            LineNumbers::Relative => LineNumbers::Off,
+        };
+    }
+
+    /// Overrides the syntax highlighting chosen for the buffer
+    pub fn set_syntax(&mut self, syntax: Box<dyn Highlighter>) {
+        let mut buf = self.buffer.borrow_mut();
+        (buf.tab_substitution, buf.tabs_required) = indentation(&syntax);
+        buf.syntax = syntax;
+        buf.highlights.get_mut().clear();
+    }
+
+    pub fn select_all(&mut self) {
+        let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
+        self.selection = Some(0);
+        self.cursor = buf.rope.len_chars();
+        self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
+    }
+
     pub fn cursor_to_selection_start(&mut self) {
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         if let Some(selection) = &mut self.selection
             && self.cursor > *selection
         {
             std::mem::swap(selection, &mut self.cursor);
-            self.cursor_column = cursor_column(&buf.rope, self.cursor);
+            self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
         }
     }
 
     pub fn cursor_to_selection_end(&mut self) {
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         if let Some(selection) = &mut self.selection
             && self.cursor < *selection
         {
             std::mem::swap(selection, &mut self.cursor);
-            self.cursor_column = cursor_column(&buf.rope, self.cursor);
+            self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
         }
     }
 
@@ -2100,15 +3503,254 @@ impl BufferContext {
     /// Attempts to select inside set, returning Ok if successful
     pub fn try_select_inside(&mut self) -> Result<(), ()> {
         let buf = self.buffer.borrow();
+        let tab_width = buf.tab_width();
         try_select_inside(&buf.rope, &mut self.cursor, &mut self.selection, |_| true).inspect(
             |()| {
-                self.cursor_column = cursor_column(&buf.rope, self.cursor);
+                self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
+            },
+        )
+    }
+
+    /// If there's no selection, selects the word under the cursor
+    ///
+    /// Returns false if there's no selection and no word to select
+    fn select_word_if_unselected(&mut self) -> bool {
+        if self.selection.is_none() {
+            let (word_start, word_end) = {
+                let rope = &self.buffer.borrow().rope;
+                (
+                    self.cursor
+                        - rope
+                            .chars_at(self.cursor)
+                            .reversed()
+                            .take_while(|c| is_word_part(*c))
+                            .count(),
+                    self.cursor
+                        + rope
+                            .chars_at(self.cursor)
+                            .take_while(|c| is_word_part(*c))
+                            .count(),
+                )
+            };
+            if word_start == word_end {
+                return false;
+            }
+            self.selection = Some(word_start);
+            self.cursor = word_end;
+        }
+        true
+    }
+
+    /// Surrounds selection with the given pair,
+    /// or the word under the cursor if there's no selection
+    pub fn surround(&mut self, mut alt: Vec<AltCursor<'_>>, pair: [char; 2]) {
+        if !self.select_word_if_unselected() {
+            self.set_error("No Word to Surround");
+            return;
+        }
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+
+        if let Some(selection) = &mut self.selection {
+            perform_surround(
+                &mut rope,
+                &mut self.cursor,
+                selection,
+                &mut Secondary::new(alt, bookmarks),
+                pair,
+            );
+            self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
+        }
+    }
+
+    /// Changes the case of the selection, or the word under the cursor,
+    /// leaving the transformed text selected
+    pub fn transform_selection(&mut self, alt: Vec<AltCursor<'_>>, case: Case) {
+        if !self.select_word_if_unselected() {
+            self.set_error("No Word to Transform");
+            return;
+        }
+
+        let Some(selection) = self.selection else {
+            return;
+        };
+        let (start, end) = reorder(self.cursor, selection);
+
+        let Some(transformed) = self
+            .buffer
+            .borrow()
+            .rope
+            .get_slice(start..end)
+            .map(|slice| case.transform(slice.chars()))
+        else {
+            return;
+        };
+
+        self.replace_selection(alt, &transformed);
+    }
+
+    /// Pipes the selection through a shell command,
+    /// replacing it with the command's output and leaving that selected,
+    /// unless the command fails
+    pub fn filter_selection(&mut self, alt: Vec<AltCursor<'_>>, command: &str) {
+        let Some((start, end)) = self
+            .selection
+            .map(|selection| reorder(self.cursor, selection))
+            .filter(|(start, end)| start != end)
+        else {
+            self.set_error("No Selection to Filter");
+            return;
+        };
+
+        let Some(input) = self
+            .buffer
+            .borrow()
+            .rope
+            .get_slice(start..end)
+            .map(String::from)
+        else {
+            return;
+        };
+
+        // the command's run to completion before touching the buffer
+        match filter(command, &input) {
+            Ok((output, warning)) => {
+                self.replace_selection(alt, &output);
+                if let Some(warning) = warning {
+                    self.set_message(warning);
+                }
+            }
+            Err(err) => self.set_error(err),
+        }
+    }
+
+    /// Replaces the selection with the given text, leaving it selected
+    fn replace_selection(&mut self, mut alt: Vec<AltCursor<'_>>, text: &str) {
+        let Some(selection) = self.selection else {
+            return;
+        };
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (start, end) = reorder(self.cursor, selection);
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+        let text_len = text.chars().count();
+
+        let mut alt = Secondary::ge(alt, bookmarks, end);
+        rope.remove(start..end);
+        rope.insert(start, text);
+        if text_len >= end - start {
+            alt += text_len - (end - start);
+        } else {
+            alt -= (end - start) - text_len;
+        }
+
+        let new_end = start + text_len;
+        if self.cursor == end {
+            self.cursor = new_end;
+            self.selection = Some(start);
+        } else {
+            self.cursor = start;
+            self.selection = Some(new_end);
+        }
+        self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
+    }
+
+    /// Removes the nearest pair surrounding the cursor or selection,
+    /// leaving the text inside it intact
+    pub fn delete_surround(&mut self, mut alt: Vec<AltCursor<'_>>) {
+        let (start, end) = match self.selection {
+            Some(selection) => reorder(self.cursor, selection),
+            None => (self.cursor, self.cursor),
+        };
+
+        let Some((mut inner_start, mut inner_end)) = ({
+            let rope = &self.buffer.borrow().rope;
+            [
+                ('(', ')', Some((')', '('))),
+                ('[', ']', Some((']', '['))),
+                ('{', '}', Some(('}', '{'))),
+                ('<', '>', Some(('>', '<'))),
+                ('"', '"', None),
+                ('\'', '\'', None),
+            ]
+            .into_iter()
+            .filter_map(|(open, close, stack)| {
+                Some((
+                    select_next_char::<false>(rope, start, open, stack.map(|(back, _)| back))?,
+                    select_next_char::<true>(rope, end, close, stack.map(|(_, forward)| forward))?,
+                ))
+            })
+            .max_by_key(|(inner_start, _)| *inner_start)
+        }) else {
+            self.set_message("No Surrounding Pair Found");
+            return;
+        };
+
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
             },
+            &mut alt,
+        );
+        let tab_width = buf.tab_width();
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+        let mut alt = Secondary::ge(alt, bookmarks, inner_start.saturating_sub(1));
+
+        let (removed_start, removed_end) = (inner_start, inner_end);
+        if delete_surround(
+            &mut rope,
+            &mut inner_start,
+            &mut inner_end,
+            &mut alt,
+            |_| true,
         )
+        .is_ok()
+        {
+            let remap = |pos: &mut usize| {
+                *pos -= if *pos > removed_end {
+                    2
+                } else if *pos >= removed_start {
+                    1
+                } else {
+                    0
+                }
+            };
+            remap(&mut self.cursor);
+            if let Some(selection) = &mut self.selection {
+                remap(selection);
+            }
+            self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
+        }
     }
 
     pub fn select_word_or_lines(&mut self) {
         let buf = &mut self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         let rope = &buf.rope;
 
         match self.selection {
@@ -2133,7 +3775,7 @@ impl BufferContext {
                         if word_start != word_end {
                             self.selection = Some(word_start);
                             self.cursor = word_end;
-                            self.cursor_column = cursor_column(rope, self.cursor);
+                            self.cursor_column = cursor_column(rope, self.cursor, tab_width);
                         }
                     }
                     _ => {
@@ -2145,7 +3787,7 @@ impl BufferContext {
                         {
                             self.selection = Some(start);
                             self.cursor = end;
-                            self.cursor_column = cursor_column(rope, self.cursor);
+                            self.cursor_column = cursor_column(rope, self.cursor, tab_width);
                         }
                     }
                 }
@@ -2268,6 +3910,7 @@ impl BufferContext {
             },
             &mut alt,
         );
+        let tab_width = buf.tab_width();
         let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
         let mut alt = Secondary::ge(alt, bookmarks, offset);
 
@@ -2282,7 +3925,7 @@ impl BufferContext {
         rope.insert(offset, replacement);
         alt += replacement_chars;
         self.cursor = offset + replacement_chars;
-        self.cursor_column = cursor_column(&rope, self.cursor);
+        self.cursor_column = cursor_column(&rope, self.cursor, tab_width);
         self.selection = None;
     }
 
@@ -2463,6 +4106,41 @@ impl BufferContext {
         }
     }
 
+    /// Pastes the cut buffer's primary item over each match,
+    /// adjusting its case to follow the case of the text it replaces
+    pub fn multi_paste_matching_case(
+        &mut self,
+        alt: Vec<AltCursor<'_>>,
+        matches: &mut [MultiCursor],
+        cut: &mut EditorCutBuffer,
+    ) {
+        let Some(pasted) = cut.primary_mut().map(|c| c.as_str().to_string()) else {
+            return;
+        };
+
+        let replacements = {
+            let rope = &self.buffer.borrow().rope;
+            matches
+                .iter()
+                .map(|m| {
+                    let original = m
+                        .selection_range()
+                        .and_then(|r| rope.get_slice(r))
+                        .map(|s| s.to_string())
+                        .unwrap_or_default();
+                    let replacement = match_case(&original, &pasted);
+                    (replacement.chars().count(), replacement)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        self.multi_insert_strings(
+            alt,
+            matches,
+            replacements.iter().map(|(len, s)| (*len, s.as_str())),
+        )
+    }
+
     pub fn multi_insert_strings<'s>(
         &mut self,
         mut alt: Vec<AltCursor<'_>>,
@@ -2496,6 +4174,31 @@ impl BufferContext {
         );
     }
 
+    /// Replaces escape sequences like \n and \t typed into each match
+    /// with the characters they represent
+    pub fn multi_unescape(&mut self, mut alt: Vec<AltCursor<'_>>, matches: &mut [MultiCursor]) {
+        let mut buf = self.buffer.borrow_update(
+            MainCursor {
+                cursor: self.cursor,
+                cursor_column: self.cursor_column,
+                selection: self.selection,
+                undo: &mut self.undo,
+                redo: &mut self.redo,
+            },
+            &mut alt,
+        );
+        let (mut rope, bookmarks) = buf.rope_bookmarks_mut();
+        let mut alt = Secondary::new(alt, bookmarks);
+
+        multicursor_update(
+            matches,
+            |m| m.unescape(&mut rope, &mut self.cursor, &mut alt),
+            |r, removed| {
+                *r -= removed;
+            },
+        );
+    }
+
     pub fn multi_backspace(&mut self, mut alt: Vec<AltCursor<'_>>, matches: &mut [MultiCursor]) {
         let mut buf = self.buffer.borrow_update(
             MainCursor {
@@ -2544,10 +4247,12 @@ impl BufferContext {
 
     pub fn multi_cursor_back(&mut self, matches: &mut [MultiCursor], selecting: bool) {
         matches.iter_mut().for_each(|m| {
+            let buffer = self.buffer.borrow_move();
             m.cursor_back(
                 &mut self.cursor,
                 &mut self.cursor_column,
-                &self.buffer.borrow_move().rope,
+                &buffer.rope,
+                buffer.tab_width(),
                 selecting,
             )
         });
@@ -2555,10 +4260,12 @@ impl BufferContext {
 
     pub fn multi_cursor_forward(&mut self, matches: &mut [MultiCursor], selecting: bool) {
         matches.iter_mut().for_each(|m| {
+            let buffer = self.buffer.borrow_move();
             m.cursor_forward(
                 &mut self.cursor,
                 &mut self.cursor_column,
-                &self.buffer.borrow_move().rope,
+                &buffer.rope,
+                buffer.tab_width(),
                 selecting,
             )
         });
@@ -2566,10 +4273,12 @@ impl BufferContext {
 
     pub fn multi_cursor_home(&mut self, matches: &mut [MultiCursor], selecting: bool) {
         matches.iter_mut().for_each(|m| {
+            let buffer = self.buffer.borrow_move();
             m.cursor_home(
                 &mut self.cursor,
                 &mut self.cursor_column,
-                &self.buffer.borrow_move().rope,
+                &buffer.rope,
+                buffer.tab_width(),
                 selecting,
             )
         });
@@ -2577,10 +4286,12 @@ impl BufferContext {
 
     pub fn multi_cursor_end(&mut self, matches: &mut [MultiCursor], selecting: bool) {
         matches.iter_mut().for_each(|m| {
+            let buffer = self.buffer.borrow_move();
             m.cursor_end(
                 &mut self.cursor,
                 &mut self.cursor_column,
-                &self.buffer.borrow_move().rope,
+                &buffer.rope,
+                buffer.tab_width(),
                 selecting,
             )
         });
@@ -2595,7 +4306,7 @@ impl BufferContext {
         });
         if let Some(c) = matches.get(selected) {
             self.cursor = c.cursor;
-            self.cursor_column = cursor_column(&buffer.rope, self.cursor);
+            self.cursor_column = cursor_column(&buffer.rope, self.cursor, buffer.tab_width());
         }
     }
 
@@ -2767,6 +4478,7 @@ impl BufferContext {
 
     fn goto_bookmark(&mut self, forward: bool) {
         let buf = self.buffer.borrow_move();
+        let tab_width = buf.tab_width();
         let Some(cursor) = (if forward {
             buf.bookmarks.next_after(self.cursor)
         } else {
@@ -2775,7 +4487,7 @@ impl BufferContext {
             return;
         };
         self.cursor = cursor;
-        self.cursor_column = cursor_column(&buf.rope, self.cursor);
+        self.cursor_column = cursor_column(&buf.rope, self.cursor, tab_width);
         self.selection = None;
     }
 
@@ -2856,6 +4568,7 @@ impl MainCursor<'_> {
             cursor_column: self.cursor_column,
             selection: self.selection,
         });
+        trim_undo(self.undo);
         self.redo.clear();
     }
 }
@@ -2875,6 +4588,7 @@ impl AltCursor<'_> {
             cursor_column: self.cursor_column,
             selection: *self.selection,
         });
+        trim_undo(self.undo);
         self.redo.clear();
     }
 }
@@ -3049,6 +4763,39 @@ impl MultiCursor {
         zapped
     }
 
+    /// Replaces escape sequences in our range
+    /// and returns the number of characters removed, if any
+    fn unescape(
+        &mut self,
+        rope: &mut ropey::Rope,
+        cursor: &mut usize,
+        secondary: &mut Secondary,
+    ) -> Result<usize, ()> {
+        let original = rope.get_slice(self.range.clone()).ok_or(())?.to_string();
+        let unescaped = unescape(&original);
+        let removed = original.chars().count() - unescaped.chars().count();
+        if removed == 0 {
+            return Err(());
+        }
+
+        let range = self.range.clone();
+        let remap = |pos: &mut usize| {
+            if *pos >= range.end {
+                *pos -= removed;
+            } else if *pos > range.start {
+                *pos = range.end - removed;
+            }
+        };
+        rope.remove(secondary.remove(range.clone()));
+        rope.insert(range.start, &unescaped);
+        remap(cursor);
+        secondary.update(remap);
+        self.range.end -= removed;
+        self.cursor = self.range.end;
+        self.selection = None;
+        Ok(removed)
+    }
+
     /// Returns number of zapped characters, if any
     #[must_use]
     fn paste_single(
@@ -3240,6 +4987,7 @@ impl MultiCursor {
         cursor: &mut usize,
         cursor_col: &mut usize,
         rope: &ropey::Rope,
+        tab_width: usize,
         selecting: bool,
     ) {
         let to_retreat = rope
@@ -3252,7 +5000,7 @@ impl MultiCursor {
         if self.cursor > self.range.start {
             if self.cursor == *cursor {
                 *cursor = cursor.saturating_sub(to_retreat);
-                *cursor_col = cursor_column(rope, *cursor);
+                *cursor_col = cursor_column(rope, *cursor, tab_width);
             }
             update_selection(&mut self.selection, self.cursor, selecting);
             self.cursor -= to_retreat;
@@ -3266,6 +5014,7 @@ impl MultiCursor {
         cursor: &mut usize,
         cursor_col: &mut usize,
         rope: &ropey::Rope,
+        tab_width: usize,
         selecting: bool,
     ) {
         if self.cursor < self.range.end {
@@ -3277,7 +5026,7 @@ impl MultiCursor {
 
             if self.cursor == *cursor {
                 *cursor += to_advance;
-                *cursor_col = cursor_column(rope, *cursor);
+                *cursor_col = cursor_column(rope, *cursor, tab_width);
             }
             update_selection(&mut self.selection, self.cursor, selecting);
             self.cursor += to_advance;
@@ -3291,11 +5040,12 @@ impl MultiCursor {
         cursor: &mut usize,
         cursor_col: &mut usize,
         rope: &ropey::Rope,
+        tab_width: usize,
         selecting: bool,
     ) {
         if self.cursor == *cursor {
             *cursor = self.range.start;
-            *cursor_col = cursor_column(rope, *cursor);
+            *cursor_col = cursor_column(rope, *cursor, tab_width);
         }
         update_selection(&mut self.selection, self.cursor, selecting);
         self.cursor = self.range.start;
@@ -3306,11 +5056,12 @@ impl MultiCursor {
         cursor: &mut usize,
         cursor_col: &mut usize,
         rope: &ropey::Rope,
+        tab_width: usize,
         selecting: bool,
     ) {
         if self.cursor == *cursor {
             *cursor = self.range.end;
-            *cursor_col = cursor_column(rope, *cursor);
+            *cursor_col = cursor_column(rope, *cursor, tab_width);
         }
         update_selection(&mut self.selection, self.cursor, selecting);
         self.cursor = self.range.end;
@@ -3654,6 +5405,14 @@ pub struct SelectionRange {
 pub trait SearchTerm: std::fmt::Display + Clone {
     /// Returns iterator of match ranges in bytes and any captured groups
     fn match_ranges(&self, s: &str) -> impl Iterator<Item = SearchMatch>;
+
+    /// Returns match ranges in bytes across a whole slice of rope
+    ///
+    /// By default, the slice is collected into a single string to search,
+    /// but terms which can search it piecemeal should do so instead.
+    fn rope_match_ranges(&self, rope: ropey::RopeSlice<'_>) -> Vec<SearchMatch> {
+        self.match_ranges(&String::from(rope)).collect()
+    }
 }
 
 pub struct SearchMatch {
@@ -3699,26 +5458,147 @@ impl SearchTerm for String {
             groups: vec![],
         })
     }
+
+    fn rope_match_ranges(&self, rope: ropey::RopeSlice<'_>) -> Vec<SearchMatch> {
+        if self.is_empty() {
+            return self.match_ranges(&String::from(rope)).collect();
+        }
+
+        // Search each chunk in place, along with a seam joining
+        // the previous chunks' unsearched tail to this chunk's head
+        // for any match which spans both.
+        // Since every match is the same length, one which crosses
+        // out of a chunk can't start after one wholly within it,
+        // so matches are found just as searching the slice all at once.
+        let overlap = self.len() - 1;
+        let mut matches = vec![];
+        let mut tail = String::new(); // where a match crossing into the next chunk may start
+        let mut seam = String::new();
+        let mut offset = 0; // chunk's position in the slice, in bytes
+
+        for chunk in rope.chunks() {
+            let mut resume = 0;
+            if !tail.is_empty() {
+                let mut head = overlap.min(chunk.len());
+                while !chunk.is_char_boundary(head) {
+                    head += 1;
+                }
+                seam.clear();
+                seam.push_str(&tail);
+                seam.push_str(&chunk[..head]);
+                if let Some(m) = self
+                    .match_ranges(&seam)
+                    .next()
+                    .filter(|m| m.start < tail.len() && m.end > tail.len())
+                {
+                    resume = m.end - tail.len();
+                    matches.push(m + (offset - tail.len()));
+                }
+            }
+
+            let mut searched = resume;
+            for m in self.match_ranges(&chunk[resume..]) {
+                searched = resume + m.end;
+                matches.push(m + (offset + resume));
+            }
+
+            if searched > 0 || chunk.len() >= overlap {
+                let mut keep = searched.max(chunk.len().saturating_sub(overlap));
+                while !chunk.is_char_boundary(keep) {
+                    keep -= 1;
+                }
+                tail.clear();
+                tail.push_str(&chunk[keep..]);
+            } else {
+                tail.push_str(chunk);
+                let mut keep = tail.len().saturating_sub(overlap);
+                while !tail.is_char_boundary(keep) {
+                    keep -= 1;
+                }
+                tail.drain(..keep);
+            }
+            offset += chunk.len();
+        }
+
+        matches
+    }
 }
 
+/// A plain search term which ignores ASCII case
 #[derive(Clone)]
-pub struct Normalizations(std::collections::HashSet<String>);
+pub struct IgnoreCase(pub String);
 
-impl TryFrom<String> for Normalizations {
-    type Error = String;
+impl std::fmt::Display for IgnoreCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
 
-    // In this instance, we don't want to fail early on Err
-    // so a try_fold is not appropriate.
-    #[allow(clippy::manual_try_fold)]
-    fn try_from(base: String) -> Result<Self, Self::Error> {
-        use unicode_normalization::UnicodeNormalization;
+impl SearchTerm for IgnoreCase {
+    fn match_ranges(&self, s: &str) -> impl Iterator<Item = SearchMatch> {
+        // ASCII case folding never changes a string's byte length,
+        // so matching bytes in place keeps offsets into the original
+        let term = self.0.as_bytes();
+        let bytes = s.as_bytes();
+        let mut start = 0;
+
+        std::iter::from_fn(move || {
+            if term.is_empty() {
+                return None;
+            }
+            let idx = start
+                + bytes
+                    .get(start..)?
+                    .windows(term.len())
+                    .position(|w| w.eq_ignore_ascii_case(term))?;
+            start = idx + term.len();
+            Some(SearchMatch {
+                start: idx,
+                end: idx + term.len(),
+                groups: vec![],
+            })
+        })
+    }
+}
 
-        [
-            base.nfc().collect(),
-            base.nfkd().collect(),
-            base.nfd().collect(),
-            base.nfkc().collect(),
-        ]
+/// A plain search term which only matches whole words
+#[derive(Clone)]
+pub struct WholeWord(pub String);
+
+impl std::fmt::Display for WholeWord {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl SearchTerm for WholeWord {
+    fn match_ranges(&self, s: &str) -> impl Iterator<Item = SearchMatch> {
+        // the edges of the searched text count as word boundaries
+        self.0.match_ranges(s).filter(|m| {
+            !s[..m.start].chars().next_back().is_some_and(is_word_part)
+                && !s[m.end..].chars().next().is_some_and(is_word_part)
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct Normalizations(std::collections::HashSet<String>);
+
+impl TryFrom<String> for Normalizations {
+    type Error = String;
+
+    // In this instance, we don't want to fail early on Err
+    // so a try_fold is not appropriate.
+    #[allow(clippy::manual_try_fold)]
+    fn try_from(base: String) -> Result<Self, Self::Error> {
+        use unicode_normalization::UnicodeNormalization;
+
+        [
+            base.nfc().collect(),
+            base.nfkd().collect(),
+            base.nfd().collect(),
+            base.nfkc().collect(),
+        ]
         .into_iter()
         .fold(Err(base), |acc, s| match acc {
             Ok(mut map) => {
@@ -3755,6 +5635,16 @@ impl SearchTerm for Normalizations {
         ranges.sort_unstable_by_key(|r| r.start);
         ranges.into_iter()
     }
+
+    fn rope_match_ranges(&self, rope: ropey::RopeSlice<'_>) -> Vec<SearchMatch> {
+        let mut ranges = self
+            .0
+            .iter()
+            .flat_map(|string| string.rope_match_ranges(rope))
+            .collect::<Vec<_>>();
+        ranges.sort_unstable_by_key(|r| r.start);
+        ranges
+    }
 }
 
 /// Buffer has been modified since last save
@@ -3814,7 +5704,7 @@ fn selected_lines(
 
 // Given cursor position from start of rope,
 // return that cursor's column in line
-fn cursor_column(rope: &ropey::Rope, cursor: usize) -> usize {
+fn cursor_column(rope: &ropey::Rope, cursor: usize, tab_width: usize) -> usize {
     use unicode_width::UnicodeWidthChar;
 
     rope.try_char_to_line(cursor)
@@ -3824,7 +5714,7 @@ fn cursor_column(rope: &ropey::Rope, cursor: usize) -> usize {
             rope.chars_at(line_start)
                 .take(cursor.saturating_sub(line_start))
                 .map(|c| match c {
-                    '\t' => *SPACES_PER_TAB,
+                    '\t' => tab_width,
                     c => c.width().unwrap_or(1),
                 })
                 .sum()
@@ -3832,6 +5722,79 @@ fn cursor_column(rope: &ropey::Rope, cursor: usize) -> usize {
         .unwrap_or(0)
 }
 
+/// Given cursor position, returns the start of the previous word
+/// (skipping non-word characters, then word characters)
+/// stopping at the start of the current line
+fn prev_word_boundary(rope: &ropey::Rope, mut cursor: usize) -> usize {
+    let mut chars = rope.chars_at(cursor).reversed().peekable();
+
+    if chars.next_if_eq(&'\n').is_some() {
+        return cursor - 1;
+    }
+    while chars.next_if(|c| *c != '\n' && !is_word_part(*c)).is_some() {
+        cursor -= 1;
+    }
+    while chars.next_if(|c| is_word_part(*c)).is_some() {
+        cursor -= 1;
+    }
+    cursor
+}
+
+/// Given cursor position, returns the end of the next word
+/// (skipping non-word characters, then word characters)
+/// stopping at the end of the current line
+fn next_word_boundary(rope: &ropey::Rope, mut cursor: usize) -> usize {
+    let mut chars = rope.chars_at(cursor).peekable();
+
+    if chars.next_if_eq(&'\n').is_some() {
+        return cursor + 1;
+    }
+    while chars.next_if(|c| *c != '\n' && !is_word_part(*c)).is_some() {
+        cursor += 1;
+    }
+    while chars.next_if(|c| is_word_part(*c)).is_some() {
+        cursor += 1;
+    }
+    cursor
+}
+
+/// Swaps adjacent line blocks start..mid and mid..end
+/// where the first block always ends with a newline
+/// but the second may not, if it's at the end of the rope.
+///
+/// Returns closure to map old positions to their new locations.
+fn swap_line_blocks(
+    rope: &mut ropey::Rope,
+    start: usize,
+    mid: usize,
+    end: usize,
+) -> impl Fn(&mut usize) + use<> {
+    let first = String::from(rope.slice(start..mid));
+    let second = String::from(rope.slice(mid..end));
+    let terminated = second.ends_with('\n');
+    let (first_len, second_len) = (mid - start, end - mid);
+
+    rope.remove(start..end);
+    if terminated {
+        rope.insert(start, &first);
+        rope.insert(start, &second);
+    } else {
+        // the block moving down needs to lose its newline
+        // and the block moving up needs to gain one
+        rope.insert(start, first.strip_suffix('\n').unwrap_or(&first));
+        rope.insert_char(start, '\n');
+        rope.insert(start, &second);
+    }
+
+    move |pos: &mut usize| {
+        if (start..mid).contains(pos) {
+            *pos += second_len + usize::from(!terminated);
+        } else if (mid..end).contains(pos) || (!terminated && *pos == end) {
+            *pos -= first_len;
+        }
+    }
+}
+
 /// Given desired cursor column and line boundaries,
 /// returns cursor's absolute position in rope
 fn apply_cursor_column(
@@ -3839,6 +5802,7 @@ fn apply_cursor_column(
     mut cursor_column: usize,
     mut line_start: usize,
     line_end: usize,
+    tab_width: usize,
 ) -> usize {
     use unicode_width::UnicodeWidthChar;
 
@@ -3846,7 +5810,7 @@ fn apply_cursor_column(
     while cursor_column > 0 && line_start < line_end {
         match chars.next() {
             Some('\t') => {
-                cursor_column = cursor_column.saturating_sub(*SPACES_PER_TAB);
+                cursor_column = cursor_column.saturating_sub(tab_width);
                 line_start += 1;
             }
             Some(c) => {
@@ -3897,6 +5861,7 @@ fn zap_selection(
     rope: &mut ropey::Rope,
     cursor: &mut usize,
     column: &mut usize,
+    tab_width: usize,
     selection: usize,
     secondary: &mut Secondary,
 ) {
@@ -3906,7 +5871,7 @@ fn zap_selection(
         .is_ok()
     {
         *cursor = selection_start;
-        *column = cursor_column(rope, *cursor);
+        *column = cursor_column(rope, *cursor, tab_width);
         secondary.update(|pos| {
             if (selection_start..selection_end).contains(pos) {
                 *pos = selection_start;
@@ -4224,17 +6189,35 @@ fn delete_surround(
 impl From<Buffer> for BufferContext {
     fn from(buffer: Buffer) -> Self {
         Self {
+            message: if buffer.mixed_endings {
+                Some(BufferMessage::Notice(
+                    "Mixed Line Endings Normalized".into(),
+                ))
+            } else if buffer.encoding == Encoding::Latin1 {
+                Some(BufferMessage::Notice("Not UTF-8, Read as Latin-1".into()))
+            } else {
+                None
+            },
             buffer: buffer.into(),
             cursor: 0,
             cursor_column: 0,
             selection: None,
-            message: None,
             undo: vec![],
             redo: vec![],
+            overwrite: false,
+            unhighlighted: false,
+            viewport_line: Cell::new(0),
+            line_numbers: LineNumbers::default(),
+            whitespace: false,
+            wrapped: false,
+            wrap_width: Cell::new(0),
         }
     }
 }
 
+/// Multi-cursor autocompletion offsets, by buffer index
+pub type BufferOffsets = BTreeMap<usize, Vec<usize>>;
+
 /// A set of buffer contexts on a per-view basis
 #[derive(Clone, Default)]
 pub struct BufferList {
@@ -4252,8 +6235,15 @@ impl BufferList {
             .collect::<Result<Vec<_>, _>>()?;
 
         if buffers.is_empty() {
+            // alongside the tutorial, offer a blank document to start with
             Ok(Self {
-                buffers: vec![Buffer::tutorial().into()],
+                buffers: vec![
+                    Buffer::tutorial().into(),
+                    Buffer::open(Source::Memory {
+                        name: "Untitled".into(),
+                    })?
+                    .into(),
+                ],
                 current: 0,
             })
         } else {
@@ -4329,18 +6319,41 @@ impl BufferList {
     }
 
     /// Returns cursor's position relative to the viewport as (row, col)
-    ///
-    /// The cursor should be centered in the viewport unless
-    /// at the very beginning of the file.
-    pub fn cursor_viewport_position(&self, viewport_height: usize) -> Option<(usize, usize)> {
+    pub fn cursor_viewport_position(
+        &self,
+        viewport_height: usize,
+        viewport_width: usize,
+    ) -> Option<(usize, usize)> {
         let buf = self.current()?;
-        buf.cursor_position()
-            .map(|(_, col)| (viewport_height / 2, col))
+        let (viewport_line, top_margin) = buf.viewport(viewport_height);
+        let gutter_width = usize::from(buf.gutter_width(viewport_height));
+        let (line, col) = buf.cursor_position()?;
+        let col = match buf.wrapped {
+            false => col,
+            true => {
+                let (rows, _, _) = buf.wrapped_rows(
+                    viewport_height,
+                    buf.wrap_width(viewport_width, viewport_height),
+                );
+                match rows.iter().rfind(|r| r.line == line && r.start <= col) {
+                    Some(WrappedRow { start: 0, .. }) | None => col,
+                    Some(WrappedRow { start, .. }) => col - start + WRAP_INDENT,
+                }
+            }
+        };
+        Some((line + top_margin - viewport_line, col + gutter_width))
+    }
+
+    /// Display width of a literal tab in the current buffer
+    pub fn tab_width(&self) -> usize {
+        self.current()
+            .map(|buf| buf.buffer.borrow().tab_width())
+            .unwrap_or(*SPACES_PER_TAB)
     }
 
-    pub fn set_cursor_focus(&mut self, area: Rect, position: Position) {
+    pub fn set_cursor_focus(&mut self, area: Rect, position: Position, selecting: bool) {
         if let Some(buf) = self.current_mut() {
-            buf.set_cursor_focus(area, position);
+            buf.set_cursor_focus(area, position, selecting);
         }
     }
 
@@ -4410,10 +6423,12 @@ impl BufferList {
                     .iter()
                     .enumerate()
                     .map(|(idx, b)| {
+                        let buf = b.buffer.borrow();
+                        let modified = if buf.modified() { " *" } else { "" };
                         if self.current == idx {
-                            format!("[{}]", b.buffer.borrow().source.short_name())
+                            format!("[{}{modified}]", buf.source.short_name())
                         } else {
-                            format!(" {} ", b.buffer.borrow().source.short_name())
+                            format!(" {}{modified} ", buf.source.short_name())
                         }
                     })
                     .collect(),
@@ -4428,7 +6443,7 @@ impl BufferList {
     /// Ok(Ok(count))  - number of buffers saved on disk
     /// Ok(Err(err))   - error saving buffer to disk
     /// Err(Modified)  - any buffer modified on disk since last save
-    pub fn save_all(&mut self) -> Result<std::io::Result<usize>, Modified> {
+    pub fn save_all(&mut self, alts: &mut [&mut Self]) -> Result<std::io::Result<usize>, Modified> {
         let mut count = 0;
 
         for (idx, buf) in self
@@ -4437,7 +6452,11 @@ impl BufferList {
             .enumerate()
             .filter(|(_, buf)| buf.modified())
         {
-            match buf.verified_save() {
+            match buf.verified_save(
+                alts.iter_mut()
+                    .filter_map(|a| a.get_mut(idx).map(|a| a.alt_cursor()))
+                    .collect(),
+            ) {
                 Ok(Ok(())) => {
                     count += 1;
                 }
@@ -4562,8 +6581,8 @@ impl BufferList {
     pub fn multi_autocomplete_matches(
         &self,
         cursors: &BTreeMap<usize, Vec<MultiCursor>>,
-    ) -> Option<(BTreeMap<usize, Vec<usize>>, Vec<String>)> {
-        let mut offsets: BTreeMap<usize, Vec<usize>> = BTreeMap::default();
+    ) -> Option<(BufferOffsets, Vec<String>)> {
+        let mut offsets = BufferOffsets::default();
         let mut prefix = None;
 
         // determine common prefix among all cursors on all buffers
@@ -4653,6 +6672,22 @@ impl BufferWidget<'_> {
 
         text_area.height.into()
     }
+
+    pub fn viewport_width(area: Rect) -> usize {
+        use ratatui::{
+            layout::{
+                Constraint::{Length, Min},
+                Layout,
+            },
+            widgets::Block,
+        };
+
+        let block = Block::bordered();
+
+        let [text_area, _] = Layout::horizontal([Min(0), Length(1)]).areas(block.inner(area));
+
+        text_area.width.into()
+    }
 }
 
 impl StatefulWidget for BufferWidget<'_> {
@@ -4661,13 +6696,13 @@ impl StatefulWidget for BufferWidget<'_> {
     fn render(self, area: Rect, buf: &mut ratatui::buffer::Buffer, state: &mut BufferContext) {
         use crate::editor::SearchType;
         use crate::help::{
-            CONFIRM_CLOSE, MARK_SET, MULTICURSOR_MARK_SET, PASTE_GROUP, REPLACE_MATCHES,
-            SELECT_BUFFER, SELECT_INSIDE, SELECT_LINE, SELECT_LINE_BOOKMARKED, SPLIT_PANE,
-            VERIFY_RELOAD, VERIFY_SAVE, render_help,
+            COMMAND_PALETTE, CONFIRM_CLOSE, MARK_SET, MULTICURSOR_MARK_SET, PASTE_GROUP,
+            REPLACE_MATCHES, SELECT_BUFFER, SELECT_INSIDE, SELECT_LINE, SELECT_LINE_BOOKMARKED,
+            SELECT_SYNTAX, SHELL_FILTER, SPLIT_PANE, SURROUND_WITH, VERIFY_RELOAD, VERIFY_SAVE,
+            render_help,
         };
         use crate::prompt::TextField;
         use crate::scrollbar::{Scrollbar, ScrollbarState};
-        use crate::syntax::{HighlightState, Highlighter, MultiComment, MultiCommentType};
         use ratatui::{
             layout::{
                 Constraint::{Length, Min},
@@ -4685,7 +6720,11 @@ impl StatefulWidget for BufferWidget<'_> {
         const MATCHING: Color = Color::Yellow;
         const MISMATCH: Color = Color::Red;
         const BOOKMARK: Color = Color::Cyan;
+        const CURSOR_LINE_BG: Color = Color::Indexed(236);
         const HIGHLIGHTED: Style = Style::new().bg(Color::Yellow).fg(Color::Black);
+        const FOUND: Style = Style::new()
+            .add_modifier(Modifier::REVERSED)
+            .add_modifier(Modifier::DIM);
         const HIGHLIGHT_MATCH: Style = underline_color(Color::Blue)
             .bg(Color::Yellow)
             .fg(Color::Black);
@@ -4694,6 +6733,36 @@ impl StatefulWidget for BufferWidget<'_> {
             matches.iter().map(|m| m.range.start..m.range.end).collect()
         }
 
+        type LineMatches = Box<dyn Fn(&str, usize) -> VecDeque<Range<usize>>>;
+
+        // Given search prompt, returns a function which takes
+        // a line of text and its starting character in the rope,
+        // and returns the character ranges of the prompt's matches,
+        // provided the prompt is non-empty and valid
+        fn search_highlighter(prompt: &TextField, type_: SearchType) -> Option<LineMatches> {
+            fn boxed<S: SearchTerm + 'static>(term: S) -> LineMatches {
+                Box::new(move |line, line_start| {
+                    term.match_ranges(line)
+                        .map(|m| {
+                            line_start + line[..m.start].chars().count()
+                                ..line_start + line[..m.end].chars().count()
+                        })
+                        .collect()
+                })
+            }
+
+            let value = prompt.value()?;
+            Some(match type_ {
+                SearchType::Plain => match Normalizations::try_from(value) {
+                    Ok(normalizations) => boxed(normalizations),
+                    Err(term) => boxed(term),
+                },
+                SearchType::IgnoreCase => boxed(IgnoreCase(value)),
+                SearchType::WholeWord => boxed(WholeWord(value)),
+                SearchType::Regex => boxed(value.parse::<fancy_regex::Regex>().ok()?),
+            })
+        }
+
         struct EditorLine<'s> {
             line: Cow<'s, str>,
             range: RangeInclusive<usize>, // range in rope in characters
@@ -4726,17 +6795,65 @@ impl StatefulWidget for BufferWidget<'_> {
             }
         }
 
-        fn widen_tabs<'l>(mut input: Line<'l>) -> Line<'l> {
-            fn tabs_to_spaces(s: &mut Cow<'_, str>) {
+        // Like widen_tabs, but renders whitespace as visible glyphs,
+        // except for the final column appended by widen()
+        fn show_whitespace<'l>(input: Line<'l>, tab: &str) -> Line<'l> {
+            const SPACE: char = '\u{b7}';
+            const TAB: char = '\u{2192}';
+
+            let mut remaining = input
+                .spans
+                .iter()
+                .map(|s| s.content.chars().count())
+                .sum::<usize>()
+                .saturating_sub(1);
+
+            let mut spans = Vec::with_capacity(input.spans.len());
+            for span in input.spans {
+                let mut run = String::new();
+                let mut run_is_ws = false;
+                for c in span.content.chars() {
+                    let is_ws = remaining > 0 && matches!(c, ' ' | '\t');
+                    remaining = remaining.saturating_sub(1);
+                    if is_ws != run_is_ws && !run.is_empty() {
+                        spans.push(match run_is_ws {
+                            true => Span::styled(std::mem::take(&mut run), span.style.dim()),
+                            false => Span::styled(std::mem::take(&mut run), span.style),
+                        });
+                    }
+                    run_is_ws = is_ws;
+                    match c {
+                        ' ' if is_ws => run.push(SPACE),
+                        '\t' if is_ws => {
+                            run.push(TAB);
+                            run.extend(std::iter::repeat_n(' ', tab.len().saturating_sub(1)));
+                        }
+                        '\t' => run.push_str(tab),
+                        c => run.push(c),
+                    }
+                }
+                if !run.is_empty() {
+                    spans.push(match run_is_ws {
+                        true => Span::styled(run, span.style.dim()),
+                        false => Span::styled(run, span.style),
+                    });
+                }
+            }
+
+            Line { spans, ..input }
+        }
+
+        fn widen_tabs<'l>(mut input: Line<'l>, tab: &str) -> Line<'l> {
+            fn tabs_to_spaces(s: &mut Cow<'_, str>, tab: &str) {
                 if s.as_ref().contains('\t') {
-                    *s = Cow::Owned(s.as_ref().replace('\t', &TAB_SUBSTITUTION));
+                    *s = Cow::Owned(s.as_ref().replace('\t', tab));
                 }
             }
 
             input
                 .spans
                 .iter_mut()
-                .for_each(|s| tabs_to_spaces(&mut s.content));
+                .for_each(|s| tabs_to_spaces(&mut s.content, tab));
             input
         }
 
@@ -5202,6 +7319,7 @@ impl StatefulWidget for BufferWidget<'_> {
             text_area: Rect,
             buf: &mut ratatui::buffer::Buffer,
             prompt: &TextField,
+            tab_width: usize,
             highlight: impl FnOnce(VecDeque<Span<'s>>) -> VecDeque<Span<'s>>,
             block: impl FnOnce(Block) -> Block,
         ) {
@@ -5226,7 +7344,7 @@ impl StatefulWidget for BufferWidget<'_> {
                 })
                 .into(),
                 prompt
-                    .cursor_column()
+                    .cursor_column(tab_width)
                     .saturating_sub(dialog_area.width.saturating_sub(2).into()),
             ))
             .block(block(Block::bordered().border_type(BorderType::Rounded)))
@@ -5234,14 +7352,17 @@ impl StatefulWidget for BufferWidget<'_> {
         }
 
         fn find_mode_help(prompt: &TextField, type_: SearchType) -> Vec<crate::help::Keybinding> {
-            use crate::help::{ctrl, keybind, none};
+            use crate::help::{alt, keybind, none};
+            use crate::key::Action;
 
             let mut help = if prompt.is_empty() {
                 // Tab when buffer is empty switches mode
                 vec![none(
                     &["Tab"],
                     match type_ {
-                        SearchType::Plain => "Regex Find",
+                        SearchType::Plain => "Case-Insensitive Find",
+                        SearchType::IgnoreCase => "Whole Word Find",
+                        SearchType::WholeWord => "Regex Find",
                         SearchType::Regex => "Plain Text Find",
                     },
                 )]
@@ -5252,16 +7373,23 @@ impl StatefulWidget for BufferWidget<'_> {
             };
 
             help.extend([
-                ctrl(&["V"], "Paste From Cut Buffer"),
-                keybind::<crate::key::GotoLine>("Goto Line"),
-                keybind::<crate::key::Find>(match prompt.is_empty() {
-                    true => "Redo Last Find",
-                    false => "Begin New Find",
-                }),
+                keybind(Action::Paste, "Paste From Cut Buffer"),
+                keybind(Action::GotoLine, "Goto Line"),
+                keybind(
+                    Action::Find,
+                    match prompt.is_empty() {
+                        true => "Redo Last Find",
+                        false => "Begin New Find",
+                    },
+                ),
                 none(&["Enter"], "Browse All Matches"),
                 none(&["Esc"], "Cancel"),
             ]);
 
+            if !prompt.is_empty() {
+                help.push(alt(&["C"], "Count Matches"));
+            }
+
             help
         }
 
@@ -5281,10 +7409,17 @@ impl StatefulWidget for BufferWidget<'_> {
                     {
                         block.title_bottom(
                             border_title(
-                                match lines {
-                                    0 => "1 Line".to_string(),
-                                    n => format!("{} Lines", n + 1),
-                                },
+                                format!(
+                                    "{} / {}",
+                                    match end - start {
+                                        1 => "1 Char".to_string(),
+                                        n => format!("{} Chars", Thousands(n)),
+                                    },
+                                    match lines {
+                                        0 => "1 Line".to_string(),
+                                        n => format!("{} Lines", Thousands(n + 1)),
+                                    }
+                                ),
                                 focused,
                             )
                             .centered(),
@@ -5317,7 +7452,7 @@ impl StatefulWidget for BufferWidget<'_> {
         // Actual rendering starts here
         ///////////////////////////////
 
-        if let Some(EditorMode::Open { chooser }) = self.mode {
+        if let Some(EditorMode::Open { chooser } | EditorMode::SaveAs { chooser }) = self.mode {
             // file selection mode overrides main editing mode
             use crate::files::FileChooser;
 
@@ -5327,7 +7462,14 @@ impl StatefulWidget for BufferWidget<'_> {
 
         let buffer = state.buffer.borrow();
         let rope = &buffer.rope;
-        let syntax = &buffer.syntax;
+        let tab_width = buffer.tab_width();
+        // the border still names the buffer's syntax when unhighlighted,
+        // alongside its PLAIN marker
+        let plain: Box<dyn Highlighter> = Box::new(crate::syntax::DefaultHighlighter);
+        let syntax = match state.unhighlighted {
+            true => &plain,
+            false => &buffer.syntax,
+        };
         let focused = self.focused && self.mode.is_some();
         let show_sub_help: fn(
             ratatui::layout::Rect,
@@ -5381,11 +7523,30 @@ impl StatefulWidget for BufferWidget<'_> {
             block
         };
 
+        let block = if state.overwrite {
+            block.title_bottom(border_title("OVR".to_string(), focused).right_aligned())
+        } else {
+            block
+        };
+
+        let block = if state.unhighlighted {
+            block.title_bottom(border_title("PLAIN".to_string(), focused).right_aligned())
+        } else {
+            block
+        };
+
+        let block = match buffer.encoding.name() {
+            Some(name) => block.title_bottom(border_title(name.to_string(), focused)),
+            None => block,
+        };
+
         let block = match buffer.endings.name() {
             Some(name) => block
-                .title_bottom(border_title(syntax.to_string(), focused).right_aligned())
+                .title_bottom(border_title(buffer.syntax.to_string(), focused).right_aligned())
                 .title_bottom(border_title(name.to_string(), focused)),
-            None => block.title_bottom(border_title(syntax.to_string(), focused).right_aligned()),
+            None => {
+                block.title_bottom(border_title(buffer.syntax.to_string(), focused).right_aligned())
+            }
         };
 
         let block = match buffer.bookmarks.len() {
@@ -5420,11 +7581,20 @@ impl StatefulWidget for BufferWidget<'_> {
                     _ => match buffer.rope.try_char_to_line(state.cursor) {
                         Ok(line) => match buffer.rope.try_line_to_char(line) {
                             Ok(line_start) => {
-                                format!(
-                                    "{}:{}",
-                                    Thousands(line + 1),
-                                    (state.cursor - line_start) + 1
-                                )
+                                use unicode_width::UnicodeWidthChar;
+
+                                // display column, with tabs expanded
+                                let column = buffer
+                                    .rope
+                                    .chars_at(line_start)
+                                    .take(state.cursor - line_start)
+                                    .map(|c| match c {
+                                        '\t' => tab_width,
+                                        c => c.width().unwrap_or(0),
+                                    })
+                                    .sum::<usize>();
+
+                                format!("{}:{}", Thousands(line + 1), column + 1)
                             }
                             Err(_) => format!("{}", Thousands(line + 1)),
                         },
@@ -5507,7 +7677,7 @@ impl StatefulWidget for BufferWidget<'_> {
             Some(EditorMode::MarkSet) => {
                 line_count(rope, block, state.cursor, state.selection, focused)
             }
-            Some(EditorMode::Open { .. }) => block,
+            Some(EditorMode::Open { .. } | EditorMode::SaveAs { .. }) => block,
             _ => line_count(rope, block, state.cursor, state.selection, focused),
         };
 
@@ -5519,38 +7689,87 @@ impl StatefulWidget for BufferWidget<'_> {
         let current_line = rope.try_char_to_line(state.cursor).ok();
         let viewport_height: usize = text_area.height.into();
 
-        let (viewport_line, top_margin): (usize, usize) = current_line
-            .map(|line| match line.checked_sub(viewport_height / 2) {
-                Some(start) => (start, 0),
-                None => (0, viewport_height / 2 - line),
-            })
-            .unwrap_or_default();
+        let (viewport_line, top_margin) = state.viewport(viewport_height);
+
+        let [gutter_area, lines_area] =
+            Layout::horizontal([Length(state.gutter_width(viewport_height)), Min(0)])
+                .areas(text_area);
+
+        // soft-wrapped rows, how many are scrolled off the top,
+        // and how many blank lines are above the rest
+        let wrapped = state.wrapped.then(|| {
+            let width = lines_area.width.saturating_sub(Self::RIGHT_MARGIN).into();
+            // so moving up and down can follow the rows onscreen
+            state.wrap_width.set(width);
+            state.wrapped_rows(viewport_height, width)
+        });
+
+        if !gutter_area.is_empty() {
+            // line number starting each onscreen row, if any
+            let row_lines: Vec<Option<usize>> = match &wrapped {
+                None => std::iter::repeat_n(None, top_margin)
+                    .chain((viewport_line..rope.len_lines()).map(Some))
+                    .take(viewport_height)
+                    .collect(),
+                Some((rows, skipped, top_margin)) => std::iter::repeat_n(None, *top_margin)
+                    .chain(
+                        rows.iter()
+                            .skip(*skipped)
+                            .map(|r| (r.start == 0).then_some(r.line)),
+                    )
+                    .take(viewport_height)
+                    .collect(),
+            };
+
+            // lines holding bookmarks get their numbers marked
+            let bookmarked = buffer
+                .bookmarks
+                .iter()
+                .filter_map(|b| rope.try_char_to_line(b).ok())
+                .collect::<std::collections::BTreeSet<_>>();
+
+            Paragraph::new(
+                row_lines
+                    .into_iter()
+                    .map(|line| {
+                        let Some(line) = line else {
+                            return Line::default();
+                        };
+                        let (number, style) = match (state.line_numbers, current_line) {
+                            (_, Some(current)) if current == line => {
+                                (line + 1, Style::default().bold())
+                            }
+                            // the cursor's own line keeps its absolute number
+                            (LineNumbers::Relative, Some(current)) => {
+                                (line.abs_diff(current), Style::default().dim())
+                            }
+                            _ => (line + 1, Style::default().dim()),
+                        };
+                        let style = match bookmarked.contains(&line) {
+                            true => Style::default().fg(Color::Black).bg(BOOKMARK),
+                            false => style,
+                        };
+                        Line::from(vec![
+                            Span::styled(number.to_string(), style),
+                            Span::raw(" "),
+                        ])
+                        .right_aligned()
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .render(gutter_area, buf);
+        }
 
         let bottom_margin = (viewport_line + viewport_height).saturating_sub(rope.len_lines());
 
         let viewport_start = rope.try_line_to_char(viewport_line).unwrap_or(0);
 
-        // Initial HighlightState varies according to whether
-        // we detect a multi-line comment is open or not.
-
-        let mut hlstate: HighlightState = match syntax.multicomment() {
-            Some(MultiCommentType::Bidirectional(f)) => rope
-                .lines_at(viewport_line)
-                .take(area.height.into())
-                .find_map(|line| {
-                    f(&Cow::from(line)).map(|multicomment| match multicomment {
-                        MultiComment::Start => HighlightState::Normal,
-                        MultiComment::End => HighlightState::Commenting,
-                    })
-                })
-                .unwrap_or_default(),
-            Some(MultiCommentType::Unidirectional(f)) => rope
-                .lines()
-                .take(viewport_line)
-                .fold(HighlightState::default(), |acc, line| {
-                    f(acc, &Cow::from(line))
-                }),
-            None => HighlightState::default(),
+        // Initial HighlightState depends on whether
+        // a multi-line comment or string is open above the viewport
+
+        let mut hlstate: HighlightState = match state.unhighlighted {
+            true => HighlightState::default(),
+            false => buffer.highlight_state(viewport_line),
         };
 
         // we're technically only viewing half of the viewport most of the time
@@ -5604,430 +7823,484 @@ impl StatefulWidget for BufferWidget<'_> {
 
         let mut marks = marks.into_iter().collect();
 
+        let expand: for<'l> fn(Line<'l>, &str) -> Line<'l> = match state.whitespace {
+            true => show_whitespace,
+            false => widen_tabs,
+        };
+        let tab = buffer.tab_substitution.as_str();
+        let widen_whitespace = |line| expand(line, tab);
+
         ////////////////////////////////////////////////////////////
         // Generate and render the actual buffer text as a Paragraph
         // according to the mode the buffer is in.
         ////////////////////////////////////////////////////////////
 
-        Paragraph::new(apply_margins(
-            crate::truncate::lines_start(
-                match self.mode {
-                    Some(EditorMode::SingleBuffer {
-                        cursors:
-                            MultiCursors {
-                                matches,
-                                match_idx,
-                                highlight: true,
-                                mode:
-                                    MultiCursorMode::Editing
-                                    | MultiCursorMode::MarkSet
-                                    | MultiCursorMode::PasteGroup { .. },
-                                ..
-                            },
+        let lines: Vec<Line<'_>> = match self.mode {
+            Some(EditorMode::SingleBuffer {
+                cursors:
+                    MultiCursors {
+                        matches,
+                        match_idx,
+                        highlight: true,
+                        mode:
+                            MultiCursorMode::Editing
+                            | MultiCursorMode::MarkSet
+                            | MultiCursorMode::PasteGroup { .. },
                         ..
-                    }) => {
-                        let selection_start = matches[*match_idx].range.start;
-                        let selection_end = matches[*match_idx].range.end;
-                        let mut matches = sub_match_ranges(matches);
-
-                        EditorLine::iter(rope, viewport_line)
-                            .map(
-                                |EditorLine {
-                                     line,
-                                     range,
-                                     number,
-                                 }| {
-                                    let colorized = colorize(
-                                        syntax,
-                                        &mut hlstate,
-                                        line,
-                                        current_line == Some(number),
-                                    );
-                                    let matches = highlight_matches(
-                                        colorized,
-                                        range.clone(),
-                                        &mut matches,
-                                        |span| span.style(HIGHLIGHTED),
-                                    );
-                                    let selection = highlight_selection(
-                                        matches,
-                                        range.clone(),
-                                        (selection_start, selection_end),
-                                        |span| span.style(HIGHLIGHT_MATCH),
-                                    );
-                                    Vec::from(selection).into()
-                                },
-                            )
-                            .map(|line| widen_tabs(line))
-                            .take(area.height.into())
-                            .collect()
-                    }
-                    Some(EditorMode::SingleBuffer {
-                        cursors:
-                            MultiCursors {
+                    },
+                ..
+            }) => {
+                let selection_start = matches[*match_idx].range.start;
+                let selection_end = matches[*match_idx].range.end;
+                let mut matches = sub_match_ranges(matches);
+
+                EditorLine::iter(rope, viewport_line)
+                    .map(
+                        |EditorLine {
+                             line,
+                             range,
+                             number,
+                         }| {
+                            let colorized =
+                                colorize(syntax, &mut hlstate, line, current_line == Some(number));
+                            let matches =
+                                highlight_matches(colorized, range.clone(), &mut matches, |span| {
+                                    span.style(HIGHLIGHTED)
+                                });
+                            let selection = highlight_selection(
                                 matches,
-                                highlight: false,
-                                mode:
-                                    MultiCursorMode::Editing
-                                    | MultiCursorMode::MarkSet
-                                    | MultiCursorMode::PasteGroup { .. },
-                                ..
-                            },
+                                range.clone(),
+                                (selection_start, selection_end),
+                                |span| span.style(HIGHLIGHT_MATCH),
+                            );
+                            Vec::from(selection).into()
+                        },
+                    )
+                    .map(widen_whitespace)
+                    .take(area.height.into())
+                    .collect()
+            }
+            Some(EditorMode::SingleBuffer {
+                cursors:
+                    MultiCursors {
+                        matches,
+                        highlight: false,
+                        mode:
+                            MultiCursorMode::Editing
+                            | MultiCursorMode::MarkSet
+                            | MultiCursorMode::PasteGroup { .. },
                         ..
-                    }) => {
-                        let (mut cursors, (mut ranges, selections)): (
-                            VecDeque<_>,
-                            (_, VecFiltered<_>),
-                        ) = matches
-                            .iter()
-                            .map(|m| {
-                                (
-                                    m.cursor..m.cursor + 1,
-                                    (m.range.clone(), m.selection_range()),
-                                )
-                            })
-                            .unzip();
-
-                        cursors.retain(|r| r.start != state.cursor);
-                        let mut selections = selections.into();
-
-                        EditorLine::iter(rope, viewport_line)
-                            .map(
-                                |EditorLine {
-                                     line,
-                                     range,
-                                     number,
-                                 }| {
-                                    let whole_range = widen_range(range);
-                                    let colorized = colorize(
-                                        syntax,
-                                        &mut hlstate,
-                                        line,
-                                        current_line == Some(number),
-                                    );
-                                    let widened = widen(colorized);
-                                    let underlined = highlight_matches(
-                                        widened,
-                                        whole_range.clone(),
-                                        &mut ranges,
-                                        |span| span.patch_style(underline_color(Color::Blue)),
-                                    );
-                                    let selections = highlight_matches(
-                                        underlined,
-                                        whole_range.clone(),
-                                        &mut selections,
-                                        |span| span.style(EDITING),
-                                    );
-                                    let cursors = highlight_matches(
-                                        selections,
-                                        whole_range,
-                                        &mut cursors,
-                                        |span| {
-                                            span.style(
-                                                Style::new()
-                                                    .fg(Color::Blue)
-                                                    .add_modifier(Modifier::REVERSED),
-                                            )
-                                        },
-                                    );
-                                    Vec::from(cursors).into()
-                                },
-                            )
-                            .map(|line| widen_tabs(line))
-                            .take(area.height.into())
-                            .collect()
-                    }
-                    Some(EditorMode::AllBuffers {
-                        cursors:
-                            MultiCursors {
-                                matches,
-                                match_idx,
-                                highlight: true,
-                                mode:
-                                    MultiCursorMode::Editing
-                                    | MultiCursorMode::MarkSet
-                                    | MultiCursorMode::PasteGroup { .. },
-                            },
-                    }) if let Some(matches) = matches.get(&self.buffer_idx) => {
-                        let selection_start = matches[*match_idx].range.start;
-                        let selection_end = matches[*match_idx].range.end;
-                        let mut matches = sub_match_ranges(matches);
-
-                        EditorLine::iter(rope, viewport_line)
-                            .map(
-                                |EditorLine {
-                                     line,
-                                     range,
-                                     number,
-                                 }| {
-                                    let colorized = colorize(
-                                        syntax,
-                                        &mut hlstate,
-                                        line,
-                                        current_line == Some(number),
-                                    );
-                                    let matches = highlight_matches(
-                                        colorized,
-                                        range.clone(),
-                                        &mut matches,
-                                        |span| span.style(HIGHLIGHTED),
-                                    );
-                                    let selection = highlight_selection(
-                                        matches,
-                                        range.clone(),
-                                        (selection_start, selection_end),
-                                        |span| span.style(HIGHLIGHT_MATCH),
-                                    );
-                                    Vec::from(selection).into()
-                                },
+                    },
+                ..
+            }) => {
+                let (mut cursors, (mut ranges, selections)): (VecDeque<_>, (_, VecFiltered<_>)) =
+                    matches
+                        .iter()
+                        .map(|m| {
+                            (
+                                m.cursor..m.cursor + 1,
+                                (m.range.clone(), m.selection_range()),
                             )
-                            .map(|line| widen_tabs(line))
-                            .take(area.height.into())
-                            .collect()
-                    }
-                    Some(EditorMode::AllBuffers {
-                        cursors:
-                            MultiCursors {
+                        })
+                        .unzip();
+
+                cursors.retain(|r| r.start != state.cursor);
+                let mut selections = selections.into();
+
+                EditorLine::iter(rope, viewport_line)
+                    .map(
+                        |EditorLine {
+                             line,
+                             range,
+                             number,
+                         }| {
+                            let whole_range = widen_range(range);
+                            let colorized =
+                                colorize(syntax, &mut hlstate, line, current_line == Some(number));
+                            let widened = widen(colorized);
+                            let underlined = highlight_matches(
+                                widened,
+                                whole_range.clone(),
+                                &mut ranges,
+                                |span| span.patch_style(underline_color(Color::Blue)),
+                            );
+                            let selections = highlight_matches(
+                                underlined,
+                                whole_range.clone(),
+                                &mut selections,
+                                |span| span.style(EDITING),
+                            );
+                            let cursors =
+                                highlight_matches(selections, whole_range, &mut cursors, |span| {
+                                    span.style(
+                                        Style::new()
+                                            .fg(Color::Blue)
+                                            .add_modifier(Modifier::REVERSED),
+                                    )
+                                });
+                            Vec::from(cursors).into()
+                        },
+                    )
+                    .map(widen_whitespace)
+                    .take(area.height.into())
+                    .collect()
+            }
+            Some(EditorMode::AllBuffers {
+                cursors:
+                    MultiCursors {
+                        matches,
+                        match_idx,
+                        highlight: true,
+                        mode:
+                            MultiCursorMode::Editing
+                            | MultiCursorMode::MarkSet
+                            | MultiCursorMode::PasteGroup { .. },
+                    },
+            }) if let Some(matches) = matches.get(&self.buffer_idx) => {
+                let selection_start = matches[*match_idx].range.start;
+                let selection_end = matches[*match_idx].range.end;
+                let mut matches = sub_match_ranges(matches);
+
+                EditorLine::iter(rope, viewport_line)
+                    .map(
+                        |EditorLine {
+                             line,
+                             range,
+                             number,
+                         }| {
+                            let colorized =
+                                colorize(syntax, &mut hlstate, line, current_line == Some(number));
+                            let matches =
+                                highlight_matches(colorized, range.clone(), &mut matches, |span| {
+                                    span.style(HIGHLIGHTED)
+                                });
+                            let selection = highlight_selection(
                                 matches,
-                                match_idx,
-                                highlight: false,
-                                mode:
-                                    MultiCursorMode::Editing
-                                    | MultiCursorMode::MarkSet
-                                    | MultiCursorMode::PasteGroup { .. },
-                            },
-                    }) if let Some(matches) = matches.get(&self.buffer_idx) => {
-                        let (mut cursors, (mut ranges, selections)): (
-                            VecDeque<_>,
-                            (_, VecFiltered<_>),
-                        ) = matches
-                            .iter()
-                            .map(|m| {
-                                (
-                                    m.cursor..m.cursor + 1,
-                                    (m.range.clone(), m.selection_range()),
-                                )
-                            })
-                            .unzip();
-
-                        cursors.retain(|r| r.start != state.cursor);
-                        let mut selections = selections.into();
-
-                        EditorLine::iter(rope, viewport_line)
-                            .map(
-                                |EditorLine {
-                                     line,
-                                     range,
-                                     number,
-                                 }| {
-                                    let whole_range = widen_range(range);
-
-                                    let colorized = colorize(
-                                        syntax,
-                                        &mut hlstate,
-                                        line,
-                                        current_line == Some(number),
-                                    );
-                                    let widened = widen(colorized);
-                                    let ranges = highlight_matches(
-                                        widened,
-                                        whole_range.clone(),
-                                        &mut ranges,
-                                        |span| span.patch_style(underline_color(Color::Blue)),
-                                    );
-                                    let selections = highlight_matches(
-                                        ranges,
-                                        whole_range.clone(),
-                                        &mut selections,
-                                        |span| span.style(EDITING),
-                                    );
-                                    let cursor = highlight_matches(
-                                        selections,
-                                        whole_range,
-                                        &mut cursors,
-                                        |span| {
-                                            span.style(
-                                                Style::new()
-                                                    .fg(Color::Blue)
-                                                    .add_modifier(Modifier::REVERSED),
-                                            )
-                                        },
-                                    );
-                                    Vec::from(cursor).into()
-                                },
+                                range.clone(),
+                                (selection_start, selection_end),
+                                |span| span.style(HIGHLIGHT_MATCH),
+                            );
+                            Vec::from(selection).into()
+                        },
+                    )
+                    .map(widen_whitespace)
+                    .take(area.height.into())
+                    .collect()
+            }
+            Some(EditorMode::AllBuffers {
+                cursors:
+                    MultiCursors {
+                        matches,
+                        match_idx,
+                        highlight: false,
+                        mode:
+                            MultiCursorMode::Editing
+                            | MultiCursorMode::MarkSet
+                            | MultiCursorMode::PasteGroup { .. },
+                    },
+            }) if let Some(matches) = matches.get(&self.buffer_idx) => {
+                let (mut cursors, (mut ranges, selections)): (VecDeque<_>, (_, VecFiltered<_>)) =
+                    matches
+                        .iter()
+                        .map(|m| {
+                            (
+                                m.cursor..m.cursor + 1,
+                                (m.range.clone(), m.selection_range()),
                             )
-                            .map(|line| widen_tabs(line))
-                            .take(area.height.into())
-                            .collect()
-                    }
-                    Some(EditorMode::SingleBuffer {
-                        cursors:
-                            MultiCursors {
-                                matches,
-                                mode:
-                                    MultiCursorMode::Autocomplete {
-                                        offsets,
-                                        completions,
-                                        index,
-                                    },
-                                ..
+                        })
+                        .unzip();
+
+                cursors.retain(|r| r.start != state.cursor);
+                let mut selections = selections.into();
+
+                EditorLine::iter(rope, viewport_line)
+                    .map(
+                        |EditorLine {
+                             line,
+                             range,
+                             number,
+                         }| {
+                            let whole_range = widen_range(range);
+
+                            let colorized =
+                                colorize(syntax, &mut hlstate, line, current_line == Some(number));
+                            let widened = widen(colorized);
+                            let ranges = highlight_matches(
+                                widened,
+                                whole_range.clone(),
+                                &mut ranges,
+                                |span| span.patch_style(underline_color(Color::Blue)),
+                            );
+                            let selections = highlight_matches(
+                                ranges,
+                                whole_range.clone(),
+                                &mut selections,
+                                |span| span.style(EDITING),
+                            );
+                            let cursor =
+                                highlight_matches(selections, whole_range, &mut cursors, |span| {
+                                    span.style(
+                                        Style::new()
+                                            .fg(Color::Blue)
+                                            .add_modifier(Modifier::REVERSED),
+                                    )
+                                });
+                            Vec::from(cursor).into()
+                        },
+                    )
+                    .map(widen_whitespace)
+                    .take(area.height.into())
+                    .collect()
+            }
+            Some(EditorMode::SingleBuffer {
+                cursors:
+                    MultiCursors {
+                        matches,
+                        mode:
+                            MultiCursorMode::Autocomplete {
+                                offsets,
+                                completions,
+                                index,
                             },
                         ..
-                    }) => {
-                        // We're underlining the multicursors' effective range (in blue),
-                        // the autocompletion replacements (in red)
-                        // *and* the cursors themselves (as a blue block).
-                        // Yes, I know it's a lot.
-
-                        let (mut cursors, mut ranges): (VecDeque<_>, _) = matches
-                            .iter()
-                            .map(|m| (m.cursor..m.cursor + 1, m.range.clone()))
-                            .unzip();
-
-                        let completion_chars = completions[*index].chars().count();
-                        let mut replacements = matches
-                            .iter()
-                            .zip(offsets)
-                            .map(|(m, o)| m.range.start + *o..m.range.start + *o + completion_chars)
-                            .collect();
-
-                        cursors.retain(|r| r.start != state.cursor);
+                    },
+                ..
+            }) => {
+                // We're underlining the multicursors' effective range (in blue),
+                // the autocompletion replacements (in red)
+                // *and* the cursors themselves (as a blue block).
+                // Yes, I know it's a lot.
 
-                        EditorLine::iter(rope, viewport_line)
-                            .map(
-                                |EditorLine {
-                                     line,
-                                     range,
-                                     number,
-                                 }| {
-                                    let whole_range = widen_range(range);
+                let (mut cursors, mut ranges): (VecDeque<_>, _) = matches
+                    .iter()
+                    .map(|m| (m.cursor..m.cursor + 1, m.range.clone()))
+                    .unzip();
 
-                                    let colorized = colorize(
-                                        syntax,
-                                        &mut hlstate,
-                                        line,
-                                        current_line == Some(number),
-                                    );
-                                    let widened = widen(colorized);
-                                    let ranges = highlight_matches(
-                                        widened,
-                                        whole_range.clone(),
-                                        &mut ranges,
-                                        |span| span.patch_style(underline_color(Color::Blue)),
-                                    );
-                                    let replacements = highlight_matches(
-                                        ranges,
-                                        whole_range.clone(),
-                                        &mut replacements,
-                                        |span| span.patch_style(underline_color(Color::Red)),
-                                    );
-                                    let cursors = highlight_matches(
-                                        replacements,
-                                        whole_range,
-                                        &mut cursors,
-                                        |span| {
-                                            span.style(
-                                                Style::new()
-                                                    .fg(Color::Blue)
-                                                    .add_modifier(Modifier::REVERSED),
-                                            )
-                                        },
-                                    );
-                                    Vec::from(cursors).into()
+                let completion_chars = completions[*index].chars().count();
+                let mut replacements = matches
+                    .iter()
+                    .zip(offsets)
+                    .map(|(m, o)| m.range.start + *o..m.range.start + *o + completion_chars)
+                    .collect();
+
+                cursors.retain(|r| r.start != state.cursor);
+
+                EditorLine::iter(rope, viewport_line)
+                    .map(
+                        |EditorLine {
+                             line,
+                             range,
+                             number,
+                         }| {
+                            let whole_range = widen_range(range);
+
+                            let colorized =
+                                colorize(syntax, &mut hlstate, line, current_line == Some(number));
+                            let widened = widen(colorized);
+                            let ranges = highlight_matches(
+                                widened,
+                                whole_range.clone(),
+                                &mut ranges,
+                                |span| span.patch_style(underline_color(Color::Blue)),
+                            );
+                            let replacements = highlight_matches(
+                                ranges,
+                                whole_range.clone(),
+                                &mut replacements,
+                                |span| span.patch_style(underline_color(Color::Red)),
+                            );
+                            let cursors = highlight_matches(
+                                replacements,
+                                whole_range,
+                                &mut cursors,
+                                |span| {
+                                    span.style(
+                                        Style::new()
+                                            .fg(Color::Blue)
+                                            .add_modifier(Modifier::REVERSED),
+                                    )
                                 },
-                            )
-                            .map(|line| widen_tabs(line))
-                            .take(area.height.into())
-                            .collect()
-                    }
-                    Some(EditorMode::AllBuffers {
-                        cursors:
-                            MultiCursors {
-                                matches,
-                                mode:
-                                    MultiCursorMode::Autocomplete {
-                                        offsets,
-                                        completions,
-                                        index,
-                                    },
-                                ..
+                            );
+                            Vec::from(cursors).into()
+                        },
+                    )
+                    .map(widen_whitespace)
+                    .take(area.height.into())
+                    .collect()
+            }
+            Some(EditorMode::AllBuffers {
+                cursors:
+                    MultiCursors {
+                        matches,
+                        mode:
+                            MultiCursorMode::Autocomplete {
+                                offsets,
+                                completions,
+                                index,
                             },
-                    }) if let Some(matches) = matches.get(&self.buffer_idx)
-                        && let Some(offsets) = offsets.get(&self.buffer_idx) =>
-                    {
-                        // We're underlining the multicursors' effective range (in blue),
-                        // the autocompletion replacements (in red)
-                        // *and* the cursors themselves (as a blue block).
-                        // Yes, I know it's a lot.
-
-                        let (mut cursors, mut ranges): (VecDeque<_>, _) = matches
-                            .iter()
-                            .map(|m| (m.cursor..m.cursor + 1, m.range.clone()))
-                            .unzip();
-
-                        let completion_chars = completions[*index].chars().count();
-                        let mut replacements = matches
-                            .iter()
-                            .zip(offsets)
-                            .map(|(m, o)| m.range.start + *o..m.range.start + *o + completion_chars)
-                            .collect();
-
-                        cursors.retain(|r| r.start != state.cursor);
+                        ..
+                    },
+            }) if let Some(matches) = matches.get(&self.buffer_idx)
+                && let Some(offsets) = offsets.get(&self.buffer_idx) =>
+            {
+                // We're underlining the multicursors' effective range (in blue),
+                // the autocompletion replacements (in red)
+                // *and* the cursors themselves (as a blue block).
+                // Yes, I know it's a lot.
 
-                        EditorLine::iter(rope, viewport_line)
-                            .map(
-                                |EditorLine {
-                                     line,
-                                     range,
-                                     number,
-                                 }| {
-                                    let whole_range = widen_range(range);
+                let (mut cursors, mut ranges): (VecDeque<_>, _) = matches
+                    .iter()
+                    .map(|m| (m.cursor..m.cursor + 1, m.range.clone()))
+                    .unzip();
 
-                                    let colorized = colorize(
-                                        syntax,
-                                        &mut hlstate,
-                                        line,
-                                        current_line == Some(number),
-                                    );
-                                    let widened = widen(colorized);
-                                    let ranges = highlight_matches(
-                                        widened,
-                                        whole_range.clone(),
-                                        &mut ranges,
-                                        |span| span.patch_style(underline_color(Color::Blue)),
-                                    );
-                                    let replacements = highlight_matches(
-                                        ranges,
-                                        whole_range.clone(),
-                                        &mut replacements,
-                                        |span| span.patch_style(underline_color(Color::Red)),
-                                    );
-                                    let cursors = highlight_matches(
-                                        replacements,
-                                        whole_range,
-                                        &mut cursors,
-                                        |span| {
-                                            span.style(
-                                                Style::new()
-                                                    .fg(Color::Blue)
-                                                    .add_modifier(Modifier::REVERSED),
-                                            )
-                                        },
-                                    );
-                                    Vec::from(cursors).into()
+                let completion_chars = completions[*index].chars().count();
+                let mut replacements = matches
+                    .iter()
+                    .zip(offsets)
+                    .map(|(m, o)| m.range.start + *o..m.range.start + *o + completion_chars)
+                    .collect();
+
+                cursors.retain(|r| r.start != state.cursor);
+
+                EditorLine::iter(rope, viewport_line)
+                    .map(
+                        |EditorLine {
+                             line,
+                             range,
+                             number,
+                         }| {
+                            let whole_range = widen_range(range);
+
+                            let colorized =
+                                colorize(syntax, &mut hlstate, line, current_line == Some(number));
+                            let widened = widen(colorized);
+                            let ranges = highlight_matches(
+                                widened,
+                                whole_range.clone(),
+                                &mut ranges,
+                                |span| span.patch_style(underline_color(Color::Blue)),
+                            );
+                            let replacements = highlight_matches(
+                                ranges,
+                                whole_range.clone(),
+                                &mut replacements,
+                                |span| span.patch_style(underline_color(Color::Red)),
+                            );
+                            let cursors = highlight_matches(
+                                replacements,
+                                whole_range,
+                                &mut cursors,
+                                |span| {
+                                    span.style(
+                                        Style::new()
+                                            .fg(Color::Blue)
+                                            .add_modifier(Modifier::REVERSED),
+                                    )
                                 },
-                            )
-                            .map(|line| widen_tabs(line))
-                            .take(area.height.into())
-                            .collect()
-                    }
-                    Some(EditorMode::Autocomplete {
-                        offset,
-                        completions,
-                        index,
-                    }) => {
-                        let completion_start = *offset;
-                        let completion_end = *offset + completions[*index].chars().count();
+                            );
+                            Vec::from(cursors).into()
+                        },
+                    )
+                    .map(widen_whitespace)
+                    .take(area.height.into())
+                    .collect()
+            }
+            Some(EditorMode::Autocomplete {
+                offset,
+                completions,
+                index,
+            }) => {
+                let completion_start = *offset;
+                let completion_end = *offset + completions[*index].chars().count();
+
+                EditorLine::iter(rope, viewport_line)
+                    .map(
+                        |EditorLine {
+                             line,
+                             range,
+                             number,
+                         }| {
+                            let colorized =
+                                colorize(syntax, &mut hlstate, line, current_line == Some(number));
+                            let selection = highlight_selection(
+                                colorized,
+                                range.clone(),
+                                (completion_start, completion_end),
+                                |span| span.patch_style(underline_color(Color::Red)),
+                            );
+                            let widened = widen(selection);
+                            let parens = highlight_parens(widened, range, &mut marks);
+                            Vec::from(parens).into()
+                        },
+                    )
+                    .map(widen_whitespace)
+                    .take(area.height.into())
+                    .collect()
+            }
+            Some(EditorMode::Search {
+                search: Search { prompt, type_, .. },
+                range,
+            }) if let Some(find) = search_highlighter(prompt, *type_) => {
+                // only visible lines are searched,
+                // so this can be done on every keystroke
+                EditorLine::iter(rope, viewport_line)
+                    .map(
+                        |EditorLine {
+                             line,
+                             range: line_range,
+                             number,
+                         }| {
+                            let mut found = if range.as_ref().is_none_or(|r| {
+                                (r.start..r.start + r.lines.get()).contains(&number)
+                            }) {
+                                find(line.trim_end_matches('\n'), *line_range.start())
+                            } else {
+                                VecDeque::default()
+                            };
+                            let colorized =
+                                colorize(syntax, &mut hlstate, line, current_line == Some(number));
+                            let found = highlight_matches(
+                                colorized,
+                                line_range.clone(),
+                                &mut found,
+                                |span| span.style(FOUND),
+                            );
+                            let widened = widen(found);
+                            let parens = highlight_parens(widened, line_range, &mut marks);
+                            Vec::from(parens).into()
+                        },
+                    )
+                    .map(widen_whitespace)
+                    .take(area.height.into())
+                    .collect()
+            }
+            _ => {
+                match state.selection {
+                    // no selection, so nothing to highlight
+                    None => EditorLine::iter(rope, viewport_line)
+                        .map(
+                            |EditorLine {
+                                 line,
+                                 range,
+                                 number,
+                             }| {
+                                let colorized = colorize(
+                                    syntax,
+                                    &mut hlstate,
+                                    line,
+                                    current_line == Some(number),
+                                );
+                                let widened = widen(colorized);
+                                let parens = highlight_parens(widened, range, &mut marks);
+                                Vec::from(parens).into()
+                            },
+                        )
+                        .map(widen_whitespace)
+                        .take(area.height.into())
+                        .collect(),
+                    // highlight whole line, no line, or part of the line
+                    Some(selection) => {
+                        let (selection_start, selection_end) = reorder(state.cursor, selection);
 
                         EditorLine::iter(rope, viewport_line)
                             .map(
@@ -6045,92 +8318,96 @@ impl StatefulWidget for BufferWidget<'_> {
                                     let selection = highlight_selection(
                                         colorized,
                                         range.clone(),
-                                        (completion_start, completion_end),
-                                        |span| span.patch_style(underline_color(Color::Red)),
+                                        (selection_start, selection_end),
+                                        |span| span.style(EDITING),
                                     );
                                     let widened = widen(selection);
                                     let parens = highlight_parens(widened, range, &mut marks);
                                     Vec::from(parens).into()
                                 },
                             )
-                            .map(|line| widen_tabs(line))
+                            .map(widen_whitespace)
                             .take(area.height.into())
                             .collect()
                     }
-                    _ => {
-                        match state.selection {
-                            // no selection, so nothing to highlight
-                            None => EditorLine::iter(rope, viewport_line)
-                                .map(
-                                    |EditorLine {
-                                         line,
-                                         range,
-                                         number,
-                                     }| {
-                                        let colorized = colorize(
-                                            syntax,
-                                            &mut hlstate,
-                                            line,
-                                            current_line == Some(number),
-                                        );
-                                        let widened = widen(colorized);
-                                        let parens = highlight_parens(widened, range, &mut marks);
-                                        Vec::from(parens).into()
-                                    },
-                                )
-                                .map(|line| widen_tabs(line))
-                                .take(area.height.into())
-                                .collect(),
-                            // highlight whole line, no line, or part of the line
-                            Some(selection) => {
-                                let (selection_start, selection_end) =
-                                    reorder(state.cursor, selection);
-
-                                EditorLine::iter(rope, viewport_line)
-                                    .map(
-                                        |EditorLine {
-                                             line,
-                                             range,
-                                             number,
-                                         }| {
-                                            let colorized = colorize(
-                                                syntax,
-                                                &mut hlstate,
-                                                line,
-                                                current_line == Some(number),
-                                            );
-                                            let selection = highlight_selection(
-                                                colorized,
-                                                range.clone(),
-                                                (selection_start, selection_end),
-                                                |span| span.style(EDITING),
-                                            );
-                                            let widened = widen(selection);
-                                            let parens =
-                                                highlight_parens(widened, range, &mut marks);
-                                            Vec::from(parens).into()
-                                        },
-                                    )
-                                    .map(|line| widen_tabs(line))
-                                    .take(area.height.into())
-                                    .collect()
-                            }
-                        }
-                    }
-                },
-                state
-                    .cursor_position()
-                    .map(|(_, col)| {
-                        col.saturating_sub(
-                            text_area.width.saturating_sub(Self::RIGHT_MARGIN).into(),
-                        )
-                    })
-                    .unwrap_or(0),
+                }
+            }
+        };
+
+        // onscreen rows taken up by the cursor's line
+        let cursor_rows = match (&wrapped, current_line) {
+            (_, None) => 0..0,
+            (None, Some(line)) => match (line + top_margin).checked_sub(viewport_line) {
+                Some(row) => row..row + 1,
+                None => 0..0,
+            },
+            (Some((rows, skipped, top_rows)), Some(line)) => {
+                let first = rows.iter().position(|r| r.line == line).unwrap_or(0);
+                let count = rows.iter().filter(|r| r.line == line).count();
+                (top_rows + first).saturating_sub(*skipped)
+                    ..(top_rows + first + count).saturating_sub(*skipped)
+            }
+        };
+
+        let (lines, top_rows, bottom_rows) = match wrapped {
+            None => (
+                crate::truncate::lines_start(
+                    lines,
+                    state
+                        .cursor_position()
+                        .map(|(_, col)| {
+                            col.saturating_sub(
+                                lines_area.width.saturating_sub(Self::RIGHT_MARGIN).into(),
+                            )
+                        })
+                        .unwrap_or(0),
+                ),
+                top_margin,
+                bottom_margin,
             ),
-            top_margin,
-            bottom_margin,
-        ))
-        .render(text_area, buf);
+            Some((rows, skipped, top_rows)) => {
+                let lines: Vec<Line<'_>> = lines
+                    .into_iter()
+                    .zip(viewport_line..)
+                    .flat_map(|(line, number)| {
+                        let points = rows
+                            .iter()
+                            .filter(|r| r.line == number)
+                            .map(|r| r.start)
+                            .collect::<Vec<_>>();
+                        let mut rows = crate::truncate::wrap_line(line, &points).into_iter();
+                        rows.next().into_iter().chain(rows.map(|mut row| {
+                            row.spans.insert(0, Span::raw(" ".repeat(WRAP_INDENT)));
+                            row
+                        }))
+                    })
+                    .skip(skipped)
+                    .take(viewport_height.saturating_sub(top_rows))
+                    .collect();
+                let bottom_rows = viewport_height.saturating_sub(top_rows + lines.len());
+                (lines, top_rows, bottom_rows)
+            }
+        };
+
+        Paragraph::new(apply_margins(lines, top_rows, bottom_rows)).render(lines_area, buf);
+
+        // give cursor's line a subtle background,
+        // leaving selections and other highlights alone
+        if *CURSOR_LINE && focused {
+            for row in cursor_rows.filter_map(|row| u16::try_from(row).ok()) {
+                if row >= lines_area.height {
+                    break;
+                }
+                for x in lines_area.left()..lines_area.right() {
+                    if let Some(cell) = buf.cell_mut((x, lines_area.y + row))
+                        && cell.bg == Color::Reset
+                        && !cell.modifier.contains(Modifier::REVERSED)
+                    {
+                        cell.set_bg(CURSOR_LINE_BG);
+                    }
+                }
+            }
+        }
 
         // Render our drop-in Scrollbar replacement on the right
         Scrollbar.render(
@@ -6158,37 +8435,42 @@ impl StatefulWidget for BufferWidget<'_> {
                 }) = self.show_help
                 {
                     use crate::help::{
-                        EDITING_0, EDITING_2, EDITING_3, F10, SWITCH_PANE, ctrl, keybind, none,
-                    };
-                    use crate::key::{
-                        GotoLine, GotoPair, SelectInside, UpdateLines, WidenSelection,
+                        EDITING_0, EDITING_2, EDITING_3, F10, SWITCH_PANE, ctrl, keybind, keybinds,
+                        none,
                     };
+                    use crate::key::Action;
 
                     let mut help = Vec::with_capacity(16);
-                    help.extend(EDITING_0);
-                    help.push(keybind::<GotoLine>(if has_bookmarks {
-                        "Goto Line / Bookmark"
-                    } else {
-                        "Goto Line"
-                    }));
+                    help.extend(EDITING_0.iter().cloned());
+                    help.push(keybind(
+                        Action::GotoLine,
+                        if has_bookmarks {
+                            "Goto Line / Bookmark"
+                        } else {
+                            "Goto Line"
+                        },
+                    ));
                     help.push(find.into());
                     help.extend(
-                        has_selection.then_some(keybind::<UpdateLines>("Update Selected Lines")),
+                        has_selection
+                            .then(|| keybind(Action::UpdateLines, "Update Selected Lines")),
                     );
                     help.extend(
                         matches!(cursor_pos, CursorPos::AtParen)
-                            .then_some(keybind::<GotoPair>("Goto Matching Pair")),
+                            .then(|| keybind(Action::GotoPair, "Goto Matching Pair")),
                     );
-                    help.push(keybind::<SelectInside>("Select Inside Pair"));
-                    help.push(keybind::<WidenSelection>(
+                    help.push(keybind(Action::SelectInside, "Select Inside Pair"));
+                    help.push(keybind(Action::Surround, "Surround With Pair"));
+                    help.push(keybind(
+                        Action::WidenSelection,
                         if matches!(cursor_pos, CursorPos::InWord) {
                             "Select Word"
                         } else {
                             "Widen Selection"
                         },
                     ));
-                    help.push(F10);
-                    help.extend(EDITING_2);
+                    help.push(F10.clone());
+                    help.extend(EDITING_2.iter().cloned());
                     help.extend(has_selection.then_some(ctrl(&["Home"], "Start of Selection")));
                     help.extend(has_selection.then_some(ctrl(&["End"], "End of Selection")));
                     help.push(none(
@@ -6199,11 +8481,18 @@ impl StatefulWidget for BufferWidget<'_> {
                             "Indent Text"
                         },
                     ));
-                    help.extend(EDITING_3);
-                    help.extend(multiple_panes.then_some(SWITCH_PANE));
-                    help.extend(
-                        multiple_buffers.then_some(ctrl(&["]", "PgUp", "PgDn"], "Switch Buffer")),
-                    );
+                    help.extend(EDITING_3.iter().cloned());
+                    help.extend(multiple_panes.then(|| SWITCH_PANE.clone()));
+                    help.extend(multiple_buffers.then(|| {
+                        keybinds(
+                            &[
+                                Action::SelectBuffer,
+                                Action::PreviousBuffer,
+                                Action::NextBuffer,
+                            ],
+                            "Switch Buffer",
+                        )
+                    }));
 
                     crate::help::render_main_help(text_area, buf, &help, |b| {
                         b.title_top("Keybindings").title_bottom(
@@ -6220,7 +8509,7 @@ impl StatefulWidget for BufferWidget<'_> {
                 }
             }
             Some(EditorMode::MarkSet) => {
-                show_sub_help(text_area, buf, MARK_SET);
+                show_sub_help(text_area, buf, &MARK_SET);
             }
             Some(EditorMode::ConfirmClose { .. }) => {
                 show_sub_help(text_area, buf, CONFIRM_CLOSE);
@@ -6241,6 +8530,14 @@ impl StatefulWidget for BufferWidget<'_> {
             Some(EditorMode::SplitPane) => {
                 show_sub_help(text_area, buf, SPLIT_PANE);
             }
+            Some(EditorMode::VerifySaveAs { .. }) => {
+                show_sub_help(text_area, buf, VERIFY_SAVE);
+                render_message(
+                    text_area,
+                    buf,
+                    BufferMessage::Error("File already exists. Really overwrite?".into()),
+                );
+            }
             Some(EditorMode::VerifyReload) => {
                 show_sub_help(text_area, buf, VERIFY_RELOAD);
                 render_message(
@@ -6252,17 +8549,32 @@ impl StatefulWidget for BufferWidget<'_> {
             Some(EditorMode::SelectInside) => {
                 show_sub_help(text_area, buf, SELECT_INSIDE);
             }
+            Some(EditorMode::SurroundWith) => {
+                show_sub_help(text_area, buf, SURROUND_WITH);
+            }
             Some(EditorMode::SelectLine { .. }) => {
                 show_sub_help(
                     text_area,
                     buf,
                     if buffer.has_bookmarks() {
-                        SELECT_LINE_BOOKMARKED
+                        &SELECT_LINE_BOOKMARKED
                     } else {
-                        SELECT_LINE
+                        &SELECT_LINE
                     },
                 );
             }
+            Some(EditorMode::ShellFilter { prompt }) => {
+                show_sub_help(text_area, buf, SHELL_FILTER);
+                render_find_prompt(
+                    FindSyntax::Plain(&crate::syntax::Shell),
+                    text_area,
+                    buf,
+                    prompt,
+                    tab_width,
+                    |s| s,
+                    |b| b.title_top("Filter Through Command"),
+                );
+            }
             Some(
                 EditorMode::Search {
                     search:
@@ -6285,12 +8597,15 @@ impl StatefulWidget for BufferWidget<'_> {
                 show_sub_help(text_area, buf, &find_mode_help(prompt, *type_));
                 render_find_prompt(
                     match type_ {
-                        SearchType::Plain => FindSyntax::Plain(syntax),
+                        SearchType::Plain | SearchType::IgnoreCase | SearchType::WholeWord => {
+                            FindSyntax::Plain(syntax)
+                        }
                         SearchType::Regex => FindSyntax::Regex,
                     },
                     text_area,
                     buf,
                     prompt,
+                    tab_width,
                     |s| s,
                     |b| {
                         let title_top = if matches!(&self.mode, Some(EditorMode::SearchAll { .. }))
@@ -6344,12 +8659,15 @@ impl StatefulWidget for BufferWidget<'_> {
                 show_sub_help(text_area, buf, &find_mode_help(prompt, *type_));
                 render_find_prompt(
                     match type_ {
-                        SearchType::Plain => FindSyntax::Plain(syntax),
+                        SearchType::Plain | SearchType::IgnoreCase | SearchType::WholeWord => {
+                            FindSyntax::Plain(syntax)
+                        }
                         SearchType::Regex => FindSyntax::Regex,
                     },
                     text_area,
                     buf,
                     prompt,
+                    tab_width,
                     |mut colorized| {
                         let mut highlighted = VecDeque::with_capacity(colorized.len());
 
@@ -6406,7 +8724,7 @@ impl StatefulWidget for BufferWidget<'_> {
                         },
                 },
             ) => {
-                show_sub_help(text_area, buf, REPLACE_MATCHES);
+                show_sub_help(text_area, buf, &REPLACE_MATCHES);
             }
             Some(
                 EditorMode::SingleBuffer {
@@ -6425,7 +8743,7 @@ impl StatefulWidget for BufferWidget<'_> {
                         },
                 },
             ) => {
-                show_sub_help(text_area, buf, MULTICURSOR_MARK_SET);
+                show_sub_help(text_area, buf, &MULTICURSOR_MARK_SET);
             }
             Some(
                 EditorMode::SingleBuffer {
@@ -6449,13 +8767,14 @@ impl StatefulWidget for BufferWidget<'_> {
                     buf,
                     PASTE_GROUP
                         .iter()
-                        .copied()
                         .take(*total + 1)
+                        .cloned()
                         .collect::<Vec<_>>()
                         .as_slice(),
                 );
             }
-            Some(EditorMode::Open { .. }) => { /* already handled, above */ }
+            Some(EditorMode::Open { .. } | EditorMode::SaveAs { .. }) => { /* already handled, above */
+            }
             Some(EditorMode::SelectBuffer { buffer_list, index }) => {
                 fn shortcut_letters() -> impl Iterator<Item = char> {
                     ['1', '2', '3', '4', '5', '6', '7', '8', '9', '0']
@@ -6469,7 +8788,7 @@ impl StatefulWidget for BufferWidget<'_> {
                 let list = ratatui::widgets::List::new(
                     buffer_list
                         .iter()
-                        .map(|bid| (bid.to_string(), *bid == selected_buf))
+                        .map(|bid| (bid.list_name(), *bid == selected_buf))
                         .inspect(|(s, _)| {
                             use unicode_width::UnicodeWidthStr;
 
@@ -6492,8 +8811,59 @@ impl StatefulWidget for BufferWidget<'_> {
                         }),
                 );
                 let mut state = ratatui::widgets::ListState::default().with_selected(Some(*index));
-                render_list(text_area, buf, list, &mut state, width);
-                show_sub_help(text_area, buf, SELECT_BUFFER);
+                render_list(text_area, buf, list, &mut state, width, "Buffer".into());
+                show_sub_help(text_area, buf, &SELECT_BUFFER);
+            }
+            Some(EditorMode::SelectSyntax { prompt, index }) => {
+                use unicode_width::UnicodeWidthStr;
+
+                let title = format!("Syntax: {}", prompt.chars().collect::<String>());
+                let names = crate::syntax::syntaxes_matching(&prompt.chars().collect::<String>())
+                    .into_iter()
+                    .map(|syntax| syntax.to_string())
+                    .collect::<Vec<_>>();
+                let width = names
+                    .iter()
+                    .map(|name| name.width())
+                    .chain([title.width()])
+                    .max()
+                    .and_then(|w| u16::try_from(w).ok())
+                    .unwrap_or(u16::MAX);
+                let list = ratatui::widgets::List::new(names);
+                let mut state = ratatui::widgets::ListState::default().with_selected(Some(*index));
+                render_list(text_area, buf, list, &mut state, width, title.into());
+                show_sub_help(text_area, buf, SELECT_SYNTAX);
+            }
+            Some(EditorMode::CommandPalette { prompt, index }) => {
+                use unicode_width::UnicodeWidthStr;
+
+                let title = format!("Command: {}", prompt.chars().collect::<String>());
+                let commands =
+                    crate::editor::commands_matching(&prompt.chars().collect::<String>());
+                let name_width = commands
+                    .iter()
+                    .map(|command| command.name.width())
+                    .max()
+                    .unwrap_or_default();
+                let key_width = commands
+                    .iter()
+                    .map(|command| command.key().width())
+                    .max()
+                    .unwrap_or_default();
+                let width = (name_width + 1 + key_width)
+                    .max(title.width())
+                    .try_into()
+                    .unwrap_or(u16::MAX);
+                let list = ratatui::widgets::List::new(commands.into_iter().map(|command| {
+                    Line::from_iter([
+                        Span::raw(command.name),
+                        Span::raw(" ".repeat(name_width + 1 - command.name.width())),
+                        Span::styled(command.key(), Style::new().reversed()),
+                    ])
+                }));
+                let mut state = ratatui::widgets::ListState::default().with_selected(Some(*index));
+                render_list(text_area, buf, list, &mut state, width, title.into());
+                show_sub_help(text_area, buf, COMMAND_PALETTE);
             }
         }
 
@@ -6535,6 +8905,7 @@ pub fn render_list(
     list: ratatui::widgets::List,
     state: &mut ratatui::widgets::ListState,
     width: u16,
+    title: ratatui::text::Line,
 ) {
     use ratatui::{
         layout::{
@@ -6542,7 +8913,6 @@ pub fn render_list(
             Layout,
         },
         style::Style,
-        text::Line,
         widgets::{Block, BorderType, Clear, Widget},
     };
 
@@ -6555,7 +8925,7 @@ pub fn render_list(
         list.highlight_style(Style::new().reversed()).block(
             Block::bordered()
                 .border_type(BorderType::Rounded)
-                .title_top(Line::from("Buffer").centered()),
+                .title_top(title.centered()),
         ),
         dialog_area,
         buf,
@@ -6586,6 +8956,14 @@ impl EditorCutBuffer {
             Self::Multiple(v) => v.first_mut(),
         }
     }
+
+    /// All the pieces of a multiple cut, one per line
+    pub fn joined(cut: &[CutBuffer]) -> String {
+        cut.iter()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 }
 
 #[derive(Default)]
@@ -6626,6 +9004,89 @@ impl From<String> for CutBuffer {
     }
 }
 
+/// Removes spaces and tabs from the ends of all lines
+fn trim_trailing_whitespace(text: &str) -> String {
+    text.split_inclusive('\n')
+        .map(|line| match line.strip_suffix('\n') {
+            Some(line) => format!("{}\n", line.trim_end_matches([' ', '\t'])),
+            None => line.trim_end_matches([' ', '\t']).to_string(),
+        })
+        .collect()
+}
+
+/// Runs text through a shell command, returning all of its output
+/// along with the first thing it warned about, if anything,
+/// or the first thing it complained about should it fail
+///
+/// Like formatters, commands are only considered to have failed
+/// by their exit status, since many warn about things on success.
+fn filter(command: &str, input: &str) -> Result<(String, Option<String>), String> {
+    let output = pipe(command, input)?;
+
+    if !output.status.success() {
+        return Err(first_error(&output.stderr).unwrap_or_else(|| "Command Failed".to_string()));
+    }
+
+    let mut text = String::from_utf8(output.stdout).map_err(|_| "Output Not UTF-8")?;
+    // commands like sort always end with a newline,
+    // which is unwanted if the selection didn't
+    if !input.ends_with('\n') && text.ends_with('\n') {
+        text.pop();
+    }
+    Ok((text, first_error(&output.stderr)))
+}
+
+/// Runs text through a formatter, returning its formatted output
+fn format_with(command: &str, input: &str) -> Result<String, String> {
+    let output = pipe(command, input)?;
+
+    if !output.status.success() {
+        return Err(first_error(&output.stderr).unwrap_or_else(|| "Formatter Failed".to_string()));
+    }
+
+    String::from_utf8(output.stdout).map_err(|_| "Formatted Output Not UTF-8".to_string())
+}
+
+/// The first non-blank line of a command's error output, if any
+fn first_error(stderr: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .map(|l| l.to_string())
+}
+
+/// Runs a shell command with the given input, collecting all its output
+fn pipe(command: &str, input: &str) -> Result<std::process::Output, String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+
+    let mut child = Command::new(shell)
+        .args([flag, command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| err.to_string())?;
+
+    // input is written from another thread so that a command
+    // which outputs as it reads can't fill its output and block
+    let mut stdin = child.stdin.take().ok_or("Unable to Write to Command")?;
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+        child.wait_with_output()
+    })
+    .map_err(|err| err.to_string())
+}
+
 /// Given rope and starting area in chars,
 /// yields lines and their start points in bytes
 fn search_area<'r>(
@@ -6661,10 +9122,149 @@ fn search_area<'r>(
 
 /// Buffer's undo/redo state
 struct BufferState {
-    rope: ropey::Rope,
+    edit: Edit,
     bookmarks: private::Bookmarks,
 }
 
+/// A change to a rope, as spans of characters replaced with some text
+///
+/// Applying an edit yields the edit which reverts it,
+/// so undo and redo steps turn into each other.
+#[derive(Clone, Default)]
+struct Edit(Vec<Hunk>); // in ascending order, without overlaps
+
+/// A single span of characters replaced with some text
+#[derive(Clone)]
+struct Hunk {
+    offset: usize,    // start of replaced span, in characters
+    removed: usize,   // length of replaced span, in characters
+    inserted: String, // text to replace it with
+}
+
+impl Edit {
+    /// The edit which turns one rope into the other
+    ///
+    /// Ropes cloned from one another share the chunks
+    /// no edit has touched, so only the spans between
+    /// those shared chunks need comparing.
+    fn between(from: &ropey::Rope, to: &ropey::Rope) -> Self {
+        use std::collections::HashMap;
+
+        let mut starts = Vec::new();
+        let mut shared = HashMap::new();
+        let mut start = 0;
+        for chunk in from.chunks() {
+            if !chunk.is_empty() {
+                shared.insert(chunk.as_ptr(), (starts.len(), chunk.len()));
+                starts.push(start);
+            }
+            start += chunk.len();
+        }
+
+        let mut hunks = vec![];
+        let (mut next, mut from_start, mut to_start, mut to_end) = (0, 0, 0, 0);
+        for chunk in to.chunks() {
+            match shared.get(&chunk.as_ptr()) {
+                Some(&(index, len)) if index >= next && len == chunk.len() => {
+                    hunks.extend(Hunk::between(
+                        from,
+                        to,
+                        from_start..starts[index],
+                        to_start..to_end,
+                    ));
+                    next = index + 1;
+                    from_start = starts[index] + len;
+                    to_end += len;
+                    to_start = to_end;
+                }
+                _ => to_end += chunk.len(),
+            }
+        }
+        hunks.extend(Hunk::between(
+            from,
+            to,
+            from_start..from.len_bytes(),
+            to_start..to_end,
+        ));
+
+        Self(hunks)
+    }
+
+    /// Applies edit to rope, returning the edit which reverts it
+    fn apply(self, rope: &mut ropey::Rope) -> Self {
+        let (mut grown, mut shrunk) = (0, 0);
+
+        Self(
+            self.0
+                .into_iter()
+                .map(|hunk| {
+                    let hunk = Hunk {
+                        offset: (hunk.offset + grown).saturating_sub(shrunk),
+                        ..hunk
+                    }
+                    .apply(rope);
+                    grown += hunk.removed;
+                    shrunk += hunk.inserted.chars().count();
+                    hunk
+                })
+                .collect(),
+        )
+    }
+}
+
+impl Hunk {
+    /// The hunk which turns a span of bytes from one rope
+    /// into a span of bytes from the other, if they differ,
+    /// with both spans falling on chunk boundaries
+    fn between(
+        from: &ropey::Rope,
+        to: &ropey::Rope,
+        from_bytes: Range<usize>,
+        to_bytes: Range<usize>,
+    ) -> Option<Self> {
+        let from_span = from.byte_to_char(from_bytes.start)..from.byte_to_char(from_bytes.end);
+        let to_span = to.byte_to_char(to_bytes.start)..to.byte_to_char(to_bytes.end);
+        let (from_slice, to_slice) = (from.slice(from_span.clone()), to.slice(to_span.clone()));
+
+        let prefix = from_slice
+            .chars()
+            .zip(to_slice.chars())
+            .take_while(|(f, t)| f == t)
+            .count();
+        let suffix = from_slice
+            .chars_at(from_slice.len_chars())
+            .reversed()
+            .zip(to_slice.chars_at(to_slice.len_chars()).reversed())
+            .take(from_slice.len_chars().min(to_slice.len_chars()) - prefix)
+            .take_while(|(f, t)| f == t)
+            .count();
+
+        let removed = from_slice.len_chars() - prefix - suffix;
+        let inserted = to_slice.slice(prefix..to_slice.len_chars() - suffix);
+
+        (removed > 0 || inserted.len_chars() > 0).then(|| Self {
+            offset: from_span.start + prefix,
+            removed,
+            inserted: inserted.to_string(),
+        })
+    }
+
+    /// Applies hunk to rope, returning the hunk which reverts it
+    fn apply(self, rope: &mut ropey::Rope) -> Self {
+        let offset = self.offset.min(rope.len_chars());
+        let end = (offset + self.removed).min(rope.len_chars());
+        let removed = rope.slice(offset..end).to_string();
+        rope.remove(offset..end);
+        rope.insert(offset, &self.inserted);
+
+        Self {
+            offset,
+            removed: self.inserted.chars().count(),
+            inserted: removed,
+        }
+    }
+}
+
 /// BufferContext's undo/redo state
 #[derive(Clone)]
 struct BufferContextState {
@@ -6926,6 +9526,40 @@ enum Toggle {
     Removed,  // existing bookmark removed
 }
 
+// columns continuation rows of soft-wrapped lines are indented by
+const WRAP_INDENT: usize = 2;
+
+/// Given a line's text, returns the display columns
+/// at which each of its soft-wrapped rows start
+fn line_wrap_points(text: ropey::RopeSlice<'_>, tab_width: usize, width: usize) -> Vec<usize> {
+    use unicode_width::UnicodeWidthChar;
+
+    let chars = text
+        .chars()
+        .filter(|c| *c != '\n')
+        .flat_map(|c| match c {
+            '\t' => std::iter::repeat_n((c, 1), tab_width),
+            c => std::iter::repeat_n((c, c.width().unwrap_or(0)), 1),
+        })
+        .chain(std::iter::once((' ', 1))); // for cursor at end of line
+
+    crate::truncate::wrap_points(chars, width, WRAP_INDENT)
+}
+
+/// A single onscreen row of a soft-wrapped line
+struct WrappedRow {
+    line: usize,  // line in rope
+    start: usize, // starting display column within line
+}
+
+#[derive(Copy, Clone, Default)]
+enum LineNumbers {
+    #[default]
+    Off, // no line number gutter
+    Absolute, // every line shows its own number
+    Relative, // lines show distance from the cursor's line
+}
+
 #[derive(Default)]
 struct VecFiltered<T>(Vec<T>);
 
@@ -6978,3 +9612,549 @@ pub fn is_grapheme_part(c: char) -> bool {
 fn reorder<T: Ord>(x: T, y: T) -> (T, T) {
     if x <= y { (x, y) } else { (y, x) }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A buffer held only in memory with the given text
+    fn memory(text: &str) -> Buffer {
+        let mut buf = Buffer::open(Source::Memory {
+            name: "Untitled".to_string(),
+        })
+        .unwrap();
+        buf.rope = ropey::Rope::from(text).into();
+        buf
+    }
+
+    /// The syntax of the given name, as listed in the syntax chooser
+    fn syntax(name: &str) -> Box<dyn Highlighter> {
+        crate::syntax::syntaxes_matching(name)
+            .into_iter()
+            .find(|syntax| syntax.to_string() == name)
+            .unwrap()
+    }
+
+    /// A temporary file which is removed once dropped,
+    /// even should the test using it fail
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, text: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("vle-test-{}-{name}", std::process::id()));
+            std::fs::write(&path, text).unwrap();
+            Self(path)
+        }
+
+        fn open(&self) -> Buffer {
+            Buffer::open(Source::Local(self.0.clone())).unwrap()
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    /// The buffer's full contents
+    fn text(context: &BufferContext) -> String {
+        context.buffer.borrow().rope.to_string()
+    }
+
+    #[test]
+    fn comment_open_above_viewport() {
+        let mut buf = memory("int a;\n/* open\nstill\nopen */\nint b;\n");
+        buf.syntax = syntax("C");
+
+        assert!(matches!(buf.highlight_state(2), HighlightState::Commenting));
+        assert!(matches!(buf.highlight_state(3), HighlightState::Commenting));
+        assert!(matches!(buf.highlight_state(4), HighlightState::Normal));
+
+        // closing the comment early recalculates the lines after it
+        buf.rope.get_mut().insert(14, " */");
+        assert!(matches!(buf.highlight_state(2), HighlightState::Normal));
+        assert!(matches!(buf.highlight_state(4), HighlightState::Normal));
+
+        // while opening one above the cached lines does too
+        buf.rope.get_mut().insert(0, "/* ");
+        assert!(matches!(buf.highlight_state(1), HighlightState::Commenting));
+        assert!(matches!(buf.highlight_state(2), HighlightState::Normal));
+    }
+
+    #[test]
+    fn syntax_sets_indentation() {
+        let mut context = BufferContext::from(memory(""));
+        assert!(!context.buffer.borrow().tabs_required);
+
+        // Go is indented with tabs unless configured otherwise
+        context.set_syntax(syntax("Go"));
+        assert!(context.buffer.borrow().tabs_required);
+        context.set_syntax(syntax("Plain"));
+        assert!(!context.buffer.borrow().tabs_required);
+
+        let indent = |spaces_per_tab, always_tab| crate::config::Indent {
+            spaces_per_tab,
+            always_tab,
+        };
+        assert_eq!(
+            indent_with(indent(Some(8), None), &*syntax("Go")),
+            (" ".repeat(8), true)
+        );
+        assert_eq!(
+            indent_with(indent(Some(2), Some(false)), &*syntax("Go")),
+            (" ".repeat(2), false)
+        );
+        assert_eq!(
+            indent_with(indent(None, Some(true)), &*syntax("YAML")),
+            (" ".repeat(*SPACES_PER_TAB), true)
+        );
+    }
+
+    #[test]
+    fn tabs_use_buffer_width() {
+        let mut buf = memory("\tx\n12345678x\n");
+        (buf.tab_substitution, buf.tabs_required) = indent_with(
+            crate::config::Indent {
+                spaces_per_tab: Some(8),
+                always_tab: None,
+            },
+            &*syntax("Go"),
+        );
+
+        let mut context = BufferContext::from(buf);
+        assert_eq!(context.buffer.borrow().tab_width(), 8);
+        context.cursor_end(false);
+        assert_eq!(context.cursor_position(), Some((0, 9)));
+
+        // moving down lands on the same display column
+        context.cursor_down(1, false);
+        assert_eq!(context.cursor_position(), Some((1, 9)));
+        context.cursor_up(1, false);
+        assert_eq!(context.cursor_position(), Some((0, 9)));
+    }
+
+    #[test]
+    fn cursor_column_expands_tabs() {
+        let mut context = BufferContext::from(memory("\tfoo\n"));
+        let tab_width = context.buffer.borrow().tab_width();
+
+        context.set_cursor(1);
+        assert_eq!(context.cursor_position(), Some((0, tab_width)));
+        context.cursor_end(false);
+        assert_eq!(context.cursor_position(), Some((0, tab_width + 3)));
+
+        // the hardware cursor lands after the gutter, on the rendered text
+        let (_, top_margin) = context.viewport(10);
+        let gutter_width = usize::from(context.gutter_width(10));
+        let list = BufferList {
+            buffers: vec![context],
+            current: 0,
+        };
+        assert_eq!(
+            list.cursor_viewport_position(10, 80),
+            Some((top_margin, gutter_width + tab_width + 3))
+        );
+    }
+
+    #[test]
+    fn reload_has_own_undo_step() {
+        let file = TempFile::new("reload.txt", "one\n");
+        let mut context = BufferContext::from(file.open());
+        context.insert_char(vec![], 'x');
+        context.save(vec![]).unwrap();
+
+        std::fs::write(&file.0, "two\n").unwrap();
+        context.reload(vec![]).unwrap();
+        assert_eq!(text(&context), "two\n");
+
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "xone\n");
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "one\n");
+        assert!(context.perform_undo_active().is_err());
+    }
+
+    /// A rope long enough to span many chunks
+    fn long_rope() -> ropey::Rope {
+        (0..20000)
+            .map(|i| format!("line {i} \u{e9}\n"))
+            .collect::<String>()
+            .into()
+    }
+
+    #[test]
+    fn edit_round_trips() {
+        let original = long_rope();
+        let mut edited = original.clone();
+        edited.insert(5, "inserted");
+        edited.remove(1000..1010);
+        edited.insert(50000, "\u{1f600}");
+        edited.remove(edited.len_chars() - 3..edited.len_chars());
+        edited.insert_char(0, 'x');
+
+        let mut rope = edited.clone();
+        let redo = Edit::between(&edited, &original).apply(&mut rope);
+        assert_eq!(rope, original);
+        redo.apply(&mut rope);
+        assert_eq!(rope, edited);
+    }
+
+    #[test]
+    fn edit_between_unrelated_ropes() {
+        let (from, to) = (ropey::Rope::from("abcdef"), ropey::Rope::from("abXdef"));
+        let edit = Edit::between(&from, &to);
+        assert_eq!(edit.0.len(), 1);
+        assert_eq!((edit.0[0].offset, edit.0[0].removed), (2, 1));
+        assert_eq!(edit.0[0].inserted, "X");
+
+        let mut rope = from.clone();
+        edit.apply(&mut rope);
+        assert_eq!(rope, to);
+
+        assert!(Edit::between(&from, &from.clone()).0.is_empty());
+    }
+
+    #[test]
+    fn edit_keeps_far_apart_spans_separate() {
+        let original = long_rope();
+        let mut edited = original.clone();
+        edited.insert_char(edited.len_chars() - 1, 'y');
+        edited.insert_char(1, 'x');
+
+        let edit = Edit::between(&edited, &original);
+        assert_eq!(edit.0.len(), 2);
+        assert!(edit.0.iter().all(|hunk| hunk.inserted.is_empty()));
+
+        let mut rope = edited.clone();
+        edit.apply(&mut rope);
+        assert_eq!(rope, original);
+    }
+
+    #[test]
+    fn undo_redo_sequence() {
+        let mut context = BufferContext::from(memory("alpha\nbeta\n"));
+
+        // typing without moving makes a single step
+        context.insert_char(vec![], 'a');
+        context.insert_char(vec![], 'b');
+        context.set_cursor(9);
+        context.backspace(vec![]);
+        context.set_cursor(11);
+        context.insert_char(vec![], '!');
+        assert_eq!(text(&context), "abalpha\neta!\n");
+
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "abalpha\neta\n");
+        assert_eq!(context.cursor, 11);
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "abalpha\nbeta\n");
+        assert_eq!(context.cursor, 9);
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "alpha\nbeta\n");
+        assert_eq!(context.cursor, 0);
+        assert!(context.perform_undo_active().is_err());
+        assert!(!context.modified());
+
+        assert!(context.perform_redo_active().is_ok());
+        assert_eq!(text(&context), "abalpha\nbeta\n");
+        assert!(context.perform_redo_active().is_ok());
+        assert_eq!(text(&context), "abalpha\neta\n");
+
+        // a fresh edit discards whatever was left to redo
+        context.set_cursor(8);
+        context.insert_char(vec![], '?');
+        assert!(context.perform_redo_active().is_err());
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "abalpha\neta\n");
+        assert!(context.perform_redo_active().is_ok());
+        assert_eq!(text(&context), "abalpha\n?eta\n");
+    }
+
+    #[test]
+    fn typing_undoes_by_word() {
+        let mut context = BufferContext::from(memory(""));
+        "hello world"
+            .chars()
+            .for_each(|c| context.insert_char(vec![], c));
+
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "hello ");
+        assert_eq!(context.cursor, 6);
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "");
+        assert!(context.perform_undo_active().is_err());
+    }
+
+    #[test]
+    fn newline_replaces_selection() {
+        let mut context = BufferContext::from(memory("one two three\n"));
+        context.set_cursor(4);
+        context.selection = Some(8);
+        context.newline(vec![]);
+
+        assert_eq!(text(&context), "one \nthree\n");
+        assert_eq!(context.cursor, 5);
+        assert_eq!(context.selection, None);
+
+        // removing the selection and breaking the line undo together
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), "one two three\n");
+        assert!(context.perform_undo_active().is_err());
+    }
+
+    #[test]
+    fn multi_cursor_undo() {
+        let rope = long_rope();
+        let end = rope.len_chars() - 1;
+        let mut context = BufferContext::from(memory(&rope.to_string()));
+        let mut matches = [0, end].map(|pos| MultiCursor {
+            range: pos..pos,
+            cursor: pos,
+            selection: None,
+            groups: vec![],
+        });
+
+        context.multi_insert_char(vec![], &mut matches, 'x');
+        context.multi_insert_char(vec![], &mut matches, 'y');
+        assert_eq!(text(&context).matches("xy").count(), 2);
+
+        // both cursors' edits are kept apart in the undo step
+        {
+            let mut buf = context.buffer.borrow_mut();
+            buf.log_undo();
+            let edit = &buf.undo.last().unwrap().edit;
+            assert_eq!(edit.0.len(), 2);
+            assert!(edit.0.iter().all(|hunk| hunk.removed == 2));
+        }
+
+        assert!(context.perform_undo_active().is_ok());
+        assert_eq!(text(&context), rope.to_string());
+        assert!(context.perform_redo_active().is_ok());
+        assert_eq!(text(&context).matches("xy").count(), 2);
+    }
+
+    #[test]
+    fn save_keeps_missing_final_newline() {
+        let file = TempFile::new("newline.txt", "no newline");
+        let mut context = BufferContext::from(file.open());
+        context.set_cursor(2);
+        context.insert_char(vec![], ',');
+        context.save(vec![]).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&file.0).unwrap(), "no, newline");
+    }
+
+    #[test]
+    fn trims_only_trailing_whitespace() {
+        assert_eq!(
+            trim_trailing_whitespace("\ta \t\n  \nb  c\t"),
+            "\ta\n\nb  c"
+        );
+        assert_eq!(trim_trailing_whitespace("\n\n"), "\n\n");
+    }
+
+    #[test]
+    fn undo_stays_within_limit() {
+        let mut context = BufferContext::from(memory(""));
+
+        let steps = *UNDO_LIMIT + 5;
+        for _ in 0..steps {
+            context.insert_char(vec![], 'a');
+            context.buffer.borrow_mut().finish_undo();
+        }
+        assert_eq!(context.buffer.borrow().undo.len(), *UNDO_LIMIT);
+        assert_eq!(context.undo.len(), *UNDO_LIMIT);
+
+        // the oldest steps are the ones dropped
+        for undone in 1..=*UNDO_LIMIT {
+            assert!(context.perform_undo_active().is_ok());
+            assert_eq!(context.cursor, steps - undone);
+        }
+        assert_eq!(text(&context), "a".repeat(5));
+        assert!(context.perform_undo_active().is_err());
+        assert_eq!(context.buffer.borrow().undo.len(), context.undo.len());
+    }
+
+    #[test]
+    fn search_ignores_case() {
+        let mut context = BufferContext::from(memory("bar foo\nFOO\n"));
+        let Ok((idx, matches)) = context.all_matches(None, IgnoreCase("Foo".into())) else {
+            panic!("no matches found");
+        };
+        assert_eq!(idx, 0);
+        assert_eq!(
+            matches.iter().map(|m| m.range.clone()).collect::<Vec<_>>(),
+            [4..7, 8..11]
+        );
+        assert_eq!(context.cursor, 7);
+
+        assert!(context.all_matches(None, "Foo".to_string()).is_err());
+    }
+
+    #[test]
+    fn regex_search_captures_groups() {
+        let mut context = BufferContext::from(memory("x = 1;\ny = 22;\n"));
+        let regex = r"(\w) = (\d+)".parse::<fancy_regex::Regex>().unwrap();
+        let Ok((_, matches)) = context.all_matches(None, regex) else {
+            panic!("no matches found");
+        };
+        assert_eq!(
+            matches.iter().map(|m| m.range.clone()).collect::<Vec<_>>(),
+            [0..5, 7..13]
+        );
+        assert_eq!(matches[1].groups, ["y = 22", "y", "22"]);
+        assert_eq!(matches[1].paste_group_count().map(NonZero::get), Some(3));
+
+        // a malformed pattern is an error for the prompt to show, not a panic
+        assert!("(".parse::<fancy_regex::Regex>().is_err());
+    }
+
+    #[test]
+    fn rope_matches_cross_chunks() {
+        let rope = long_rope();
+        let whole = rope.to_string();
+        let ranges = |matches: Vec<SearchMatch>| {
+            matches
+                .into_iter()
+                .map(|m| m.start..m.end)
+                .collect::<Vec<_>>()
+        };
+
+        // byte offsets where one chunk ends and the next begins
+        let mut seams = vec![];
+        rope.chunks().fold(0, |offset, chunk| {
+            seams.push(offset);
+            offset + chunk.len()
+        });
+
+        for term in ["\u{e9}\nline 1", "9 \u{e9}\nl", "e", "line 12345 "] {
+            let term = term.to_string();
+            assert_eq!(
+                ranges(term.rope_match_ranges(rope.slice(..))),
+                ranges(term.match_ranges(&whole).collect()),
+                "{term:?}"
+            );
+        }
+
+        // and the test does exercise matches split between chunks
+        let term = "\u{e9}\nline ".to_string();
+        assert!(
+            ranges(term.rope_match_ranges(rope.slice(..)))
+                .iter()
+                .any(|m| seams.iter().any(|seam| m.start < *seam && *seam < m.end))
+        );
+
+        // overlapping candidates match just as str::match_indices does
+        let rope = ropey::Rope::from("a".repeat(5000));
+        let term = "aaa".to_string();
+        assert_eq!(
+            ranges(term.rope_match_ranges(rope.slice(..))),
+            ranges(term.match_ranges(&rope.to_string()).collect())
+        );
+    }
+
+    #[test]
+    fn search_sees_same_length_edit() {
+        let mut context = BufferContext::from(memory("cat\n"));
+        assert!(context.all_matches(None, "cat".to_string()).is_ok());
+
+        context.set_cursor(1);
+        context.backspace(vec![]);
+        context.insert_char(vec![], 'b');
+        assert_eq!(text(&context), "bat\n");
+
+        assert!(context.all_matches(None, "cat".to_string()).is_err());
+        assert!(context.all_matches(None, "bat".to_string()).is_ok());
+        assert!(
+            context
+                .all_multiline_matches(None, "cat".to_string())
+                .is_err()
+        );
+        assert!(
+            context
+                .all_multiline_matches(None, "bat\n".to_string())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn drag_selects_text() {
+        let mut context = BufferContext::from(memory("one two three\nfour\n"));
+        let area = Rect::new(0, 0, 40, 12);
+
+        // the text area sits within the border and past the gutter
+        let height = usize::from(area.height - 2);
+        let (_, top_margin) = context.viewport(height);
+        let x = 1 + context.gutter_width(height);
+        let y = 1 + top_margin as u16;
+
+        context.set_cursor_focus(area, Position { x: x + 4, y }, false);
+        assert_eq!(context.cursor, 4);
+        assert_eq!(context.selection, None);
+        context.set_cursor_focus(area, Position { x: x + 6, y }, true);
+        context.set_cursor_focus(area, Position { x: x + 7, y }, true);
+        assert_eq!(context.cursor, 7);
+
+        let selected = context.get_selection().unwrap();
+        assert_eq!(selected.data, "two");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn filter_fails_by_exit_status() {
+        assert_eq!(filter("sort", "b\na"), Ok(("a\nb".into(), None)));
+        assert_eq!(
+            filter("echo careful >&2; tr a-z A-Z", "ab\n"),
+            Ok(("AB\n".into(), Some("careful".into())))
+        );
+        assert_eq!(
+            filter("echo \"bad input\" >&2; exit 1", ""),
+            Err("bad input".into())
+        );
+        assert_eq!(filter("exit 3", ""), Err("Command Failed".into()));
+    }
+
+    #[test]
+    fn moved_lines_stay_selected() {
+        for (source, moved) in [
+            ("one\ntwo\nthree\n", "two\nthree\none\n"),
+            ("one\ntwo\nthree", "two\nthree\none"),
+        ] {
+            // selecting through to the very end of the buffer
+            let mut context = BufferContext::from(memory(source));
+            context.set_cursor(4);
+            context.selection = Some(source.len());
+            context.move_lines_up(vec![]);
+
+            assert_eq!(text(&context), moved);
+            assert_eq!((context.cursor, context.selection), (0, Some(9)));
+
+            // so only the moved lines move back down
+            context.move_lines_down(vec![]);
+            assert_eq!(text(&context), "one\ntwo\nthree\n"[..source.len()]);
+            assert_eq!((context.cursor, context.selection), (4, Some(13)));
+        }
+    }
+
+    #[test]
+    fn wrapped_lines_move_by_row() {
+        let mut context = BufferContext::from(memory("aaaa bbbb cccc\ndd\n"));
+        context.toggle_wrap();
+        context.wrap_width.set(6);
+        context.set_cursor(1);
+
+        // rows start at "aaaa ", "bbbb " and "cccc", the latter two indented
+        context.cursor_down(1, false);
+        assert_eq!(context.cursor, 5);
+        context.cursor_down(1, false);
+        assert_eq!(context.cursor, 10);
+        // returning to the original column once past the indent
+        context.cursor_down(1, false);
+        assert_eq!(context.cursor, 16);
+        context.cursor_up(2, false);
+        assert_eq!(context.cursor, 5);
+        context.cursor_up(1, false);
+        assert_eq!(context.cursor, 1);
+    }
+}
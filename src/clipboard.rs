@@ -0,0 +1,127 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[cfg(feature = "clipboard")]
+use std::sync::Mutex;
+
+/// System clipboard's contents from just before we last copied to it,
+/// which are left in place if the terminal ignored our copy
+#[cfg(feature = "clipboard")]
+static STALE: Mutex<Option<String>> = Mutex::new(None);
+
+/// The text we last copied to the system clipboard
+#[cfg(feature = "clipboard")]
+static PUSHED: Mutex<Option<String>> = Mutex::new(None);
+
+/// System clipboard's contents as of when we last read it,
+/// or None if it's yet to be read
+#[cfg(feature = "clipboard")]
+static LAST_READ: Mutex<Option<Option<String>>> = Mutex::new(None);
+
+/// How long to wait on the clipboard tool before giving up on it
+#[cfg(feature = "clipboard")]
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Copies text to the system clipboard via the terminal,
+/// which works over SSH as well as locally
+///
+/// Rather than reading the system clipboard on every copy,
+/// its contents are taken as whatever was last read from it,
+/// since only pasting needs to read it afresh.
+#[cfg(feature = "clipboard")]
+pub fn copy(text: &str) {
+    use crossterm::{clipboard::CopyToClipboard, execute};
+
+    let last_read = LAST_READ.lock().ok().and_then(|last| last.clone());
+    if let Ok(mut stale) = STALE.lock() {
+        *stale = last_read.unwrap_or_else(read);
+    }
+    if let Ok(mut pushed) = PUSHED.lock() {
+        *pushed = Some(text.to_string());
+    }
+    let _ = execute!(std::io::stdout(), CopyToClipboard::to_clipboard_from(text));
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn copy(_text: &str) {}
+
+/// Returns the system clipboard's contents if something else
+/// has copied to it since we last did
+#[cfg(feature = "clipboard")]
+pub fn newer() -> Option<String> {
+    let current = read()?;
+    (PUSHED.lock().ok()?.as_ref() != Some(&current)
+        && STALE.lock().ok()?.as_ref() != Some(&current))
+    .then_some(current)
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub fn newer() -> Option<String> {
+    None
+}
+
+/// Reads the system clipboard with whatever tool the platform provides,
+/// if there's a clipboard to be read at all,
+/// and remembers what was read
+#[cfg(feature = "clipboard")]
+fn read() -> Option<String> {
+    let read = run_reader();
+    if let Ok(mut last) = LAST_READ.lock() {
+        *last = Some(read.clone());
+    }
+    read
+}
+
+/// Runs the platform's clipboard tool for its output,
+/// killing it should it not finish within READ_TIMEOUT
+#[cfg(feature = "clipboard")]
+fn run_reader() -> Option<String> {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+
+    let (program, args): (&str, &[&str]) = if cfg!(target_os = "macos") {
+        ("pbpaste", &[])
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        ("wl-paste", &["--no-newline"])
+    } else if std::env::var_os("DISPLAY").is_some() {
+        ("xclip", &["-selection", "clipboard", "-o"])
+    } else {
+        return None;
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // read output on its own thread so a stalled tool can't stall us
+    let mut stdout = child.stdout.take()?;
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut output = vec![];
+        let _ = sender.send(stdout.read_to_end(&mut output).map(|_| output));
+    });
+
+    match receiver.recv_timeout(READ_TIMEOUT) {
+        Ok(Ok(output)) => child
+            .wait()
+            .ok()?
+            .success()
+            .then(|| String::from_utf8(output).ok())
+            .flatten()
+            .filter(|text| !text.is_empty()),
+        Ok(Err(_)) | Err(_) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            None
+        }
+    }
+}
@@ -0,0 +1,393 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::key::{Action, Shortcut};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+/// Configuration loaded from the config file at startup
+///
+/// Any environmental variables take precedence over these,
+/// and anything unset in either falls back to its default.
+pub static CONFIG: LazyLock<Config> = LazyLock::new(|| {
+    let Some(path) = config_path() else {
+        return Config::default();
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(ron) => Config::parse(&ron).unwrap_or_else(|err| {
+            eprintln!("* {}: {err}", path.display());
+            Config::default()
+        }),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Config::default(),
+        Err(err) => {
+            eprintln!("* {}: {err}", path.display());
+            Config::default()
+        }
+    }
+});
+
+/// Either $VLE_CONFIG or the default config file location
+fn config_path() -> Option<PathBuf> {
+    std::env::var_os("VLE_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| {
+                    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+                })
+                .map(|config| config.join("vle").join("config.ron"))
+        })
+}
+
+#[derive(Default)]
+pub struct Config {
+    pub spaces_per_tab: Option<usize>,      // number of spaces per tab
+    pub always_tab: Option<bool>,           // whether to insert literal tabs
+    pub trim_trailing: Option<bool>,        // whether to trim trailing whitespace on save
+    pub final_newline: Option<bool>,        // whether to ensure files end in a newline
+    pub scrolloff: Option<usize>,           // lines of context before view scrolls
+    pub undo_limit: Option<usize>,          // most undo steps to keep per file
+    pub theme: Option<PathBuf>,             // path to syntax highlighting theme file
+    pub session: Option<bool>,              // whether to save the session on quit
+    pub keys: Vec<(Action, Vec<Shortcut>)>, // actions rebound to other keys
+    pub indent: Vec<(String, Indent)>,      // indentation by syntax name
+    pub format: Vec<(String, String)>,      // formatter commands by syntax name
+}
+
+/// Indentation settings for a particular syntax
+#[derive(Copy, Clone, Default)]
+pub struct Indent {
+    pub spaces_per_tab: Option<usize>, // number of spaces per tab
+    pub always_tab: Option<bool>,      // whether to insert literal tabs
+}
+
+impl Indent {
+    fn parse(ron: &str) -> Result<Self, InvalidRon> {
+        let mut indent = Self::default();
+
+        for (name, value) in fields(ron)? {
+            match name {
+                "spaces_per_tab" => indent.spaces_per_tab = Some(parse_value(name, value)?),
+                "always_tab" => indent.always_tab = Some(parse_value(name, value)?),
+                _ => return Err(InvalidRon(format!("unknown indent field {name}"))),
+            }
+        }
+
+        Ok(indent)
+    }
+}
+
+impl Config {
+    /// Parses config from a RON struct, like:
+    ///
+    /// (spaces_per_tab: 2, final_newline: true, theme: "theme.ron")
+    ///
+    /// where omitted items keep their defaults
+    /// and keys are rebound with a nested struct, like:
+    ///
+    /// (keys: (save: "ctrl-w", find: ["ctrl-f", "f5"]))
+    ///
+    /// and indentation is set per syntax with a map, like:
+    ///
+    /// (indent: {"YAML": (spaces_per_tab: 2), "Go": (always_tab: true)})
+    ///
+    /// and formatters to run on save are set per syntax with a map, like:
+    ///
+    /// (format: {"Rust": "rustfmt --edition 2024", "Go": "gofmt"})
+    fn parse(ron: &str) -> Result<Self, InvalidRon> {
+        let mut config = Self::default();
+
+        for (name, value) in fields(&strip_comments(ron))? {
+            match name {
+                "spaces_per_tab" => config.spaces_per_tab = Some(parse_value(name, value)?),
+                "always_tab" => config.always_tab = Some(parse_value(name, value)?),
+                "trim_trailing" => config.trim_trailing = Some(parse_value(name, value)?),
+                "final_newline" => config.final_newline = Some(parse_value(name, value)?),
+                "scrolloff" => config.scrolloff = Some(parse_value(name, value)?),
+                "undo_limit" => config.undo_limit = Some(parse_value(name, value)?),
+                "session" => config.session = Some(parse_value(name, value)?),
+                "theme" => {
+                    config.theme = Some(unquote(value).map(PathBuf::from).ok_or_else(|| {
+                        InvalidRon(format!("expected quoted path for theme, got {value}"))
+                    })?)
+                }
+                "keys" => {
+                    for (name, value) in fields(value)? {
+                        config.keys.push((
+                            name.parse()
+                                .map_err(|_| InvalidRon(format!("unknown action {name}")))?,
+                            shortcuts(value)?,
+                        ));
+                    }
+                }
+                "indent" => {
+                    for (name, value) in map(value)? {
+                        config.indent.push((name, Indent::parse(value)?));
+                    }
+                }
+                "format" => {
+                    for (name, value) in map(value)? {
+                        config.format.push((
+                            name,
+                            unquote(value).ok_or_else(|| {
+                                InvalidRon(format!("expected quoted command, got {value}"))
+                            })?,
+                        ));
+                    }
+                }
+                _ => return Err(InvalidRon(format!("unknown field {name}"))),
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Indentation settings for the named syntax, if any
+    pub fn indent(&self, syntax: &str) -> Indent {
+        self.indent
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(syntax))
+            .map(|(_, indent)| *indent)
+            .unwrap_or_default()
+    }
+
+    /// Command to format text of the named syntax with, if any
+    pub fn formatter(&self, syntax: &str) -> Option<&str> {
+        self.format
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(syntax))
+            .map(|(_, command)| command.as_str())
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, InvalidRon> {
+    value
+        .parse()
+        .map_err(|_| InvalidRon(format!("invalid value for {name}: {value}")))
+}
+
+/// Splits "(name: value, name: value)" into its names and values,
+/// where values may themselves contain nested structs, maps or lists
+pub fn fields(ron: &str) -> Result<Vec<(&str, &str)>, InvalidRon> {
+    entries(ron, '(', ')')
+}
+
+/// Splits "{"name": value, "name": value}" into its unquoted names and values
+fn map(ron: &str) -> Result<Vec<(String, &str)>, InvalidRon> {
+    entries(ron, '{', '}')?
+        .into_iter()
+        .map(|(name, value)| {
+            unquote(name)
+                .map(|n| (n, value))
+                .ok_or_else(|| InvalidRon(format!("expected quoted name, got {name}")))
+        })
+        .collect()
+}
+
+fn entries(ron: &str, open: char, close: char) -> Result<Vec<(&str, &str)>, InvalidRon> {
+    items(ron, open, close)?
+        .into_iter()
+        .map(|item| {
+            unquoted(item)
+                .find(|(_, c)| *c == ':')
+                .map(|(i, _)| (item[..i].trim(), item[i + 1..].trim()))
+                .ok_or_else(|| InvalidRon(format!("expected name: value, got {item}")))
+        })
+        .collect()
+}
+
+/// Iterates over the characters of some RON, along with their positions,
+/// skipping over any within quoted strings
+fn unquoted(ron: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let mut quoted = false;
+    let mut escaped = false;
+    ron.char_indices().filter(move |(_, c)| match c {
+        _ if escaped => {
+            escaped = false;
+            false
+        }
+        '\\' if quoted => {
+            escaped = true;
+            false
+        }
+        '"' => {
+            quoted = !quoted;
+            false
+        }
+        _ => !quoted,
+    })
+}
+
+/// Removes any // comments running to the end of their lines,
+/// leaving any within quoted strings alone
+pub fn strip_comments(ron: &str) -> String {
+    ron.lines()
+        .map(|line| {
+            match unquoted(line).find(|(i, c)| *c == '/' && line[i + 1..].starts_with('/')) {
+                Some((i, _)) => &line[..i],
+                None => line,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Splits the items between the outermost delimiters, like "[a, b, c]",
+/// where items may themselves contain nested structs, maps or lists
+pub fn items(ron: &str, open: char, close: char) -> Result<Vec<&str>, InvalidRon> {
+    let items = ron
+        .split_once(open)
+        .and_then(|(_, items)| items.rsplit_once(close))
+        .map(|(items, _)| items)
+        .ok_or_else(|| InvalidRon(format!("expected items within {open}{close}")))?;
+
+    let mut split = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in unquoted(items) {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| InvalidRon(format!("unexpected {c}")))?
+            }
+            ',' if depth == 0 => {
+                split.push(items[start..i].trim());
+                start = i + 1;
+            }
+            _ => { /* continue */ }
+        }
+    }
+    if depth > 0 {
+        return Err(InvalidRon(format!("unclosed items within {open}{close}")));
+    }
+    split.push(items[start..].trim());
+    split.retain(|item| !item.is_empty());
+
+    Ok(split)
+}
+
+/// Removes quotes from a string, along with any escapes within it
+pub fn unquote(ron: &str) -> Option<String> {
+    let mut unquoted = String::new();
+    let mut chars = ron.trim().strip_prefix('"')?.strip_suffix('"')?.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+    Some(unquoted)
+}
+
+/// Parses either a single quoted shortcut or a list of them
+fn shortcuts(value: &str) -> Result<Vec<Shortcut>, InvalidRon> {
+    fn shortcut(value: &str) -> Result<Shortcut, InvalidRon> {
+        unquote(value)
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| InvalidRon(format!("invalid key {}", value.trim())))
+    }
+
+    match value.starts_with('[') {
+        true => items(value, '[', ']')?.into_iter().map(shortcut).collect(),
+        false => shortcut(value).map(|s| vec![s]),
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidRon(pub String);
+
+impl std::error::Error for InvalidRon {}
+
+impl std::fmt::Display for InvalidRon {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_are_stripped() {
+        let config = Config::parse(
+            "// leading comment\n\
+             (\n\
+             \x20   spaces_per_tab: 2, // trailing comment\n\
+             \x20   // commented: out,\n\
+             \x20   theme: \"/themes//dark.ron\", // kept within quotes\n\
+             )",
+        )
+        .unwrap();
+        assert_eq!(config.spaces_per_tab, Some(2));
+        assert_eq!(config.theme, Some(PathBuf::from("/themes//dark.ron")));
+    }
+
+    #[test]
+    fn nested_maps() {
+        let config = Config::parse(
+            "(indent: {\"YAML\": (spaces_per_tab: 2), \"Make\\\"file\": (always_tab: true)},\
+              format: {\"Go\": \"gofmt\"},\
+              keys: (save: \"ctrl-w\", find: [\"ctrl-f\", \"f5\"]))",
+        )
+        .unwrap();
+        assert_eq!(config.indent.len(), 2);
+        assert_eq!(config.indent("yaml").spaces_per_tab, Some(2));
+        assert_eq!(config.indent("Make\"file").always_tab, Some(true));
+        assert_eq!(config.formatter("Go"), Some("gofmt"));
+        assert_eq!(config.keys.len(), 2);
+        assert_eq!(config.keys[0].0, Action::Save);
+        assert_eq!(config.keys[1].0, Action::Find);
+        assert_eq!(
+            config.keys[1]
+                .1
+                .iter()
+                .map(|s| s.label.as_str())
+                .collect::<Vec<_>>(),
+            ["Ctrl-F", "F5"]
+        );
+    }
+
+    #[test]
+    fn quoted_commas() {
+        let config = Config::parse(
+            "(format: {\"CSV, sorted\": \"sort -t, -k2: (x)\"}, theme: \"a,b: c.ron\")",
+        )
+        .unwrap();
+        assert_eq!(config.formatter("CSV, sorted"), Some("sort -t, -k2: (x)"));
+        assert_eq!(config.theme, Some(PathBuf::from("a,b: c.ron")));
+        assert_eq!(
+            items("[\"a,b\", \"c\\\",d\", e]", '[', ']').unwrap().len(),
+            3
+        );
+    }
+
+    #[test]
+    fn bad_input() {
+        for ron in [
+            "spaces_per_tab: 2",
+            "(spaces_per_tab: 2",
+            "(spaces_per_tab: two)",
+            "(spaces_per_tab 2)",
+            "(unknown: 1)",
+            "(theme: theme.ron)",
+            "(keys: (save: \"ctrl-\"))",
+            "(keys: (frobnicate: \"ctrl-w\"))",
+            "(indent: {YAML: (spaces_per_tab: 2)})",
+            "(indent: {\"YAML\": (tabs: 2)})",
+            "(format: {\"Go\": gofmt})",
+            "(keys: (find: [\"ctrl-f\", \"f5\"))",
+        ] {
+            assert!(Config::parse(ron).is_err(), "{ron} parsed");
+        }
+    }
+}
@@ -10,22 +10,22 @@
 use crate::files::LocalSource;
 #[cfg(feature = "ssh")]
 use crate::files::{EitherSource, SshSource};
-use crate::key;
 use crate::{
     buffer::{
-        AltCursor, BufferContext, BufferId, BufferList, EditorCutBuffer, MultiCursor,
-        SelectionRange, Source,
+        AltCursor, BufferContext, BufferId, BufferList, BufferOffsets, Case, EditorCutBuffer,
+        MultiCursor, SelectionRange, Source,
     },
     files::{ChooserSource, FileChooserState},
-    key::{Binding, CtrlBinding},
+    key::{Action, KEYMAP},
     prompt::{LinePrompt, TextField},
+    session::Session,
 };
 use crossterm::event::Event;
 use ratatui::{
     layout::{Position, Rect},
     widgets::StatefulWidget,
 };
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 #[cfg(feature = "ssh")]
 use std::rc::Rc;
@@ -39,6 +39,14 @@ static PAGE_SIZE: LazyLock<usize> = LazyLock::new(|| {
         .unwrap_or(25)
 });
 
+static REFLOW_WIDTH: LazyLock<usize> = LazyLock::new(|| {
+    std::env::var("VLE_REFLOW_WIDTH")
+        .ok()
+        .and_then(|s| s.parse::<usize>().ok())
+        .filter(|s| *s > 0)
+        .unwrap_or(80)
+});
+
 type DirMap = fn(Direction) -> Option<&'static [&'static str]>;
 
 // External terminal multiplexer integration
@@ -77,12 +85,16 @@ pub enum EditorMode {
     VerifySave,
     /// Verifying a reload over a dirty buffer
     VerifyReload,
+    /// Verifying a save over some existing file
+    VerifySaveAs { source: Source },
     /// Querying which direction to split the pane
     SplitPane,
     /// Verifying whether to close dirty buffer
     ConfirmClose { buffer: BufferId },
     /// Querying for what to select inside of
     SelectInside,
+    /// Querying for what to surround selection with
+    SurroundWith,
     /// Querying for which line to select
     SelectLine { prompt: LinePrompt },
     /// Querying for what text to search for
@@ -99,7 +111,7 @@ pub enum EditorMode {
     },
     /// Multi-cursor operation on multiple buffers
     AllBuffers {
-        cursors: MultiCursors<BTreeMap<usize, Vec<MultiCursor>>, BTreeMap<usize, Vec<usize>>>,
+        cursors: MultiCursors<BTreeMap<usize, Vec<MultiCursor>>, BufferOffsets>,
     },
     /// Opening a new file
     Open {
@@ -108,12 +120,25 @@ pub enum EditorMode {
         #[cfg(feature = "ssh")]
         chooser: Box<FileChooserState<EitherSource>>,
     },
+    /// Choosing a new file to save buffer to
+    SaveAs {
+        #[cfg(not(feature = "ssh"))]
+        chooser: Box<FileChooserState<LocalSource>>,
+        #[cfg(feature = "ssh")]
+        chooser: Box<FileChooserState<EitherSource>>,
+    },
     /// Performing autocomplete on a partial word
     Autocomplete {
         offset: usize,            // our character offset in rope
         completions: Vec<String>, // autocompletion candidates
         index: usize,             // the current candidate
     },
+    /// Querying for which syntax to highlight buffer with
+    SelectSyntax { prompt: TextField, index: usize },
+    /// Querying for which command to perform
+    CommandPalette { prompt: TextField, index: usize },
+    /// Querying for which command to filter selection through
+    ShellFilter { prompt: TextField },
     /// Determining what buffer to select from menu
     SelectBuffer {
         buffer_list: Vec<BufferId>, // buffers
@@ -178,47 +203,42 @@ pub enum MultiCursorMode<O> {
 pub enum SearchType {
     #[default]
     Plain,
+    IgnoreCase,
+    WholeWord,
     Regex,
 }
 
+impl SearchType {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Plain => Self::IgnoreCase,
+            Self::IgnoreCase => Self::WholeWord,
+            Self::WholeWord => Self::Regex,
+            Self::Regex => Self::Plain,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            Self::Plain => Self::Regex,
+            Self::IgnoreCase => Self::Plain,
+            Self::WholeWord => Self::IgnoreCase,
+            Self::Regex => Self::WholeWord,
+        }
+    }
+}
+
 impl std::fmt::Display for SearchType {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             Self::Plain => "Find".fmt(f),
+            Self::IgnoreCase => "Find Ignoring Case".fmt(f),
+            Self::WholeWord => "Find Whole Word".fmt(f),
             Self::Regex => "Find Regex".fmt(f),
         }
     }
 }
 
-macro_rules! keybind {
-    ($bind:ident) => {
-        Event::Key(
-            KeyEvent {
-                code: key::$bind::PRIMARY_KEY,
-                modifiers: KeyModifiers::CONTROL,
-                kind: KeyEventKind::Press,
-                ..
-            } | KeyEvent {
-                code: key::$bind::SECONDARY_KEY,
-                modifiers: KeyModifiers::NONE,
-                kind: KeyEventKind::Press,
-                ..
-            },
-        )
-    };
-}
-
-macro_rules! ctrl_keybind {
-    ($bind:ident) => {
-        Event::Key(KeyEvent {
-            code: key::$bind::KEY,
-            modifiers: KeyModifiers::CONTROL,
-            kind: KeyEventKind::Press,
-            ..
-        })
-    };
-}
-
 macro_rules! key {
     ($code:ident) => {
         Event::Key(KeyEvent {
@@ -262,9 +282,47 @@ macro_rules! key {
     };
 }
 
+/// Cursor positions from before large jumps,
+/// which can be stepped back and forth through
+#[derive(Default)]
+struct JumpList {
+    back: std::collections::VecDeque<(BufferId, usize)>, // positions jumped from
+    forward: Vec<(BufferId, usize)>,                     // positions jumped back from
+    pending: Option<(BufferId, usize)>,                  // position a jump is underway from
+}
+
+impl JumpList {
+    /// The most positions to remember
+    const LIMIT: usize = 100;
+
+    /// Records position before a jump,
+    /// discarding any positions we'd stepped back from
+    fn push(&mut self, position: (BufferId, usize)) {
+        self.forward.clear();
+        if self.back.back() != Some(&position) {
+            if self.back.len() >= Self::LIMIT {
+                self.back.pop_front();
+            }
+            self.back.push_back(position);
+        }
+    }
+
+    /// Records position a jump was underway from,
+    /// provided the jump actually moved somewhere else
+    fn settle(&mut self, current: Option<(BufferId, usize)>) {
+        if let Some(position) = self.pending.take()
+            && current.as_ref() != Some(&position)
+        {
+            self.push(position);
+        }
+    }
+}
+
 #[derive(Default)]
 struct LastSearch {
     plain: Option<TextField>,
+    ignore_case: Option<TextField>,
+    whole_word: Option<TextField>,
     regex: Option<TextField>,
 }
 
@@ -274,6 +332,8 @@ impl std::ops::Index<SearchType> for LastSearch {
     fn index(&self, t: SearchType) -> &Option<TextField> {
         match t {
             SearchType::Plain => &self.plain,
+            SearchType::IgnoreCase => &self.ignore_case,
+            SearchType::WholeWord => &self.whole_word,
             SearchType::Regex => &self.regex,
         }
     }
@@ -283,6 +343,8 @@ impl std::ops::IndexMut<SearchType> for LastSearch {
     fn index_mut(&mut self, t: SearchType) -> &mut Option<TextField> {
         match t {
             SearchType::Plain => &mut self.plain,
+            SearchType::IgnoreCase => &mut self.ignore_case,
+            SearchType::WholeWord => &mut self.whole_word,
             SearchType::Regex => &mut self.regex,
         }
     }
@@ -398,9 +460,12 @@ pub struct Editor {
     mode: EditorMode,                    // what mode the editing is in
     cut_buffer: Option<EditorCutBuffer>, // contents of cut buffer
     last_search: LastSearch,             // last searches performed
+    jumps: JumpList,                     // positions from before large jumps
     show_help: bool,                     // whether to show keybindinings
     show_sub_help: bool,                 // whether to show sub-mode help
     open_dir: OpenDir,                   // currently open directory
+    session: Option<Session>,            // session from before quitting began
+    saves_session: bool,                 // whether to save session on quit
     #[cfg(feature = "ssh")]
     remote: Option<Remote>, // remote SSH session
 }
@@ -413,14 +478,59 @@ impl Editor {
             mode: EditorMode::default(),
             cut_buffer: None,
             last_search: LastSearch::default(),
+            jumps: JumpList::default(),
             show_help: false,
             show_sub_help: true,
             open_dir: OpenDir::default(),
+            session: None,
+            saves_session: crate::config::CONFIG.session.unwrap_or(false),
             #[cfg(feature = "ssh")]
             remote: None,
         })
     }
 
+    /// Restores files, cursor positions and panes from a saved session,
+    /// which will be saved again on quit
+    ///
+    /// Files which no longer open are skipped with a notice.
+    pub fn from_session(session: Session) -> std::io::Result<Self> {
+        let mut opened = HashMap::new();
+        let mut skipped = vec![];
+        let mut editor = Self::new([])?;
+
+        if let Some(layout) = Layout::restore(session, &mut opened, &mut skipped) {
+            editor.layout = layout;
+        }
+        editor.saves_session = true;
+
+        if !skipped.is_empty()
+            && let Some(buf) = editor.layout.selected_buffer_list_mut().current_mut()
+        {
+            buf.set_message(format!("Skipped Missing Files: {}", skipped.join(", ")));
+        }
+
+        Ok(editor)
+    }
+
+    /// Remembers session before any buffers are quit
+    fn remember_session(&mut self) {
+        if self.session.is_none() {
+            self.session = Some(self.layout.session());
+        }
+    }
+
+    /// Saves session as it was before quitting began, if wanted
+    pub fn save_session(&self) -> std::io::Result<()> {
+        match (
+            self.saves_session,
+            &self.session,
+            crate::session::session_path(),
+        ) {
+            (true, Some(session), Some(path)) => session.save(&path),
+            _ => Ok(()),
+        }
+    }
+
     #[cfg(feature = "ssh")]
     pub fn new_remote(
         buffers: impl IntoIterator<Item = Source>,
@@ -445,12 +555,28 @@ impl Editor {
         })
     }
 
-    pub fn at_line(mut self, LineNumber { line, column }: LineNumber) -> Self {
+    pub fn at_line(mut self, line: LineNumber) -> Self {
         let buffers = self.layout.selected_buffer_list_mut();
         if let Some(first) = buffers.current_mut() {
-            match column {
-                None => first.select_line(line),
-                Some(column) => first.select_line_and_column(line, column),
+            line.select(first);
+        }
+        self
+    }
+
+    /// Moves each opened file to its respective line, if any
+    pub fn at_lines(
+        mut self,
+        lines: impl IntoIterator<Item = (PathBuf, Option<LineNumber>)>,
+    ) -> Self {
+        let lines = lines
+            .into_iter()
+            .filter_map(|(path, line)| Some((path, line?)))
+            .collect::<HashMap<_, _>>();
+
+        let buffers = self.layout.selected_buffer_list_mut();
+        for buf in buffers.buffers_mut() {
+            if let Some(line) = buf.local_path().and_then(|path| lines.get(&path)) {
+                line.clone().select(buf);
             }
         }
         self
@@ -514,31 +640,185 @@ impl Editor {
 
     fn perform_cut(&mut self) {
         if let Some(Some(selection)) = self.layout.on_current_at(|b, a| b.take_selection(a)) {
+            crate::clipboard::copy(selection.as_str());
             self.cut_buffer = Some(EditorCutBuffer::Single(selection));
         }
     }
 
+    fn perform_kill(&mut self) {
+        if let Some(Some(line)) = self.layout.on_current_at(|b, a| b.kill_line(a)) {
+            crate::clipboard::copy(line.as_str());
+            self.cut_buffer = Some(EditorCutBuffer::Single(line));
+        }
+    }
+
     fn perform_copy(&mut self) {
         if let Some(Some(selection)) = self.layout.on_current(|b| b.get_selection()) {
+            crate::clipboard::copy(selection.as_str());
             self.cut_buffer = Some(EditorCutBuffer::Single(selection));
         }
     }
 
+    /// If something else has since copied to the system clipboard,
+    /// replaces the cut buffer with its contents
+    fn take_clipboard(&mut self) {
+        if let Some(text) = crate::clipboard::newer() {
+            self.cut_buffer = Some(EditorCutBuffer::Single(text.into()));
+        }
+    }
+
+    /// A file chooser starting from wherever files were last opened
+    #[cfg(not(feature = "ssh"))]
+    fn file_chooser(&self) -> Result<Box<FileChooserState<LocalSource>>, String> {
+        FileChooserState::new(LocalSource, self.open_dir[DirTarget::Local].clone())
+            .map(Box::new)
+            .map_err(|err| err.to_string())
+    }
+
+    /// A file chooser starting from wherever files were last opened
+    #[cfg(feature = "ssh")]
+    fn file_chooser(&self) -> Result<Box<FileChooserState<EitherSource>>, String> {
+        match self.remote.as_ref() {
+            None => FileChooserState::new(
+                EitherSource::local(),
+                self.open_dir[DirTarget::Local].clone(),
+            ),
+            Some(remote) => FileChooserState::new(
+                EitherSource::ssh(
+                    SshSource::open(remote.to_string(), remote.sftp()),
+                    self.open_dir.last.unwrap_or(DirTarget::Ssh),
+                ),
+                self.open_dir[self.open_dir.last.unwrap_or(DirTarget::Ssh)].clone(),
+            ),
+        }
+        .map(Box::new)
+        .map_err(|err| err.to_string())
+    }
+
+    /// Queries for a file to save the current buffer to
+    fn save_as(&mut self) {
+        match self.file_chooser() {
+            Ok(chooser) => self.mode = EditorMode::SaveAs { chooser },
+            Err(err) => self.update_buffer(|b| b.set_error(err)),
+        }
+    }
+
+    /// Current buffer and cursor position, if any
+    fn position(&self) -> Option<(BufferId, usize)> {
+        self.layout
+            .selected_buffer_list()
+            .current()
+            .map(|b| (b.id(), b.cursor()))
+    }
+
+    /// Remembers the current position before jumping elsewhere,
+    /// once back to editing and only if the cursor has since moved,
+    /// since prompts like Goto Line may be cancelled or go nowhere
+    fn record_jump(&mut self) {
+        self.jumps.pending = self.position();
+    }
+
+    /// Switches to position's buffer and moves to its cursor,
+    /// returning false if its buffer is no longer open
+    fn jump_to(&mut self, (id, cursor): (BufferId, usize)) -> bool {
+        let list = self.layout.selected_buffer_list_mut();
+        let Some(index) = list.buffers().position(|b| b.id() == id) else {
+            return false;
+        };
+        list.set_index(index);
+        list.update_buf(|b| {
+            // buffer may have shrunk since, so cursor is clamped
+            b.jump_to(cursor);
+            set_title(b);
+        });
+        true
+    }
+
+    /// Returns to the position from before the latest jump
+    fn jump_back(&mut self) {
+        let Some(current) = self.position() else {
+            return;
+        };
+        while let Some(position) = self.jumps.back.pop_back() {
+            if position != current && self.jump_to(position) {
+                self.jumps.forward.push(current);
+                return;
+            }
+        }
+        self.update_buffer(|b| b.set_error("No Earlier Position"));
+    }
+
+    /// Undoes the latest jump back
+    fn jump_forward(&mut self) {
+        let Some(current) = self.position() else {
+            return;
+        };
+        while let Some(position) = self.jumps.forward.pop() {
+            if position != current && self.jump_to(position) {
+                self.jumps.back.push_back(current);
+                return;
+            }
+        }
+        self.update_buffer(|b| b.set_error("No Later Position"));
+    }
+
+    /// Queries for a command to filter the selection through,
+    /// provided there's a selection to filter
+    fn shell_filter(&mut self) {
+        if self.on_buffer(|b| b.has_selection()) == Some(true) {
+            self.mode = EditorMode::ShellFilter {
+                prompt: TextField::default(),
+            };
+        } else {
+            self.update_buffer(|b| b.set_error("No Selection to Filter"));
+        }
+    }
+
+    fn update_block(&mut self) {
+        if let Some(matches) = self.on_buffer(|b| b.block_cursors())
+            && let Some(match_idx) = matches.len().checked_sub(1)
+        {
+            self.mode = EditorMode::SingleBuffer {
+                cursors: MultiCursors {
+                    matches,
+                    match_idx,
+                    highlight: false,
+                    mode: MultiCursorMode::Editing,
+                },
+                range: None,
+            };
+        }
+    }
+
+    fn select_buffer(&mut self) {
+        let buffer_list = self.layout.selected_buffer_list();
+        let index = buffer_list.current_index();
+        let buffer_list = buffer_list.buffers().map(|b| b.id()).collect();
+        self.mode = EditorMode::SelectBuffer { buffer_list, index };
+    }
+
     pub fn process_event(&mut self, area: Rect, event: Event) {
         use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
+        // since quitting closes buffers one at a time,
+        // remember the session as it was before the first one
+        if Action::Quit.pressed(&event) {
+            if matches!(self.mode, EditorMode::SelectBuffer { .. }) {
+                self.remember_session();
+            }
+        } else if matches!(self.mode, EditorMode::Editing)
+            && matches!(event, Event::Key(_) | Event::Paste(_))
+        {
+            self.session = None;
+        }
+
         match event {
             // Esc is an escape hatch that always returns to normal mode
             // regardless of what mode we were in before
             key!(Esc) => {
                 self.mode = EditorMode::default();
             }
-            Event::Key(KeyEvent {
-                code: KeyCode::F(1),
-                modifiers: KeyModifiers::NONE,
-                kind: KeyEventKind::Press,
-                ..
-            }) => match self.mode {
+            _ if Action::Help.pressed(&event) => match self.mode {
                 EditorMode::Editing => {
                     self.show_help = !self.show_help;
                 }
@@ -756,7 +1036,9 @@ impl Editor {
                 }
                 EditorMode::VerifySave => self.process_verify_save(event),
                 EditorMode::VerifyReload => self.process_verify_reload(event),
+                EditorMode::VerifySaveAs { .. } => self.process_verify_save_as(event),
                 EditorMode::SelectInside => self.process_select_inside(event),
+                EditorMode::SurroundWith => self.process_surround_with(event),
                 EditorMode::SelectLine { prompt } => {
                     if let Some(buf) = self.layout.selected_buffer_list_mut().current_mut()
                         && let Some(new_mode) = process_select_line(buf, prompt, event)
@@ -764,6 +1046,29 @@ impl Editor {
                         self.mode = new_mode;
                     }
                 }
+                EditorMode::SelectSyntax { prompt, index } => {
+                    if let Some(buf) = self.layout.selected_buffer_list_mut().current_mut()
+                        && let Some(new_mode) = process_select_syntax(buf, prompt, index, event)
+                    {
+                        self.mode = new_mode;
+                    }
+                }
+                EditorMode::CommandPalette { prompt, index } => {
+                    if let Some(command) = process_command_palette(prompt, index, event) {
+                        self.mode = EditorMode::default();
+                        self.perform(command.action);
+                    }
+                }
+                EditorMode::ShellFilter { prompt } => match event {
+                    key!(Enter) => {
+                        let command = prompt.chars().collect::<String>();
+                        self.mode = EditorMode::default();
+                        if !command.trim().is_empty() {
+                            self.update_buffer_at(|b, a| b.filter_selection(a, &command));
+                        }
+                    }
+                    event => prompt.process_event(event),
+                },
                 EditorMode::Open { chooser } => {
                     if let Some(new_mode) =
                         process_open_file(&mut self.layout, chooser, &mut self.open_dir, event)
@@ -771,6 +1076,13 @@ impl Editor {
                         self.mode = new_mode;
                     }
                 }
+                EditorMode::SaveAs { chooser } => {
+                    if let Some(new_mode) =
+                        process_save_as(&mut self.layout, chooser, &mut self.open_dir, event)
+                    {
+                        self.mode = new_mode;
+                    }
+                }
                 EditorMode::Search {
                     search:
                         Search {
@@ -1065,7 +1377,8 @@ impl Editor {
                             self.layout.swap_buffers(idx_a, idx_b);
                         }
                         Some(SelectBuffer::SaveAll) => {
-                            match self.layout.selected_buffer_list_mut().save_all() {
+                            let (list, mut alts) = self.layout.current_buffer_list_mut();
+                            match list.save_all(&mut alts) {
                                 Ok(Ok(saved)) => {
                                     self.layout.on_current(|b| {
                                         match saved {
@@ -1077,6 +1390,10 @@ impl Editor {
                                     });
                                     self.mode = EditorMode::default();
                                 }
+                                Ok(Err(_)) if self.on_buffer(|b| b.needs_file()) == Some(true) => {
+                                    // the failing buffer is now current
+                                    self.save_as();
+                                }
                                 Ok(Err(err)) => {
                                     self.layout.on_current(|b| b.set_error(err.to_string()));
                                 }
@@ -1133,42 +1450,38 @@ impl Editor {
                 }
             },
         }
+
+        if matches!(self.mode, EditorMode::Editing) {
+            let current = self.position();
+            self.jumps.settle(current);
+        }
     }
 
     fn process_normal_event(&mut self, area: Rect, event: Event) {
-        use crate::buffer::SelectionType;
         use crossterm::event::{
             Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
             MouseEventKind,
         };
         use std::process::Command;
 
+        if let Some(action) = KEYMAP.action(&event) {
+            self.perform(action);
+            return;
+        }
+
         match event {
-            keybind!(Quit) => {
-                if let Some(buf) = self.layout.selected_buffer_list().current() {
-                    if buf.modified() {
-                        self.mode = EditorMode::ConfirmClose { buffer: buf.id() };
-                    } else {
-                        self.layout.remove(buf.id());
-                        if let Some(buf) = self.layout.selected_buffer_list().current() {
-                            set_title(buf);
-                        }
-                    }
-                }
-            }
-            key!(CONTROL, PageUp) => {
-                if let Some(buf) = self.layout.previous_buffer() {
-                    set_title(buf);
-                }
-            }
-            key!(CONTROL, PageDown) => {
-                if let Some(buf) = self.layout.next_buffer() {
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(digit @ '1'..='9'),
+                modifiers: KeyModifiers::ALT,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                let buffer_list = self.layout.selected_buffer_list_mut();
+                buffer_list.set_index(usize::from(digit as u8 - b'1'));
+                if let Some(buf) = buffer_list.current() {
                     set_title(buf);
                 }
             }
-            keybind!(SplitPane) => {
-                self.mode = EditorMode::SplitPane;
-            }
             Event::Key(KeyEvent {
                 code:
                     code @ KeyCode::Left
@@ -1244,6 +1557,26 @@ impl Editor {
                 kind: KeyEventKind::Press,
                 ..
             }) => self.update_buffer(|b| b.cursor_forward(modifiers.contains(KeyModifiers::SHIFT))),
+            Event::Key(KeyEvent {
+                code: KeyCode::Left,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) if modifiers == KeyModifiers::ALT
+                || modifiers == KeyModifiers::ALT | KeyModifiers::SHIFT =>
+            {
+                self.update_buffer(|b| b.cursor_prev_word(modifiers.contains(KeyModifiers::SHIFT)))
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Right,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) if modifiers == KeyModifiers::ALT
+                || modifiers == KeyModifiers::ALT | KeyModifiers::SHIFT =>
+            {
+                self.update_buffer(|b| b.cursor_next_word(modifiers.contains(KeyModifiers::SHIFT)))
+            }
             Event::Key(KeyEvent {
                 code: KeyCode::Home,
                 modifiers: modifiers @ KeyModifiers::NONE | modifiers @ KeyModifiers::SHIFT,
@@ -1264,38 +1597,19 @@ impl Editor {
             }) => self.update_buffer_at(|b, a| b.insert_char(a, c)),
             key!(Backspace) => self.update_buffer_at(|b, a| b.backspace(a)),
             key!(Delete) => self.update_buffer_at(|b, a| b.delete(a)),
-            key!(Enter) => self.update_buffer_at(|b, a| b.newline(a)),
-            keybind!(WidenSelection) => self.update_buffer(|b| b.select_word_or_lines()),
-            ctrl_keybind!(Cut) => self.perform_cut(),
-            ctrl_keybind!(Copy) => self.perform_copy(),
-            ctrl_keybind!(Paste) => {
-                self.layout.update_current_at(|b, a| {
-                    b.paste(a, &mut self.cut_buffer);
-                });
+            key!(CONTROL, Backspace) | key!(ALT, Backspace) => {
+                self.update_buffer_at(|b, a| b.backspace_word(a))
             }
+            key!(CONTROL, Delete) | key!(ALT, Delete) => {
+                self.update_buffer_at(|b, a| b.delete_word(a))
+            }
+            key!(Enter) => self.update_buffer_at(|b, a| b.newline(a)),
             Event::Paste(pasted) => {
                 self.cut_buffer = Some(EditorCutBuffer::Single(pasted.into()));
                 self.layout.update_current_at(|b, a| {
                     b.paste(a, &mut self.cut_buffer);
                 });
             }
-            ctrl_keybind!(Undo) => {
-                let _ = self
-                    .layout
-                    .on_all(|b| b.perform_undo_active(), |b| b.perform_undo_inactive());
-            }
-            ctrl_keybind!(Redo) => {
-                let _ = self
-                    .layout
-                    .on_all(|b| b.perform_redo_active(), |b| b.perform_redo_inactive());
-            }
-            keybind!(Save) => {
-                // if save fails, we'll already be in normal mode
-                // to display the save failure message
-                if let Some(Err(crate::buffer::Modified)) = self.on_buffer(|b| b.verified_save()) {
-                    self.mode = EditorMode::VerifySave;
-                }
-            }
             key!(Tab) => {
                 if let Some(Some((offset, completions))) =
                     self.on_buffer_at(|b, a| b.complete_or_indent(a))
@@ -1338,152 +1652,19 @@ impl Editor {
                     }
                 };
             }
-            keybind!(GotoPair) => self.update_buffer(|b| b.select_matching_paren()),
-            keybind!(Bookmark) => self.update_buffer(|b| b.toggle_bookmark()),
-            keybind!(SelectInside) => {
-                if let Some(Err(())) = self.on_buffer(|b| b.try_select_inside()) {
-                    self.mode = EditorMode::SelectInside;
-                }
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollDown,
+                modifiers: modifiers @ KeyModifiers::NONE | modifiers @ KeyModifiers::SHIFT,
+                ..
+            }) => {
+                self.update_buffer(|b| b.cursor_down(1, modifiers.contains(KeyModifiers::SHIFT)));
             }
-            keybind!(GotoLine) => {
-                self.mode = EditorMode::SelectLine {
-                    prompt: LinePrompt::default(),
-                };
-            }
-            keybind!(Find) => {
-                if let Some(Ok(find)) = self.on_buffer(|b| match b.selection_range() {
-                    Some(SelectionType::Term(selection)) => {
-                        use crate::buffer::Normalizations;
-
-                        (match Normalizations::try_from(selection) {
-                            Ok(normalizations) => {
-                                b.all_matches(None, normalizations).map_err(|_| ())
-                            }
-                            Err(selection) => b.all_matches(None, selection).map_err(|_| ()),
-                        })
-                        .map(|(match_idx, matches)| {
-                            EditorMode::SingleBuffer {
-                                cursors: MultiCursors {
-                                    matches,
-                                    match_idx,
-                                    highlight: true,
-                                    mode: MultiCursorMode::Editing,
-                                },
-                                range: None,
-                            }
-                        })
-                    }
-                    Some(SelectionType::Range(range)) => Ok(EditorMode::Search {
-                        search: Search {
-                            prompt: TextField::default(),
-                            type_: SearchType::default(),
-                            mode: SearchMode::Editing,
-                        },
-                        range: Some(range),
-                    }),
-                    None => Ok(EditorMode::Search {
-                        search: Search {
-                            prompt: TextField::default(),
-                            type_: SearchType::default(),
-                            mode: SearchMode::Editing,
-                        },
-                        range: None,
-                    }),
-                }) {
-                    self.mode = find;
-                }
-            }
-            #[cfg(not(feature = "ssh"))]
-            keybind!(Open) => {
-                match FileChooserState::new(LocalSource, self.open_dir[DirTarget::Local].clone()) {
-                    Ok(chooser) => {
-                        self.mode = EditorMode::Open {
-                            chooser: Box::new(chooser),
-                        }
-                    }
-                    Err(err) => {
-                        self.update_buffer(|b| b.set_error(err.to_string()));
-                    }
-                }
-            }
-            #[cfg(feature = "ssh")]
-            keybind!(Open) => match self.remote.as_ref() {
-                None => match FileChooserState::new(
-                    EitherSource::local(),
-                    self.open_dir[DirTarget::Local].clone(),
-                ) {
-                    Ok(chooser) => {
-                        self.mode = EditorMode::Open {
-                            chooser: Box::new(chooser),
-                        }
-                    }
-                    Err(err) => {
-                        self.update_buffer(|b| b.set_error(err.to_string()));
-                    }
-                },
-                Some(remote) => match FileChooserState::new(
-                    EitherSource::ssh(
-                        SshSource::open(remote.to_string(), remote.sftp()),
-                        self.open_dir.last.unwrap_or(DirTarget::Ssh),
-                    ),
-                    self.open_dir[self.open_dir.last.unwrap_or(DirTarget::Ssh)].clone(),
-                ) {
-                    Ok(chooser) => {
-                        self.mode = EditorMode::Open {
-                            chooser: Box::new(chooser),
-                        }
-                    }
-                    Err(err) => self.update_buffer(|b| b.set_error(err.to_string())),
-                },
-            },
-            keybind!(Reload) => {
-                match self.on_buffer_at(|b, a| b.verified_reload(a)) {
-                    Some(Err(crate::buffer::Modified)) => {
-                        self.mode = EditorMode::VerifyReload;
-                    }
-                    Some(Ok(Err(err))) => {
-                        self.update_buffer(|b| b.set_error(err.to_string()));
-                    }
-                    None | Some(Ok(Ok(()))) => { /* do nothing */ }
-                }
-            }
-            keybind!(UpdateLines) | key!(CONTROL, 'r') => {
-                if let Some(matches) = self.on_buffer(|b| b.selection_cursors())
-                    && let Some(match_idx) = matches.len().checked_sub(1)
-                {
-                    self.mode = EditorMode::SingleBuffer {
-                        cursors: MultiCursors {
-                            matches,
-                            match_idx,
-                            highlight: false,
-                            mode: MultiCursorMode::Editing,
-                        },
-                        range: None,
-                    };
-                }
-            }
-            ctrl_keybind!(Mark) => {
-                self.mode = EditorMode::MarkSet;
-            }
-            key!(CONTROL, '5') => {
-                let buffer_list = self.layout.selected_buffer_list();
-                let index = buffer_list.current_index();
-                let buffer_list = buffer_list.buffers().map(|b| b.id()).collect();
-                self.mode = EditorMode::SelectBuffer { buffer_list, index };
-            }
-            Event::Mouse(MouseEvent {
-                kind: MouseEventKind::ScrollDown,
-                modifiers: modifiers @ KeyModifiers::NONE | modifiers @ KeyModifiers::SHIFT,
-                ..
-            }) => {
-                self.update_buffer(|b| b.cursor_down(1, modifiers.contains(KeyModifiers::SHIFT)));
-            }
-            Event::Mouse(MouseEvent {
-                kind: MouseEventKind::ScrollUp,
-                modifiers: modifiers @ KeyModifiers::NONE | modifiers @ KeyModifiers::SHIFT,
-                ..
-            }) => {
-                self.update_buffer(|b| b.cursor_up(1, modifiers.contains(KeyModifiers::SHIFT)));
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                modifiers: modifiers @ KeyModifiers::NONE | modifiers @ KeyModifiers::SHIFT,
+                ..
+            }) => {
+                self.update_buffer(|b| b.cursor_up(1, modifiers.contains(KeyModifiers::SHIFT)));
             }
             Event::Mouse(MouseEvent {
                 kind: MouseEventKind::ScrollLeft,
@@ -1506,11 +1687,20 @@ impl Editor {
                 ..
             }) => {
                 self.layout
-                    .set_cursor_focus(area, Position { y: row, x: column });
+                    .set_cursor_focus(area, Position { y: row, x: column }, false);
                 if let Some(buf) = self.layout.selected_buffer_list().current() {
                     set_title(buf);
                 }
             }
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Drag(MouseButton::Left),
+                column,
+                row,
+                ..
+            }) => {
+                self.layout
+                    .set_cursor_focus(area, Position { y: row, x: column }, true);
+            }
             Event::Mouse(MouseEvent {
                 kind: MouseEventKind::Down(MouseButton::Right),
                 column,
@@ -1518,7 +1708,7 @@ impl Editor {
                 ..
             }) => {
                 self.layout
-                    .set_cursor_focus(area, Position { y: row, x: column });
+                    .set_cursor_focus(area, Position { y: row, x: column }, false);
                 if let Some(buf) = self.layout.selected_buffer_list().current() {
                     set_title(buf);
                 }
@@ -1531,7 +1721,7 @@ impl Editor {
                 ..
             }) => {
                 self.layout
-                    .set_cursor_focus(area, Position { y: row, x: column });
+                    .set_cursor_focus(area, Position { y: row, x: column }, false);
                 if let Some(buf) = self.layout.selected_buffer_list().current() {
                     set_title(buf);
                 }
@@ -1543,6 +1733,215 @@ impl Editor {
         }
     }
 
+    /// Performs an action, whether pressed or chosen from the command palette
+    fn perform(&mut self, action: Action) {
+        use crate::buffer::SelectionType;
+
+        match action {
+            Action::Quit => {
+                self.remember_session();
+                if let Some(buf) = self.layout.selected_buffer_list().current() {
+                    if buf.modified() {
+                        self.mode = EditorMode::ConfirmClose { buffer: buf.id() };
+                    } else {
+                        self.layout.remove(buf.id());
+                        if let Some(buf) = self.layout.selected_buffer_list().current() {
+                            set_title(buf);
+                        }
+                    }
+                }
+            }
+            Action::SplitPane => {
+                self.mode = EditorMode::SplitPane;
+            }
+            Action::WidenSelection => self.update_buffer(|b| b.select_word_or_lines()),
+            Action::SelectAll => self.update_buffer(|b| b.select_all()),
+            Action::Cut => self.perform_cut(),
+            Action::Copy => self.perform_copy(),
+            Action::Kill => self.perform_kill(),
+            Action::Paste => {
+                self.take_clipboard();
+                self.layout.update_current_at(|b, a| {
+                    b.paste(a, &mut self.cut_buffer);
+                });
+            }
+            Action::Undo => {
+                let _ = self
+                    .layout
+                    .on_all(|b| b.perform_undo_active(), |b| b.perform_undo_inactive());
+            }
+            Action::Redo => {
+                let _ = self
+                    .layout
+                    .on_all(|b| b.perform_redo_active(), |b| b.perform_redo_inactive());
+            }
+            Action::Save if self.on_buffer(|b| b.needs_file()) == Some(true) => self.save_as(),
+            Action::Save => {
+                // if save fails, we'll already be in normal mode
+                // to display the save failure message
+                if let Some(Err(crate::buffer::Modified)) =
+                    self.on_buffer_at(|b, a| b.verified_save(a))
+                {
+                    self.mode = EditorMode::VerifySave;
+                }
+            }
+            Action::GotoPair => {
+                self.record_jump();
+                self.update_buffer(|b| b.select_matching_paren());
+            }
+            Action::Bookmark => self.update_buffer(|b| b.toggle_bookmark()),
+            Action::SelectInside => {
+                if let Some(Err(())) = self.on_buffer(|b| b.try_select_inside()) {
+                    self.mode = EditorMode::SelectInside;
+                }
+            }
+            Action::Surround => {
+                self.mode = EditorMode::SurroundWith;
+            }
+            Action::GotoLine => {
+                self.record_jump();
+                self.mode = EditorMode::SelectLine {
+                    prompt: LinePrompt::default(),
+                };
+            }
+            Action::Find => {
+                self.record_jump();
+                if let Some(Ok(find)) = self.on_buffer(|b| match b.selection_range() {
+                    Some(SelectionType::Term(selection)) => {
+                        use crate::buffer::Normalizations;
+
+                        (match Normalizations::try_from(selection) {
+                            Ok(normalizations) => {
+                                b.all_matches(None, normalizations).map_err(|_| ())
+                            }
+                            Err(selection) => b.all_matches(None, selection).map_err(|_| ()),
+                        })
+                        .map(|(match_idx, matches)| {
+                            EditorMode::SingleBuffer {
+                                cursors: MultiCursors {
+                                    matches,
+                                    match_idx,
+                                    highlight: true,
+                                    mode: MultiCursorMode::Editing,
+                                },
+                                range: None,
+                            }
+                        })
+                    }
+                    Some(SelectionType::Range(range)) => Ok(EditorMode::Search {
+                        search: Search {
+                            prompt: TextField::default(),
+                            type_: SearchType::default(),
+                            mode: SearchMode::Editing,
+                        },
+                        range: Some(range),
+                    }),
+                    None => Ok(EditorMode::Search {
+                        search: Search {
+                            prompt: TextField::default(),
+                            type_: SearchType::default(),
+                            mode: SearchMode::Editing,
+                        },
+                        range: None,
+                    }),
+                }) {
+                    self.mode = find;
+                }
+            }
+            Action::Open => match self.file_chooser() {
+                Ok(chooser) => self.mode = EditorMode::Open { chooser },
+                Err(err) => self.update_buffer(|b| b.set_error(err)),
+            },
+            Action::Reload => {
+                match self.on_buffer_at(|b, a| b.verified_reload(a)) {
+                    Some(Err(crate::buffer::Modified)) => {
+                        self.mode = EditorMode::VerifyReload;
+                    }
+                    Some(Ok(Err(err))) => {
+                        self.update_buffer(|b| b.set_error(err.to_string()));
+                    }
+                    None | Some(Ok(Ok(()))) => { /* do nothing */ }
+                }
+            }
+            Action::UpdateLines => {
+                if let Some(matches) = self.on_buffer(|b| b.selection_cursors())
+                    && let Some(match_idx) = matches.len().checked_sub(1)
+                {
+                    self.mode = EditorMode::SingleBuffer {
+                        cursors: MultiCursors {
+                            matches,
+                            match_idx,
+                            highlight: false,
+                            mode: MultiCursorMode::Editing,
+                        },
+                        range: None,
+                    };
+                }
+            }
+            Action::Mark => {
+                self.mode = EditorMode::MarkSet;
+            }
+            Action::Palette => {
+                self.mode = EditorMode::CommandPalette {
+                    prompt: TextField::default(),
+                    index: 0,
+                };
+            }
+            Action::SaveAs => self.save_as(),
+            Action::UpdateBlock => self.update_block(),
+            Action::JumpBack => self.jump_back(),
+            Action::JumpForward => self.jump_forward(),
+            Action::SelectBuffer => self.select_buffer(),
+            Action::PreviousBuffer => {
+                if let Some(buf) = self.layout.previous_buffer() {
+                    set_title(buf);
+                }
+            }
+            Action::NextBuffer => {
+                if let Some(buf) = self.layout.next_buffer() {
+                    set_title(buf);
+                }
+            }
+            Action::PreviousPane => {
+                if let Some(buf) = self.layout.focus_prev() {
+                    set_title(buf);
+                }
+            }
+            Action::NextPane => {
+                if let Some(buf) = self.layout.focus_next() {
+                    set_title(buf);
+                }
+            }
+            Action::Overwrite => self.update_buffer(|b| b.toggle_overwrite()),
+            Action::MoveLinesUp => self.update_buffer_at(|b, a| b.move_lines_up(a)),
+            Action::MoveLinesDown => self.update_buffer_at(|b, a| b.move_lines_down(a)),
+            Action::ToggleComment => self.update_buffer_at(|b, a| b.toggle_comment(a)),
+            Action::Reflow => self.update_buffer_at(|b, a| b.reflow(a, *REFLOW_WIDTH)),
+            Action::UpperCase => {
+                self.update_buffer_at(|b, a| b.transform_selection(a, Case::Upper))
+            }
+            Action::LowerCase => {
+                self.update_buffer_at(|b, a| b.transform_selection(a, Case::Lower))
+            }
+            Action::ToggleCase => {
+                self.update_buffer_at(|b, a| b.transform_selection(a, Case::Toggle))
+            }
+            Action::Filter => self.shell_filter(),
+            Action::SelectSyntax => {
+                self.mode = EditorMode::SelectSyntax {
+                    prompt: TextField::default(),
+                    index: 0,
+                };
+            }
+            Action::Highlighting => self.update_buffer(|b| b.toggle_highlighting()),
+            Action::LineNumbers => self.update_buffer(|b| b.toggle_line_numbers()),
+            Action::Whitespace => self.update_buffer(|b| b.toggle_whitespace()),
+            Action::SoftWrap => self.update_buffer(|b| b.toggle_wrap()),
+            Action::LineEndings => self.update_buffer(|b| b.cycle_endings()),
+            Action::Help => self.show_help = !self.show_help,
+        }
+    }
+
     pub fn process_mark_set_event(&mut self, event: Event) -> Option<Event> {
         use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
@@ -1623,7 +2022,7 @@ impl Editor {
                 self.update_buffer(|b| b.cursor_end(true));
                 None
             }
-            ctrl_keybind!(Mark) => {
+            _ if Action::Mark.pressed(&event) => {
                 self.mode = EditorMode::default();
                 None
             }
@@ -1657,10 +2056,10 @@ impl Editor {
         match event {
             key!('y') => {
                 // save buffer anyway
-                self.update_buffer(|b| {
+                self.update_buffer_at(|b, a| {
                     // buffer already updated with error message
                     // in case save doesn't succeed
-                    let _ = b.save();
+                    let _ = b.save(a);
                 });
                 self.mode = EditorMode::default();
             }
@@ -1672,6 +2071,28 @@ impl Editor {
         }
     }
 
+    fn process_verify_save_as(&mut self, event: Event) {
+        use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+        match event {
+            key!('y') => {
+                // overwrite file anyway
+                if let EditorMode::VerifySaveAs { source } = std::mem::take(&mut self.mode) {
+                    self.update_buffer_at(|b, a| {
+                        if b.save_as(source, a).is_ok() {
+                            set_title(b);
+                        }
+                    });
+                }
+            }
+            key!('n') => {
+                // cancel save
+                self.mode = EditorMode::default();
+            }
+            _ => { /* ignore other events */ }
+        }
+    }
+
     fn process_verify_reload(&mut self, event: Event) {
         use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
@@ -1731,6 +2152,16 @@ impl Editor {
                 }
                 self.mode = EditorMode::default();
             }
+            key!(Tab) => {
+                if let Some(buf) = self.layout.focus_next() {
+                    set_title(buf);
+                }
+            }
+            key!(SHIFT, BackTab) => {
+                if let Some(buf) = self.layout.focus_prev() {
+                    set_title(buf);
+                }
+            }
             key!(SHIFT, Left) => {
                 let _ = self.layout.swap_pane(Direction::Left);
             }
@@ -1845,20 +2276,373 @@ impl Editor {
         }
     }
 
-    pub fn auto_save(&mut self) -> bool {
-        if matches!(self.mode, EditorMode::Editing) {
-            self.layout
-                .selected_buffer_list_mut()
-                .buffers_mut()
-                .fold(false, |saved, buf| {
-                    if buf.modified() {
-                        matches!(buf.verified_save(), Ok(Ok(()))) | saved
-                    } else {
-                        saved
-                    }
-                })
-        } else {
-            false
+    fn process_surround_with(&mut self, event: Event) {
+        use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+        match event {
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('(' | ')'),
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                self.update_buffer_at(|b, a| b.surround(a, ['(', ')']));
+                self.mode = EditorMode::default();
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('[' | ']'),
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                self.update_buffer_at(|b, a| b.surround(a, ['[', ']']));
+                self.mode = EditorMode::default();
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('{' | '}'),
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                self.update_buffer_at(|b, a| b.surround(a, ['{', '}']));
+                self.mode = EditorMode::default();
+            }
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('<' | '>'),
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+                ..
+            }) => {
+                self.update_buffer_at(|b, a| b.surround(a, ['<', '>']));
+                self.mode = EditorMode::default();
+            }
+            key!('"') => {
+                self.update_buffer_at(|b, a| b.surround(a, ['"', '"']));
+                self.mode = EditorMode::default();
+            }
+            key!('\'') => {
+                self.update_buffer_at(|b, a| b.surround(a, ['\'', '\'']));
+                self.mode = EditorMode::default();
+            }
+            key!(Delete) => {
+                self.update_buffer_at(|b, a| b.delete_surround(a));
+                self.mode = EditorMode::default();
+            }
+            _ => { /* do nothing */ }
+        }
+    }
+
+    /// Warns if the current buffer's file has been changed
+    /// by something else, returning true if it has
+    pub fn check_changed(&mut self) -> bool {
+        if !matches!(self.mode, EditorMode::Editing) {
+            return false;
+        }
+
+        let reload = KEYMAP
+            .shortcuts(Action::Reload)
+            .first()
+            .map(|s| format!(", {} to Reload", s.label))
+            .unwrap_or_default();
+
+        self.on_buffer(|b| {
+            b.newly_changed()
+                .then(|| b.set_message(format!("File Changed on Disk{reload}")))
+                .is_some()
+        })
+        .unwrap_or(false)
+    }
+
+    pub fn auto_save(&mut self) -> bool {
+        if matches!(self.mode, EditorMode::Editing) {
+            let (list, mut alts) = self.layout.current_buffer_list_mut();
+            list.buffers_mut()
+                .enumerate()
+                .fold(false, |saved, (idx, buf)| {
+                    if buf.modified() && !buf.needs_file() {
+                        let alt = alts
+                            .iter_mut()
+                            .filter_map(|a| a.get_mut(idx).map(|a| a.alt_cursor()))
+                            .collect();
+                        matches!(buf.verified_save(alt), Ok(Ok(()))) | saved
+                    } else {
+                        saved
+                    }
+                })
+        } else {
+            false
+        }
+    }
+}
+
+fn process_select_syntax(
+    buffer: &mut BufferContext,
+    prompt: &mut TextField,
+    index: &mut usize,
+    event: Event,
+) -> Option<EditorMode> {
+    use crate::syntax::syntaxes_matching;
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+    let total = syntaxes_matching(&prompt.chars().collect::<String>()).len();
+
+    match event {
+        key!(Up) => {
+            *index = index.checked_sub(1).unwrap_or(total.saturating_sub(1));
+            None
+        }
+        key!(Down) => {
+            *index = (*index + 1).checked_rem(total).unwrap_or(0);
+            None
+        }
+        key!(Enter) => {
+            let syntax = syntaxes_matching(&prompt.chars().collect::<String>())
+                .into_iter()
+                .nth(*index)?;
+            buffer.set_syntax(syntax);
+            Some(EditorMode::default())
+        }
+        event => {
+            prompt.process_event(event);
+            *index = 0;
+            None
+        }
+    }
+}
+
+/// A named command which may be chosen from the command palette
+pub struct Command {
+    pub name: &'static str, // command's name, as listed
+    action: Action,         // action the command performs
+}
+
+impl Command {
+    /// Label of the key the command is also available from, if any
+    pub fn key(&self) -> &'static str {
+        KEYMAP
+            .shortcuts(self.action)
+            .first()
+            .map(|s| s.label.as_str())
+            .unwrap_or_default()
+    }
+}
+
+static COMMANDS: &[Command] = &[
+    Command {
+        name: "Open File",
+        action: Action::Open,
+    },
+    Command {
+        name: "Save File",
+        action: Action::Save,
+    },
+    Command {
+        name: "Save File As",
+        action: Action::SaveAs,
+    },
+    Command {
+        name: "Reload File",
+        action: Action::Reload,
+    },
+    Command {
+        name: "Quit File",
+        action: Action::Quit,
+    },
+    Command {
+        name: "Goto Line or Bookmark",
+        action: Action::GotoLine,
+    },
+    Command {
+        name: "Find Text",
+        action: Action::Find,
+    },
+    Command {
+        name: "Update Selected Lines",
+        action: Action::UpdateLines,
+    },
+    Command {
+        name: "Update Selected Block",
+        action: Action::UpdateBlock,
+    },
+    Command {
+        name: "Goto Matching Pair",
+        action: Action::GotoPair,
+    },
+    Command {
+        name: "Jump Back",
+        action: Action::JumpBack,
+    },
+    Command {
+        name: "Jump Forward",
+        action: Action::JumpForward,
+    },
+    Command {
+        name: "Select Inside Pair",
+        action: Action::SelectInside,
+    },
+    Command {
+        name: "Surround With Pair",
+        action: Action::Surround,
+    },
+    Command {
+        name: "Select Word or Whole Lines",
+        action: Action::WidenSelection,
+    },
+    Command {
+        name: "Manage Panes",
+        action: Action::SplitPane,
+    },
+    Command {
+        name: "Manage Buffers",
+        action: Action::SelectBuffer,
+    },
+    Command {
+        name: "Previous Buffer",
+        action: Action::PreviousBuffer,
+    },
+    Command {
+        name: "Next Buffer",
+        action: Action::NextBuffer,
+    },
+    Command {
+        name: "Toggle Bookmark",
+        action: Action::Bookmark,
+    },
+    Command {
+        name: "Toggle Overwrite Mode",
+        action: Action::Overwrite,
+    },
+    Command {
+        name: "Set Mark",
+        action: Action::Mark,
+    },
+    Command {
+        name: "Select All",
+        action: Action::SelectAll,
+    },
+    Command {
+        name: "Cut",
+        action: Action::Cut,
+    },
+    Command {
+        name: "Copy",
+        action: Action::Copy,
+    },
+    Command {
+        name: "Cut to End of Line",
+        action: Action::Kill,
+    },
+    Command {
+        name: "Paste",
+        action: Action::Paste,
+    },
+    Command {
+        name: "Undo",
+        action: Action::Undo,
+    },
+    Command {
+        name: "Redo",
+        action: Action::Redo,
+    },
+    Command {
+        name: "Move Lines Up",
+        action: Action::MoveLinesUp,
+    },
+    Command {
+        name: "Move Lines Down",
+        action: Action::MoveLinesDown,
+    },
+    Command {
+        name: "Toggle Line Comments",
+        action: Action::ToggleComment,
+    },
+    Command {
+        name: "Reflow Paragraph",
+        action: Action::Reflow,
+    },
+    Command {
+        name: "Upper Case",
+        action: Action::UpperCase,
+    },
+    Command {
+        name: "Lower Case",
+        action: Action::LowerCase,
+    },
+    Command {
+        name: "Toggle Case",
+        action: Action::ToggleCase,
+    },
+    Command {
+        name: "Filter Selection Through Command",
+        action: Action::Filter,
+    },
+    Command {
+        name: "Choose Syntax Highlighting",
+        action: Action::SelectSyntax,
+    },
+    Command {
+        name: "Toggle Syntax Highlighting",
+        action: Action::Highlighting,
+    },
+    Command {
+        name: "Cycle Line Numbers",
+        action: Action::LineNumbers,
+    },
+    Command {
+        name: "Toggle Visible Whitespace",
+        action: Action::Whitespace,
+    },
+    Command {
+        name: "Toggle Soft Wrap",
+        action: Action::SoftWrap,
+    },
+    Command {
+        name: "Cycle Line Endings",
+        action: Action::LineEndings,
+    },
+    Command {
+        name: "Toggle Keybindings Display",
+        action: Action::Help,
+    },
+];
+
+/// Commands whose names contain the given text, ignoring case
+pub fn commands_matching(name: &str) -> Vec<&'static Command> {
+    let name = name.to_lowercase();
+
+    let mut commands = COMMANDS
+        .iter()
+        .filter(|command| command.name.to_lowercase().contains(&name))
+        .collect::<Vec<_>>();
+    commands.sort_by_key(|command| command.name);
+    commands
+}
+
+fn process_command_palette(
+    prompt: &mut TextField,
+    index: &mut usize,
+    event: Event,
+) -> Option<&'static Command> {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+    let total = commands_matching(&prompt.chars().collect::<String>()).len();
+
+    match event {
+        key!(Up) => {
+            *index = index.checked_sub(1).unwrap_or(total.saturating_sub(1));
+            None
+        }
+        key!(Down) => {
+            *index = (*index + 1).checked_rem(total).unwrap_or(0);
+            None
+        }
+        key!(Enter) => commands_matching(&prompt.chars().collect::<String>())
+            .into_iter()
+            .nth(*index),
+        event => {
+            prompt.process_event(event);
+            *index = 0;
+            None
         }
     }
 }
@@ -1937,7 +2721,7 @@ fn process_select_line(
             buffer.select_line(buffer.last_line());
             Some(EditorMode::default())
         }
-        keybind!(Find) => Some(EditorMode::Search {
+        _ if Action::Find.pressed(&event) => Some(EditorMode::Search {
             search: Search {
                 prompt: TextField::default(),
                 type_: SearchType::default(),
@@ -2028,13 +2812,21 @@ fn process_open_file<S: ChooserSource>(
             None
         }
         key!(Tab) => {
-            chooser.toggle_selected();
+            chooser.tab();
             None
         }
         key!(CONTROL, 'h') => {
             chooser.toggle_show_hidden();
             None
         }
+        key!(CONTROL, 'g') => {
+            chooser.edit_filter();
+            None
+        }
+        key!(CONTROL, 'd') => {
+            chooser.new_dir();
+            None
+        }
         key!(Enter) => {
             for selected in chooser.select()? {
                 if let Err(()) = layout.add(selected) {
@@ -2050,7 +2842,11 @@ fn process_open_file<S: ChooserSource>(
             open_dir.last = Some(chooser.target());
             Some(EditorMode::default())
         }
-        keybind!(Open) => {
+        _ if Action::Find.pressed(&event) => {
+            chooser.toggle_find();
+            None
+        }
+        _ if Action::Open.pressed(&event) => {
             if let Err(err) = chooser.toggle_source(open_dir)
                 && let Some(buf) = layout.selected_buffer_list_mut().current_mut()
             {
@@ -2062,6 +2858,42 @@ fn process_open_file<S: ChooserSource>(
     }
 }
 
+fn process_save_as<S: ChooserSource>(
+    layout: &mut Layout,
+    chooser: &mut FileChooserState<S>,
+    open_dir: &mut OpenDir,
+    event: Event,
+) -> Option<EditorMode> {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+    match event {
+        key!(Enter) => {
+            let mut selected = chooser.select()?;
+            open_dir[chooser.target()] = Some(chooser.selected_dir().to_path_buf());
+            open_dir.last = Some(chooser.target());
+
+            let (_, buf, alt) = layout.current_buffer_mut()?;
+            match (selected.pop(), selected.is_empty()) {
+                (Some(source), true) if source.exists() => {
+                    Some(EditorMode::VerifySaveAs { source })
+                }
+                (Some(source), true) => {
+                    if buf.save_as(source, alt).is_ok() {
+                        set_title(buf);
+                    }
+                    Some(EditorMode::default())
+                }
+                _ => {
+                    buf.set_error("Choose Only One File to Save To");
+                    Some(EditorMode::default())
+                }
+            }
+        }
+        // everything else navigates the chooser just like opening
+        event => process_open_file(layout, chooser, open_dir, event),
+    }
+}
+
 // which mode to switch to next
 enum NextModeIncremental {
     Browse {
@@ -2085,13 +2917,13 @@ fn process_search(
     range: Option<&SelectionRange>,
     event: Event,
 ) -> Option<NextModeIncremental> {
-    use crate::buffer::Normalizations;
+    use crate::buffer::{IgnoreCase, Normalizations, WholeWord};
     use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
     static NOT_FOUND: &str = "Not Found";
 
     match event {
-        ctrl_keybind!(Paste) => {
+        _ if Action::Paste.pressed(&event) => {
             let c = cut_buffer?;
             let b = c.paste_and_rotate();
             if b.multi_line() {
@@ -2126,10 +2958,7 @@ fn process_search(
         key!(Tab) => {
             if prompt.is_empty() {
                 prompt.reset();
-                *type_ = match *type_ {
-                    SearchType::Plain => SearchType::Regex,
-                    SearchType::Regex => SearchType::Plain,
-                };
+                *type_ = type_.next();
                 None
             } else {
                 let (offset, search) = prompt.autocomplete_word()?;
@@ -2153,10 +2982,7 @@ fn process_search(
         key!(SHIFT, BackTab) => {
             if prompt.is_empty() {
                 prompt.reset();
-                *type_ = match *type_ {
-                    SearchType::Plain => SearchType::Regex,
-                    SearchType::Regex => SearchType::Plain,
-                };
+                *type_ = type_.previous();
                 None
             } else {
                 let (offset, search) = prompt.autocomplete_word()?;
@@ -2200,6 +3026,28 @@ fn process_search(
                     }
                 },
             },
+            SearchType::IgnoreCase => {
+                match buffer.all_matches(range, IgnoreCase(prompt.value()?)) {
+                    Ok((match_idx, matches)) => {
+                        *last_search = Some(std::mem::take(prompt));
+                        Some(NextModeIncremental::Browse { match_idx, matches })
+                    }
+                    Err(_) => {
+                        buffer.set_error(NOT_FOUND);
+                        None
+                    }
+                }
+            }
+            SearchType::WholeWord => match buffer.all_matches(range, WholeWord(prompt.value()?)) {
+                Ok((match_idx, matches)) => {
+                    *last_search = Some(std::mem::take(prompt));
+                    Some(NextModeIncremental::Browse { match_idx, matches })
+                }
+                Err(_) => {
+                    buffer.set_error(NOT_FOUND);
+                    None
+                }
+            },
             SearchType::Regex => match prompt.value()?.parse::<fancy_regex::Regex>() {
                 Ok(regex) => match buffer.all_matches(range, regex) {
                     Ok((match_idx, matches)) => {
@@ -2217,8 +3065,23 @@ fn process_search(
                 }
             },
         },
-        keybind!(GotoLine) => Some(NextModeIncremental::SelectLine),
-        keybind!(Find) => {
+        key!(ALT, 'c') => {
+            match type_ {
+                SearchType::Plain => match Normalizations::try_from(prompt.value()?) {
+                    Err(term) => buffer.count_matches(range, term),
+                    Ok(normalizations) => buffer.count_matches(range, normalizations),
+                },
+                SearchType::IgnoreCase => buffer.count_matches(range, IgnoreCase(prompt.value()?)),
+                SearchType::WholeWord => buffer.count_matches(range, WholeWord(prompt.value()?)),
+                SearchType::Regex => match prompt.value()?.parse::<fancy_regex::Regex>() {
+                    Ok(regex) => buffer.count_matches(range, regex),
+                    Err(err) => buffer.set_error(err.to_string()),
+                },
+            }
+            None
+        }
+        _ if Action::GotoLine.pressed(&event) => Some(NextModeIncremental::SelectLine),
+        _ if Action::Find.pressed(&event) => {
             if prompt.is_empty()
                 && let Some(last) = last_search
             {
@@ -2256,13 +3119,13 @@ fn process_search_all(
     type_: &mut SearchType,
     event: Event,
 ) -> Option<NextModeIncrementalAll> {
-    use crate::buffer::Normalizations;
+    use crate::buffer::{IgnoreCase, Normalizations, WholeWord};
     use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
     static NOT_FOUND: &str = "Not Found";
 
     match event {
-        ctrl_keybind!(Paste) => {
+        _ if Action::Paste.pressed(&event) => {
             let c = cut_buffer?;
             let b = c.paste_and_rotate();
             if b.multi_line() {
@@ -2296,10 +3159,7 @@ fn process_search_all(
         key!(Tab) => {
             if prompt.is_empty() {
                 prompt.reset();
-                *type_ = match *type_ {
-                    SearchType::Plain => SearchType::Regex,
-                    SearchType::Regex => SearchType::Plain,
-                };
+                *type_ = type_.next();
                 None
             } else {
                 let (offset, search) = prompt.autocomplete_word()?;
@@ -2323,10 +3183,7 @@ fn process_search_all(
         key!(SHIFT, BackTab) => {
             if prompt.is_empty() {
                 prompt.reset();
-                *type_ = match *type_ {
-                    SearchType::Plain => SearchType::Regex,
-                    SearchType::Regex => SearchType::Plain,
-                };
+                *type_ = type_.previous();
                 None
             } else {
                 let (offset, search) = prompt.autocomplete_word()?;
@@ -2370,6 +3227,26 @@ fn process_search_all(
                     }
                 },
             },
+            SearchType::IgnoreCase => match buffer_list.all_matches(IgnoreCase(prompt.value()?)) {
+                Ok((match_idx, matches)) => {
+                    *last_search = Some(std::mem::take(prompt));
+                    Some(NextModeIncrementalAll::Browse { match_idx, matches })
+                }
+                Err(_) => {
+                    buffer_list.current_mut()?.set_error(NOT_FOUND);
+                    None
+                }
+            },
+            SearchType::WholeWord => match buffer_list.all_matches(WholeWord(prompt.value()?)) {
+                Ok((match_idx, matches)) => {
+                    *last_search = Some(std::mem::take(prompt));
+                    Some(NextModeIncrementalAll::Browse { match_idx, matches })
+                }
+                Err(_) => {
+                    buffer_list.current_mut()?.set_error(NOT_FOUND);
+                    None
+                }
+            },
             SearchType::Regex => match prompt.value()?.parse::<fancy_regex::Regex>() {
                 Ok(regex) => match buffer_list.all_matches(regex) {
                     Ok((match_idx, matches)) => {
@@ -2387,8 +3264,8 @@ fn process_search_all(
                 }
             },
         },
-        keybind!(GotoLine) => Some(NextModeIncrementalAll::SelectLine),
-        keybind!(Find) => {
+        _ if Action::GotoLine.pressed(&event) => Some(NextModeIncrementalAll::SelectLine),
+        _ if Action::Find.pressed(&event) => {
             if prompt.is_empty()
                 && let Some(last) = last_search
             {
@@ -2459,7 +3336,7 @@ fn process_multi_cursor(
                 None => Some(EditorMode::default()),
             }
         }
-        keybind!(Find) => Some(EditorMode::Search {
+        _ if Action::Find.pressed(&event) => Some(EditorMode::Search {
             search: Search {
                 prompt: TextField::default(),
                 type_: SearchType::default(),
@@ -2467,7 +3344,7 @@ fn process_multi_cursor(
             },
             range: range.take(),
         }),
-        keybind!(SelectInside) => {
+        _ if Action::SelectInside.pressed(&event) => {
             *highlight = false;
             buffer.multi_select_inside(matches, *match_idx);
             None
@@ -2513,63 +3390,81 @@ fn process_multi_cursor(
             buffer.multi_cursor_end(matches, modifiers.contains(KeyModifiers::SHIFT));
             None
         }
-        ctrl_keybind!(Paste) => match matches.iter().map(|m| m.paste_group_count()).max() {
-            Some(Some(total)) => Some(EditorMode::SingleBuffer {
-                cursors: MultiCursors {
-                    matches: std::mem::take(matches),
-                    match_idx: std::mem::take(match_idx),
-                    highlight: std::mem::take(highlight),
-                    mode: MultiCursorMode::PasteGroup { total: total.get() },
-                },
-                range: range.take(),
-            }),
-            _ => {
-                if let Some(cut) = cut_buffer {
-                    buffer.multi_paste(alt, matches, cut);
+        key!(ALT, 'e') => {
+            *highlight = false;
+            buffer.multi_unescape(alt, matches);
+            None
+        }
+        key!(ALT, 'v') => {
+            if let Some(cut) = cut_buffer {
+                *highlight = false;
+                buffer.multi_paste_matching_case(alt, matches, cut);
+            }
+            None
+        }
+        _ if Action::Paste.pressed(&event) => {
+            match matches.iter().map(|m| m.paste_group_count()).max() {
+                Some(Some(total)) => Some(EditorMode::SingleBuffer {
+                    cursors: MultiCursors {
+                        matches: std::mem::take(matches),
+                        match_idx: std::mem::take(match_idx),
+                        highlight: std::mem::take(highlight),
+                        mode: MultiCursorMode::PasteGroup { total: total.get() },
+                    },
+                    range: range.take(),
+                }),
+                _ => {
+                    if let Some(cut) = cut_buffer {
+                        buffer.multi_paste(alt, matches, cut);
+                    }
+                    None
                 }
-                None
             }
-        },
-        ctrl_keybind!(Copy) => {
+        }
+        _ if Action::Copy.pressed(&event) => {
             let cut = buffer.multi_cursor_copy(matches);
             match cut.len() {
                 0 => { /* do nothing */ }
                 1 => {
                     buffer.set_message("Copied 1 Item");
                     *highlight = false;
+                    crate::clipboard::copy(&EditorCutBuffer::joined(&cut));
                     *cut_buffer = Some(EditorCutBuffer::Multiple(cut));
                 }
                 n => {
                     buffer.set_message(format!("Copied {n} Items"));
                     *highlight = false;
+                    crate::clipboard::copy(&EditorCutBuffer::joined(&cut));
                     *cut_buffer = Some(EditorCutBuffer::Multiple(cut));
                 }
             }
             None
         }
-        ctrl_keybind!(Cut) => {
+        _ if Action::Cut.pressed(&event) => {
             let cut = buffer.multi_cursor_cut(alt, matches);
             match cut.len() {
                 0 => { /* do nothing */ }
                 1 => {
                     buffer.set_message("Cut 1 Item");
                     *highlight = false;
+                    crate::clipboard::copy(&EditorCutBuffer::joined(&cut));
                     *cut_buffer = Some(EditorCutBuffer::Multiple(cut));
                 }
                 n => {
                     buffer.set_message(format!("Cut {n} Items"));
                     *highlight = false;
+                    crate::clipboard::copy(&EditorCutBuffer::joined(&cut));
                     *cut_buffer = Some(EditorCutBuffer::Multiple(cut));
                 }
             }
             None
         }
-        keybind!(WidenSelection) => {
+        _ if Action::WidenSelection.pressed(&event) => {
             *highlight = false;
             buffer.multi_cursor_widen(matches);
             None
         }
-        keybind!(Bookmark) => {
+        _ if Action::Bookmark.pressed(&event) => {
             *highlight = false;
             let toggled = buffer.toggle_bookmarks(matches.iter().map(|m| m.cursor()));
             if let Ok(msg) = toggled.try_into() {
@@ -2661,7 +3556,7 @@ fn process_multi_cursor(
             }
             None
         }
-        ctrl_keybind!(Mark) => Some(EditorMode::SingleBuffer {
+        _ if Action::Mark.pressed(&event) => Some(EditorMode::SingleBuffer {
             cursors: MultiCursors {
                 matches: std::mem::take(matches),
                 match_idx: std::mem::take(match_idx),
@@ -2723,7 +3618,7 @@ fn process_multi_cursor_mark_set(
             buffer.multi_cursor_end(matches, true);
             Ok(None)
         }
-        ctrl_keybind!(Mark) => Err(()),
+        _ if Action::Mark.pressed(&event) => Err(()),
         event => Ok(Some(event)),
     }
 }
@@ -2813,14 +3708,14 @@ fn process_multi_cursor_all(
                 }
             }
         }
-        keybind!(Find) => Some(EditorMode::SearchAll {
+        _ if Action::Find.pressed(&event) => Some(EditorMode::SearchAll {
             search: Search {
                 prompt: TextField::default(),
                 type_: SearchType::default(),
                 mode: SearchMode::Editing,
             },
         }),
-        keybind!(SelectInside) => {
+        _ if Action::SelectInside.pressed(&event) => {
             *highlight = false;
             layout.on_global(matches, |buffer, matches| {
                 buffer.multi_select_inside(matches, *match_idx);
@@ -2876,7 +3771,7 @@ fn process_multi_cursor_all(
             });
             None
         }
-        ctrl_keybind!(Paste) => match matches
+        _ if Action::Paste.pressed(&event) => match matches
             .values()
             .flat_map(|m| m.iter().map(|m| m.paste_group_count()))
             .max()
@@ -2898,7 +3793,7 @@ fn process_multi_cursor_all(
                 None
             }
         },
-        ctrl_keybind!(Copy) => {
+        _ if Action::Copy.pressed(&event) => {
             let mut cut = vec![];
             layout.on_global(matches, |buffer, matches| {
                 cut.extend(buffer.multi_cursor_copy(matches));
@@ -2907,6 +3802,7 @@ fn process_multi_cursor_all(
                 0 => { /* do nothing */ }
                 1 => {
                     *highlight = false;
+                    crate::clipboard::copy(&EditorCutBuffer::joined(&cut));
                     *cut_buffer = Some(EditorCutBuffer::Multiple(cut));
                     layout
                         .selected_buffer_list_mut()
@@ -2915,6 +3811,7 @@ fn process_multi_cursor_all(
                 }
                 n => {
                     *highlight = false;
+                    crate::clipboard::copy(&EditorCutBuffer::joined(&cut));
                     *cut_buffer = Some(EditorCutBuffer::Multiple(cut));
                     layout
                         .selected_buffer_list_mut()
@@ -2924,7 +3821,7 @@ fn process_multi_cursor_all(
             }
             None
         }
-        ctrl_keybind!(Cut) => {
+        _ if Action::Cut.pressed(&event) => {
             let mut cut = vec![];
             layout.on_global_at(matches, |buffer, alt, matches| {
                 cut.extend(buffer.multi_cursor_cut(alt, matches));
@@ -2933,6 +3830,7 @@ fn process_multi_cursor_all(
                 0 => { /* do nothing */ }
                 1 => {
                     *highlight = false;
+                    crate::clipboard::copy(&EditorCutBuffer::joined(&cut));
                     *cut_buffer = Some(EditorCutBuffer::Multiple(cut));
                     layout
                         .selected_buffer_list_mut()
@@ -2941,6 +3839,7 @@ fn process_multi_cursor_all(
                 }
                 n => {
                     *highlight = false;
+                    crate::clipboard::copy(&EditorCutBuffer::joined(&cut));
                     *cut_buffer = Some(EditorCutBuffer::Multiple(cut));
                     layout
                         .selected_buffer_list_mut()
@@ -2950,14 +3849,14 @@ fn process_multi_cursor_all(
             }
             None
         }
-        keybind!(WidenSelection) => {
+        _ if Action::WidenSelection.pressed(&event) => {
             *highlight = false;
             layout.on_global(matches, |buffer, matches| {
                 buffer.multi_cursor_widen(matches);
             });
             None
         }
-        keybind!(Bookmark) => {
+        _ if Action::Bookmark.pressed(&event) => {
             use crate::buffer::ToggledBookmarks;
 
             *highlight = false;
@@ -3117,7 +4016,7 @@ fn process_multi_cursor_all(
             }
             None
         }
-        ctrl_keybind!(Mark) => Some(EditorMode::AllBuffers {
+        _ if Action::Mark.pressed(&event) => Some(EditorMode::AllBuffers {
             cursors: MultiCursors {
                 matches: std::mem::take(matches),
                 match_idx: std::mem::take(match_idx),
@@ -3186,7 +4085,7 @@ fn process_multi_cursor_mark_set_all(
             });
             Ok(None)
         }
-        ctrl_keybind!(Mark) => Err(()),
+        _ if Action::Mark.pressed(&event) => Err(()),
         event => Ok(Some(event)),
     }
 }
@@ -3223,7 +4122,7 @@ fn process_paste_group(
 
             buf.multi_insert_group(alt, matches, group);
         }
-        ctrl_keybind!(Paste) => {
+        _ if Action::Paste.pressed(&event) => {
             if let Some(cut) = cut_buffer {
                 buf.multi_paste(alt, matches, cut);
             }
@@ -3265,7 +4164,7 @@ fn process_paste_group_all(
                 buf.multi_insert_group(alt, matches, group);
             });
         }
-        ctrl_keybind!(Paste) => {
+        _ if Action::Paste.pressed(&event) => {
             if let Some(cut) = cut_buffer {
                 layout.on_global_at(matches, |buf, alt, matches| {
                     buf.multi_paste(alt, matches, cut);
@@ -3385,10 +4284,10 @@ fn process_select_buffer(
             .select_buffer(char_to_index(c)?)
             .ok()
             .map(|_| SelectBuffer::Finish),
-        keybind!(Save) => Some(SelectBuffer::SaveAll),
-        keybind!(Find) => Some(SelectBuffer::FindAll),
-        keybind!(Reload) => Some(SelectBuffer::ReloadAll),
-        keybind!(Quit) => Some(SelectBuffer::QuitAll),
+        _ if Action::Save.pressed(&event) => Some(SelectBuffer::SaveAll),
+        _ if Action::Find.pressed(&event) => Some(SelectBuffer::FindAll),
+        _ if Action::Reload.pressed(&event) => Some(SelectBuffer::ReloadAll),
+        _ if Action::Quit.pressed(&event) => Some(SelectBuffer::QuitAll),
         _ => None, // ignore other events
     }
 }
@@ -3432,6 +4331,141 @@ impl Default for Layout {
 }
 
 impl Layout {
+    /// Local files open in each pane and their cursor positions
+    fn session(&self) -> Session {
+        match self {
+            Self::Single(list) => {
+                let current = list.current().map(|buf| buf.id());
+                let mut index = 0;
+                let mut files = vec![];
+                for buf in list.buffers() {
+                    if let Some(path) = buf.local_path() {
+                        if current.as_ref() == Some(&buf.id()) {
+                            index = files.len();
+                        }
+                        files.push((std::path::absolute(&path).unwrap_or(path), buf.cursor()));
+                    }
+                }
+                Session::Single {
+                    files,
+                    current: index,
+                }
+            }
+            Self::Horizontal {
+                top,
+                top_fill,
+                bottom,
+                bottom_fill,
+                which,
+            } => Session::Horizontal {
+                top: Box::new(top.session()),
+                top_fill: *top_fill,
+                bottom: Box::new(bottom.session()),
+                bottom_fill: *bottom_fill,
+                bottom_focused: matches!(which, HorizontalPos::Bottom),
+            },
+            Self::Vertical {
+                left,
+                left_fill,
+                right,
+                right_fill,
+                which,
+            } => Session::Vertical {
+                left: Box::new(left.session()),
+                left_fill: *left_fill,
+                right: Box::new(right.session()),
+                right_fill: *right_fill,
+                right_focused: matches!(which, VerticalPos::Right),
+            },
+        }
+    }
+
+    /// Reopens panes from session, sharing buffers opened in several,
+    /// and collapsing any panes whose files no longer open
+    fn restore(
+        session: Session,
+        opened: &mut HashMap<PathBuf, BufferContext>,
+        skipped: &mut Vec<String>,
+    ) -> Option<Self> {
+        match session {
+            Session::Single { files, current } => {
+                let mut list = BufferList::default();
+                let mut index = 0;
+                for (i, (path, cursor)) in files.into_iter().enumerate() {
+                    let mut buf = match opened.get(&path) {
+                        Some(buf) => buf.clone(),
+                        None => match path
+                            .exists()
+                            .then(|| BufferContext::open(Source::from(path.clone())))
+                        {
+                            Some(Ok(buf)) => opened.entry(path).or_insert(buf).clone(),
+                            Some(Err(_)) | None => {
+                                let path = path.display().to_string();
+                                if !skipped.contains(&path) {
+                                    skipped.push(path);
+                                }
+                                continue;
+                            }
+                        },
+                    };
+                    buf.set_cursor(cursor);
+                    if i <= current {
+                        index = list.len();
+                    }
+                    list.push(buf, false);
+                }
+                list.set_index(index);
+                (!list.is_empty()).then_some(Self::Single(list))
+            }
+            Session::Horizontal {
+                top,
+                top_fill,
+                bottom,
+                bottom_fill,
+                bottom_focused,
+            } => match (
+                Self::restore(*top, opened, skipped),
+                Self::restore(*bottom, opened, skipped),
+            ) {
+                (Some(top), Some(bottom)) => Some(Self::Horizontal {
+                    top: Box::new(top),
+                    top_fill,
+                    bottom: Box::new(bottom),
+                    bottom_fill,
+                    which: if bottom_focused {
+                        HorizontalPos::Bottom
+                    } else {
+                        HorizontalPos::Top
+                    },
+                }),
+                (layout, None) | (None, layout) => layout,
+            },
+            Session::Vertical {
+                left,
+                left_fill,
+                right,
+                right_fill,
+                right_focused,
+            } => match (
+                Self::restore(*left, opened, skipped),
+                Self::restore(*right, opened, skipped),
+            ) {
+                (Some(left), Some(right)) => Some(Self::Vertical {
+                    left: Box::new(left),
+                    left_fill,
+                    right: Box::new(right),
+                    right_fill,
+                    which: if right_focused {
+                        VerticalPos::Right
+                    } else {
+                        VerticalPos::Left
+                    },
+                }),
+                (layout, None) | (None, layout) => layout,
+            },
+        }
+    }
+
     fn has_open_buffers(&self) -> bool {
         match self {
             Self::Single(b) => !b.is_empty(),
@@ -3784,7 +4818,7 @@ impl Layout {
     fn on_global_offset_at(
         &mut self,
         matches: &mut BTreeMap<usize, Vec<MultiCursor>>,
-        offsets: &BTreeMap<usize, Vec<usize>>,
+        offsets: &BufferOffsets,
         mut f: impl FnMut(&mut BufferContext, Vec<AltCursor<'_>>, &mut [MultiCursor], &[usize]),
     ) {
         let (buffer_list, mut alts) = self.current_buffer_list_mut();
@@ -3958,6 +4992,124 @@ impl Layout {
         }
     }
 
+    /// Moves focus to the next pane, wrapping around to the first
+    fn focus_next(&mut self) -> Option<&BufferContext> {
+        if self.try_focus_next().is_err() {
+            self.focus_first();
+        }
+        self.selected_buffer_list().current()
+    }
+
+    /// Moves focus to the previous pane, wrapping around to the last
+    fn focus_prev(&mut self) -> Option<&BufferContext> {
+        if self.try_focus_prev().is_err() {
+            self.focus_last();
+        }
+        self.selected_buffer_list().current()
+    }
+
+    // Err if already focused on the last pane
+    fn try_focus_next(&mut self) -> Result<(), ()> {
+        match self {
+            Self::Single(_) => Err(()),
+            Self::Horizontal {
+                which: which @ HorizontalPos::Top,
+                top,
+                bottom,
+                ..
+            } => top.try_focus_next().or_else(|()| {
+                *which = HorizontalPos::Bottom;
+                bottom.focus_first();
+                Ok(())
+            }),
+            Self::Vertical {
+                which: which @ VerticalPos::Left,
+                left,
+                right,
+                ..
+            } => left.try_focus_next().or_else(|()| {
+                *which = VerticalPos::Right;
+                right.focus_first();
+                Ok(())
+            }),
+            Self::Horizontal {
+                which: HorizontalPos::Bottom,
+                bottom: active,
+                ..
+            }
+            | Self::Vertical {
+                which: VerticalPos::Right,
+                right: active,
+                ..
+            } => active.try_focus_next(),
+        }
+    }
+
+    // Err if already focused on the first pane
+    fn try_focus_prev(&mut self) -> Result<(), ()> {
+        match self {
+            Self::Single(_) => Err(()),
+            Self::Horizontal {
+                which: which @ HorizontalPos::Bottom,
+                top,
+                bottom,
+                ..
+            } => bottom.try_focus_prev().or_else(|()| {
+                *which = HorizontalPos::Top;
+                top.focus_last();
+                Ok(())
+            }),
+            Self::Vertical {
+                which: which @ VerticalPos::Right,
+                left,
+                right,
+                ..
+            } => right.try_focus_prev().or_else(|()| {
+                *which = VerticalPos::Left;
+                left.focus_last();
+                Ok(())
+            }),
+            Self::Horizontal {
+                which: HorizontalPos::Top,
+                top: active,
+                ..
+            }
+            | Self::Vertical {
+                which: VerticalPos::Left,
+                left: active,
+                ..
+            } => active.try_focus_prev(),
+        }
+    }
+
+    fn focus_first(&mut self) {
+        match self {
+            Self::Single(_) => { /* nothing to do */ }
+            Self::Horizontal { which, top, .. } => {
+                *which = HorizontalPos::Top;
+                top.focus_first();
+            }
+            Self::Vertical { which, left, .. } => {
+                *which = VerticalPos::Left;
+                left.focus_first();
+            }
+        }
+    }
+
+    fn focus_last(&mut self) {
+        match self {
+            Self::Single(_) => { /* nothing to do */ }
+            Self::Horizontal { which, bottom, .. } => {
+                *which = HorizontalPos::Bottom;
+                bottom.focus_last();
+            }
+            Self::Vertical { which, right, .. } => {
+                *which = VerticalPos::Right;
+                right.focus_last();
+            }
+        }
+    }
+
     fn swap_pane(&mut self, direction: Direction) -> Result<(), &mut BufferList> {
         match (self, direction) {
             (Self::Single(buflist), _) => Err(buflist),
@@ -4206,6 +5358,7 @@ impl Layout {
             area: Rect,
             (row, col): (usize, usize),
             mode: Option<&EditorMode>,
+            tab_width: usize,
         ) -> Option<Position> {
             use ratatui::widgets::Block;
 
@@ -4226,17 +5379,19 @@ impl Layout {
                     }
                     | EditorMode::SearchAll {
                         search: Search { prompt, .. },
-                    },
+                    }
+                    | EditorMode::ShellFilter { prompt },
                 ) => {
                     let [_, dialog_area, _] =
                         Layout::vertical([Min(0), Length(3), Min(0)]).areas(text_area);
                     let dialog_area = Block::bordered().inner(dialog_area);
                     Some(Position {
-                        x: dialog_area.x + (prompt.cursor_column() as u16).min(dialog_area.width),
+                        x: dialog_area.x
+                            + (prompt.cursor_column(tab_width) as u16).min(dialog_area.width),
                         y: dialog_area.y,
                     })
                 }
-                Some(EditorMode::Open { chooser }) => {
+                Some(EditorMode::Open { chooser } | EditorMode::SaveAs { chooser }) => {
                     let (x, y) = chooser.cursor_position();
                     Some(Position {
                         x: text_area.x + x,
@@ -4248,7 +5403,7 @@ impl Layout {
 
                     let width = buffer_list
                         .iter()
-                        .map(|bid| match u16::try_from(bid.to_string().width()) {
+                        .map(|bid| match u16::try_from(bid.list_name().width()) {
                             Ok(w) => w.saturating_add(4),
                             Err(_) => u16::MAX,
                         })
@@ -4286,8 +5441,11 @@ impl Layout {
 
         match self {
             Self::Single(buf) => buf
-                .cursor_viewport_position(BufferWidget::viewport_height(area))
-                .and_then(|pos| apply_position(area, pos, mode)),
+                .cursor_viewport_position(
+                    BufferWidget::viewport_height(area),
+                    BufferWidget::viewport_width(area),
+                )
+                .and_then(|pos| apply_position(area, pos, mode, buf.tab_width())),
             Self::Horizontal {
                 top,
                 which: HorizontalPos::Top,
@@ -4345,7 +5503,10 @@ impl Layout {
     ///
     /// Given an onscreen row and column, sets focus somewhere
     /// in the editor if possible.
-    fn set_cursor_focus(&mut self, mut area: Rect, position: Position) {
+    ///
+    /// If selecting, focus remains in the current pane
+    /// and text is selected up to the given position.
+    fn set_cursor_focus(&mut self, mut area: Rect, position: Position, selecting: bool) {
         use ratatui::layout::{
             Constraint::{Length, Min},
             Layout,
@@ -4354,6 +5515,9 @@ impl Layout {
         if let Some((_, tabs)) = self.selected_buffer_list().tabs() {
             let [tabs_area, layout_area] = Layout::vertical([Length(1), Min(0)]).areas(area);
             if tabs_area.contains(position) {
+                if selecting {
+                    return;
+                }
                 let mut col = position.x;
                 for (index, tab) in tabs.into_iter().enumerate() {
                     use unicode_width::UnicodeWidthStr;
@@ -4374,16 +5538,16 @@ impl Layout {
             area = layout_area;
         }
 
-        self.set_cursor_focus_inner(area, position);
+        self.set_cursor_focus_inner(area, position, selecting);
     }
 
     /// set_cursor_focus, but with tabs already accounted for
-    fn set_cursor_focus_inner(&mut self, area: Rect, position: Position) {
+    fn set_cursor_focus_inner(&mut self, area: Rect, position: Position, selecting: bool) {
         use ratatui::layout::{Constraint, Layout};
 
         match self {
             Self::Single(buffer) => {
-                buffer.set_cursor_focus(area, position);
+                buffer.set_cursor_focus(area, position, selecting);
             }
             Self::Horizontal {
                 top,
@@ -4395,12 +5559,22 @@ impl Layout {
                 let [top_area, bottom_area] =
                     Layout::vertical(Constraint::from_fills([*top_fill, *bottom_fill])).areas(area);
 
-                if top_area.contains(position) {
-                    *which = HorizontalPos::Top;
-                    top.set_cursor_focus_inner(top_area, position);
-                } else if bottom_area.contains(position) {
-                    *which = HorizontalPos::Bottom;
-                    bottom.set_cursor_focus_inner(bottom_area, position);
+                match which {
+                    HorizontalPos::Top if selecting => {
+                        top.set_cursor_focus_inner(top_area, position, selecting);
+                    }
+                    HorizontalPos::Bottom if selecting => {
+                        bottom.set_cursor_focus_inner(bottom_area, position, selecting);
+                    }
+                    _ if top_area.contains(position) => {
+                        *which = HorizontalPos::Top;
+                        top.set_cursor_focus_inner(top_area, position, selecting);
+                    }
+                    _ if bottom_area.contains(position) => {
+                        *which = HorizontalPos::Bottom;
+                        bottom.set_cursor_focus_inner(bottom_area, position, selecting);
+                    }
+                    _ => { /* outside of any pane */ }
                 }
             }
             Self::Vertical {
@@ -4414,12 +5588,22 @@ impl Layout {
                     Layout::horizontal(Constraint::from_fills([*left_fill, *right_fill]))
                         .areas(area);
 
-                if left_area.contains(position) {
-                    *which = VerticalPos::Left;
-                    left.set_cursor_focus_inner(left_area, position);
-                } else if right_area.contains(position) {
-                    *which = VerticalPos::Right;
-                    right.set_cursor_focus_inner(right_area, position);
+                match which {
+                    VerticalPos::Left if selecting => {
+                        left.set_cursor_focus_inner(left_area, position, selecting);
+                    }
+                    VerticalPos::Right if selecting => {
+                        right.set_cursor_focus_inner(right_area, position, selecting);
+                    }
+                    _ if left_area.contains(position) => {
+                        *which = VerticalPos::Left;
+                        left.set_cursor_focus_inner(left_area, position, selecting);
+                    }
+                    _ if right_area.contains(position) => {
+                        *which = VerticalPos::Right;
+                        right.set_cursor_focus_inner(right_area, position, selecting);
+                    }
+                    _ => { /* outside of any pane */ }
                 }
             }
         }
@@ -4709,12 +5893,21 @@ impl StatefulWidget for LayoutWidget<'_> {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct LineNumber {
     line: usize,
     column: Option<usize>,
 }
 
+impl LineNumber {
+    fn select(self, buf: &mut BufferContext) {
+        match self.column {
+            None => buf.select_line(self.line),
+            Some(column) => buf.select_line_and_column(self.line, column),
+        }
+    }
+}
+
 impl std::str::FromStr for LineNumber {
     type Err = InvalidLine;
 
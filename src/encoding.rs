@@ -0,0 +1,273 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use std::io::{Error, ErrorKind};
+use std::sync::LazyLock;
+
+/// Whether to open files which appear to be binary anyway
+static OPEN_BINARY: LazyLock<bool> = LazyLock::new(|| {
+    std::env::var("VLE_OPEN_BINARY")
+        .map(|s| s.trim() != "0")
+        .unwrap_or(false)
+});
+
+/// How many bytes at the start of a file to check for binary data
+const BINARY_SNIFF_LEN: usize = 8192;
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16_BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// Text encodings which files are read and written in
+///
+/// Rather than pulling in encoding_rs, which decodes many encodings
+/// but detects none of them, these are limited to what can be told apart
+/// from the data alone: byte order marks identify UTF-8 and UTF-16,
+/// data without one is UTF-8 if it's valid as such,
+/// and anything else is taken as Latin-1, into which any bytes decode
+/// and whose characters can all be saved back as the bytes they came from.
+/// The standard library handles all of those by itself.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Utf8, // UTF-8 without byte order mark
+    Utf8Bom, // UTF-8 with byte order mark
+    Utf16Le, // little-endian UTF-16 with byte order mark
+    Utf16Be, // big-endian UTF-16 with byte order mark
+    Latin1,  // ISO-8859-1, used for anything else which isn't UTF-8
+}
+
+impl Encoding {
+    /// Some user-visible name to display in the title bar, if not default
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            Self::Utf8 => None,
+            Self::Utf8Bom => Some("UTF-8 BOM"),
+            Self::Utf16Le => Some("UTF-16LE"),
+            Self::Utf16Be => Some("UTF-16BE"),
+            Self::Latin1 => Some("Latin-1"),
+        }
+    }
+
+    /// Reads string from reader, probing for its encoding
+    /// and decoding it if necessary.
+    ///
+    /// Refuses to read files which appear to be binary
+    /// unless VLE_OPEN_BINARY is set.
+    pub fn reader_to_string<R>(mut r: R) -> std::io::Result<(Self, String)>
+    where
+        R: std::io::Read,
+    {
+        let mut bytes = vec![];
+        r.read_to_end(&mut bytes)?;
+
+        let encoding = if bytes.starts_with(UTF8_BOM) {
+            Self::Utf8Bom
+        } else if bytes.starts_with(UTF16_LE_BOM) {
+            Self::Utf16Le
+        } else if bytes.starts_with(UTF16_BE_BOM) {
+            Self::Utf16Be
+        } else if !*OPEN_BINARY && looks_binary(&bytes) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "file appears to be binary",
+            ));
+        } else if std::str::from_utf8(&bytes).is_ok() {
+            Self::Utf8
+        } else {
+            Self::Latin1
+        };
+
+        encoding.decode(bytes).map(|s| (encoding, s))
+    }
+
+    /// Reads string from reader using our encoding
+    pub fn reader_to_string_as<R>(self, mut r: R) -> std::io::Result<String>
+    where
+        R: std::io::Read,
+    {
+        let mut bytes = vec![];
+        r.read_to_end(&mut bytes)?;
+        self.decode(bytes)
+    }
+
+    /// Decodes bytes to string, stripping any byte order mark
+    fn decode(self, mut bytes: Vec<u8>) -> std::io::Result<String> {
+        fn decode_utf16(bytes: &[u8], word: fn([u8; 2]) -> u16) -> std::io::Result<String> {
+            let (pairs, []) = bytes.as_chunks::<2>() else {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "UTF-16 data has an odd number of bytes",
+                ));
+            };
+
+            char::decode_utf16(pairs.iter().copied().map(word))
+                .collect::<Result<String, _>>()
+                .map_err(|err| Error::new(ErrorKind::InvalidData, err))
+        }
+
+        match self {
+            Self::Utf8 => {
+                String::from_utf8(bytes).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+            }
+            Self::Utf8Bom => {
+                if bytes.starts_with(UTF8_BOM) {
+                    bytes.drain(0..UTF8_BOM.len());
+                }
+                String::from_utf8(bytes).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+            }
+            Self::Utf16Le => decode_utf16(
+                bytes.strip_prefix(UTF16_LE_BOM).unwrap_or(&bytes),
+                u16::from_le_bytes,
+            ),
+            Self::Utf16Be => decode_utf16(
+                bytes.strip_prefix(UTF16_BE_BOM).unwrap_or(&bytes),
+                u16::from_be_bytes,
+            ),
+            Self::Latin1 => Ok(bytes.into_iter().map(char::from).collect()),
+        }
+    }
+
+    /// Writes rope to writer using our encoding and the given line endings
+    pub fn rope_to_writer<W>(
+        self,
+        endings: crate::endings::LineEndings,
+        rope: &ropey::Rope,
+        mut w: W,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        /// The rope's text with the given line endings
+        fn text(
+            endings: crate::endings::LineEndings,
+            rope: &ropey::Rope,
+        ) -> std::io::Result<String> {
+            let mut utf8 = vec![];
+            endings.rope_to_writer(rope, &mut utf8)?;
+            String::from_utf8(utf8).map_err(|err| Error::new(ErrorKind::InvalidData, err))
+        }
+
+        fn encode_utf16(s: &str, bom: &[u8], bytes: fn(u16) -> [u8; 2]) -> Vec<u8> {
+            bom.iter()
+                .copied()
+                .chain(s.encode_utf16().flat_map(bytes))
+                .collect()
+        }
+
+        match self {
+            Self::Utf8 => endings.rope_to_writer(rope, w),
+            Self::Utf8Bom => {
+                w.write_all(UTF8_BOM)?;
+                endings.rope_to_writer(rope, w)
+            }
+            Self::Utf16Le => w.write_all(&encode_utf16(
+                &text(endings, rope)?,
+                UTF16_LE_BOM,
+                u16::to_le_bytes,
+            )),
+            Self::Utf16Be => w.write_all(&encode_utf16(
+                &text(endings, rope)?,
+                UTF16_BE_BOM,
+                u16::to_be_bytes,
+            )),
+            Self::Latin1 => w.write_all(
+                &text(endings, rope)?
+                    .chars()
+                    .map(|c| {
+                        u8::try_from(c).map_err(|_| {
+                            Error::new(
+                                ErrorKind::InvalidData,
+                                format!("{c} cannot be saved as Latin-1"),
+                            )
+                        })
+                    })
+                    .collect::<std::io::Result<Vec<u8>>>()?,
+            ),
+        }
+    }
+}
+
+/// Whether data contains NUL bytes or is mostly control characters
+/// (aside from whitespace and escapes) near its start
+fn looks_binary(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(BINARY_SNIFF_LEN)];
+
+    sample.contains(&0)
+        || sample
+            .iter()
+            .filter(|b| b.is_ascii_control() && !b.is_ascii_whitespace() && **b != 0x1b)
+            .count()
+            * 10
+            > sample.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::endings::LineEndings;
+
+    /// Reads bytes, writes them back and returns what was read
+    fn round_trip(bytes: &[u8]) -> (Encoding, String) {
+        let (encoding, s) = Encoding::reader_to_string(bytes).unwrap();
+        let (endings, rope, _) = LineEndings::string_to_rope(s.clone());
+        let mut written = vec![];
+        encoding
+            .rope_to_writer(endings, &rope, &mut written)
+            .unwrap();
+        assert_eq!(written, bytes, "{:?}", encoding.name());
+        (encoding, s)
+    }
+
+    #[test]
+    fn encodings_round_trip() {
+        let text = "caf\u{e9} \u{1f600}\r\nline\r\n";
+        let utf16 = |bom: &[u8], bytes: fn(u16) -> [u8; 2]| {
+            bom.iter()
+                .copied()
+                .chain(text.encode_utf16().flat_map(bytes))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(round_trip(text.as_bytes()), (Encoding::Utf8, text.into()));
+        assert_eq!(
+            round_trip(&[UTF8_BOM, text.as_bytes()].concat()),
+            (Encoding::Utf8Bom, text.into())
+        );
+        assert_eq!(
+            round_trip(&utf16(UTF16_LE_BOM, u16::to_le_bytes)),
+            (Encoding::Utf16Le, text.into())
+        );
+        assert_eq!(
+            round_trip(&utf16(UTF16_BE_BOM, u16::to_be_bytes)),
+            (Encoding::Utf16Be, text.into())
+        );
+        assert_eq!(
+            round_trip(b"caf\xe9\nna\xefve\n"),
+            (Encoding::Latin1, "caf\u{e9}\nna\u{ef}ve\n".into())
+        );
+    }
+
+    #[test]
+    fn malformed_utf16_is_rejected() {
+        // an odd number of bytes
+        assert!(Encoding::reader_to_string(&[0xFF, 0xFE, b'a', 0, b'b'][..]).is_err());
+        // an unpaired surrogate
+        assert!(Encoding::reader_to_string(&[0xFF, 0xFE, 0x00, 0xD8, b'a', 0][..]).is_err());
+    }
+
+    #[test]
+    fn unencodable_text_is_rejected() {
+        let rope = ropey::Rope::from("\u{1f600}");
+        assert!(
+            Encoding::Latin1
+                .rope_to_writer(LineEndings::default(), &rope, &mut vec![])
+                .is_err()
+        );
+    }
+}
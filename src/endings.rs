@@ -6,11 +6,12 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-#[derive(Copy, Clone, Default)]
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
 pub enum LineEndings {
     #[default]
     Lf, // Unix-style line-endings
     CrLf, // MS-DOS style line-endings
+    Cr,   // Classic Mac OS style line-endings
 }
 
 impl LineEndings {
@@ -19,60 +20,65 @@ impl LineEndings {
         match self {
             Self::Lf => None,
             Self::CrLf => Some("DOS"),
+            Self::Cr => Some("Mac"),
         }
     }
 
-    /// Create rope from reader, probes for its line endings
-    /// and converts to Unix-style if necessary.
-    pub fn reader_to_rope<R>(r: R) -> std::io::Result<(Self, ropey::Rope)>
-    where
-        R: std::io::Read,
-    {
-        use std::io::{BufRead, BufReader};
+    /// Full user-visible name of line endings style
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Lf => "Unix Line Endings",
+            Self::CrLf => "DOS Line Endings",
+            Self::Cr => "Mac Line Endings",
+        }
+    }
 
-        let mut rope = ropey::RopeBuilder::default();
-        let mut reader = BufReader::new(r);
-        let mut line = String::default();
+    /// Cycles to the next style of line endings
+    pub fn next(self) -> Self {
+        match self {
+            Self::Lf => Self::CrLf,
+            Self::CrLf => Self::Cr,
+            Self::Cr => Self::Lf,
+        }
+    }
 
+    /// Create rope from string, probes for its line endings
+    /// and converts to Unix-style if necessary.
+    ///
+    /// Also returns whether any line endings were inconsistent
+    /// with the first line's.
+    pub fn string_to_rope(s: String) -> (Self, ropey::Rope, bool) {
         // probe the file's first line for its ending, if any
-        reader.read_line(&mut line)?;
-        let endings = match line.ends_with("\r\n") {
-            true => LineEndings::CrLf,
-            false => LineEndings::Lf,
+        // (can't simply read lines since those split on \n alone)
+        let endings = match s.find(['\r', '\n']).map(|idx| &s[idx..]) {
+            Some(ending) if ending.starts_with("\r\n") => LineEndings::CrLf,
+            Some(ending) if ending.starts_with('\r') => LineEndings::Cr,
+            _ => LineEndings::Lf,
         };
 
-        // transfer all lines from reader to rope,
-        // converting \r\n to \n if necessary
-        while !line.is_empty() {
-            // Just because the first line ends with \r\n
-            // doesn't mean we can assume all of them do (or the reverse).
-            // For mixed-endings files, we update them to be consistent
-            // with the first line's endings,
-            // which appears to be how Nano does things.
-            if line.ends_with("\r\n") {
-                assert_eq!(line.pop(), Some('\n'));
-                assert_eq!(line.pop(), Some('\r'));
-                line.push('\n');
-            }
-            rope.append(&line);
-            line.clear();
-            reader.read_line(&mut line)?;
-        }
-
-        Ok((endings, rope.finish()))
+        // Just because the first line ends with \r\n
+        // doesn't mean we can assume all of them do (or the reverse).
+        // For mixed-endings files, we update them to be consistent
+        // with the first line's endings,
+        // which appears to be how Nano does things.
+        let mixed = match endings {
+            Self::Lf => s.contains("\r\n"),
+            Self::CrLf => s.matches('\n').count() != s.matches("\r\n").count(),
+            Self::Cr => s.contains('\n'),
+        };
+        let mut rope = ropey::RopeBuilder::default();
+        rope.append(&endings.to_unix(s));
+        (endings, rope.finish(), mixed)
     }
 
-    /// Reads string from reader using our line endings,
-    /// converting to Unix-style if necessary.
-    pub fn reader_to_string<R>(self, mut r: R) -> std::io::Result<String>
-    where
-        R: std::io::Read,
-    {
-        let mut s = String::new();
-        r.read_to_string(&mut s)?;
+    /// Converts string's line endings to Unix-style
+    ///
+    /// Lone \r characters are only considered line endings
+    /// for Classic Mac OS style files.
+    pub fn to_unix(self, s: String) -> String {
         match self {
-            Self::Lf => Ok(s),
-            Self::CrLf => Ok(s.replace("\r\n", "\n")),
+            Self::Lf | Self::CrLf => s.replace("\r\n", "\n"),
+            Self::Cr => s.replace("\r\n", "\n").replace('\r', "\n"),
         }
     }
 
@@ -92,6 +98,9 @@ impl LineEndings {
                 }
                 Ok(())
             }),
+            Self::Cr => rope
+                .chunks()
+                .try_for_each(|chunk| w.write_all(chunk.replace('\n', "\r").as_bytes())),
         }
     }
 }
@@ -21,6 +21,15 @@ const TEXT_WIDTH: u16 = 30;
 /// Size of each page, in rows
 const PAGE_SIZE: usize = 10;
 
+/// How many directories deep to search for files to find
+const FIND_MAX_DEPTH: usize = 8;
+
+/// Maximum number of files to search through
+const FIND_MAX_FILES: usize = 10000;
+
+/// Directories of generated files not worth searching through
+const FIND_SKIPPED_DIRS: &[&str] = &["target", "node_modules", "__pycache__"];
+
 pub trait ChooserSource: Clone + std::fmt::Display {
     type Error: std::fmt::Display;
 
@@ -30,6 +39,9 @@ pub trait ChooserSource: Clone + std::fmt::Display {
 
     fn open(&self, path: PathBuf) -> Source;
 
+    /// Creates new, empty directory at path
+    fn make_dir(&self, path: &Path) -> Result<(), Self::Error>;
+
     fn target(&self) -> DirTarget;
 
     /// Returns whether target can be toggled
@@ -90,6 +102,10 @@ impl ChooserSource for LocalSource {
         Source::Local(path)
     }
 
+    fn make_dir(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::create_dir(path)
+    }
+
     fn target(&self) -> DirTarget {
         DirTarget::Local
     }
@@ -154,6 +170,10 @@ impl ChooserSource for SshSource {
         }
     }
 
+    fn make_dir(&self, path: &Path) -> Result<(), Self::Error> {
+        self.remote.mkdir(path, 0o755)
+    }
+
     fn target(&self) -> DirTarget {
         DirTarget::Ssh
     }
@@ -258,6 +278,22 @@ impl ChooserSource for EitherSource {
         }
     }
 
+    fn make_dir(&self, path: &Path) -> Result<(), Self::Error> {
+        match self {
+            Self::Local(local)
+            | Self::Ssh {
+                local,
+                active: DirTarget::Local,
+                ..
+            } => local.make_dir(path).map_err(RemoteError::Io),
+            Self::Ssh {
+                ssh,
+                active: DirTarget::Ssh,
+                ..
+            } => ssh.make_dir(path).map_err(RemoteError::Ssh),
+        }
+    }
+
     fn target(&self) -> DirTarget {
         match self {
             Self::Local(local)
@@ -321,7 +357,10 @@ impl<S: ChooserSource> StatefulWidget for FileChooser<S> {
         state: &mut FileChooserState<S>,
     ) {
         use crate::buffer::{BufferMessage, render_message};
-        use crate::help::{CREATE_FILE, OPEN_FILE, OPEN_FILE_TOGGLEABLE, render_help};
+        use crate::help::{
+            CREATE_DIR, CREATE_FILE, FILTER_FILES, FIND_FILE, OPEN_FILE, OPEN_FILE_TOGGLEABLE,
+            render_help,
+        };
         use crate::scrollbar::{Scrollbar, ScrollbarState};
         use ratatui::{
             layout::{
@@ -341,6 +380,15 @@ impl<S: ChooserSource> StatefulWidget for FileChooser<S> {
                 Span::styled(state.dir.display().to_string(), Style::default().bold()),
                 Span::raw("\u{2523}"),
             ]))
+            .title_top(match &state.filter {
+                Some(filter) => Line::from(vec![
+                    Span::raw("\u{252b}"),
+                    Span::styled(format!("Filter: {filter}"), Style::default().bold()),
+                    Span::raw("\u{2523}"),
+                ])
+                .right_aligned(),
+                None => Line::default(),
+            })
             .title_bottom(
                 Line::from(vec![
                     Span::raw("\u{252b}"),
@@ -370,7 +418,9 @@ impl<S: ChooserSource> StatefulWidget for FileChooser<S> {
                 .render(text_area, buf),
             Chosen::New(filename) => Paragraph::new(crate::truncate::line_start(
                 filename.value().unwrap_or_default().into(),
-                filename.cursor_column().saturating_sub(TEXT_WIDTH.into()),
+                filename
+                    .cursor_column(*crate::buffer::SPACES_PER_TAB)
+                    .saturating_sub(TEXT_WIDTH.into()),
             ))
             .block(
                 Block::bordered()
@@ -384,11 +434,48 @@ impl<S: ChooserSource> StatefulWidget for FileChooser<S> {
             })
             .block(Block::bordered().border_type(BorderType::Rounded))
             .render(text_area, buf),
+            Chosen::NewDir(name) => Paragraph::new(crate::truncate::line_start(
+                name.value().unwrap_or_default().into(),
+                name.cursor_column(*crate::buffer::SPACES_PER_TAB)
+                    .saturating_sub(TEXT_WIDTH.into()),
+            ))
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title("New Directory"),
+            )
+            .render(text_area, buf),
+            Chosen::Filter(filter) => Paragraph::new(crate::truncate::line_start(
+                filter.value().unwrap_or_default().into(),
+                filter
+                    .cursor_column(*crate::buffer::SPACES_PER_TAB)
+                    .saturating_sub(TEXT_WIDTH.into()),
+            ))
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title("Filter"),
+            )
+            .render(text_area, buf),
+            Chosen::Find(Find { prompt, .. }) => Paragraph::new(crate::truncate::line_start(
+                prompt.value().unwrap_or_default().into(),
+                prompt
+                    .cursor_column(*crate::buffer::SPACES_PER_TAB)
+                    .saturating_sub(TEXT_WIDTH.into()),
+            ))
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .title("Find File"),
+            )
+            .render(text_area, buf),
         }
 
         StatefulWidget::render(
             (match &state.chosen {
-                Chosen::Default | Chosen::New(_) => List::new(state.dir_entries()),
+                Chosen::Default | Chosen::New(_) | Chosen::NewDir(_) | Chosen::Filter(_) => {
+                    List::new(state.dir_entries())
+                }
                 Chosen::Selected(selected) => List::new(state.contents.iter().map(|e| {
                     if selected.contains(&e.path) {
                         format!("* {}", e.name)
@@ -396,6 +483,9 @@ impl<S: ChooserSource> StatefulWidget for FileChooser<S> {
                         format!("  {}", e.name)
                     }
                 })),
+                Chosen::Find(Find { files, matches, .. }) => {
+                    List::new(matches.iter().map(|idx| files[*idx].name.as_str()))
+                }
             })
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
             list_area,
@@ -406,14 +496,17 @@ impl<S: ChooserSource> StatefulWidget for FileChooser<S> {
         Scrollbar.render(
             scrollbar_area,
             buf,
-            &mut ScrollbarState::new(state.contents.len())
-                .viewport_content_length(list_area.height.into())
-                .position(
-                    state
-                        .selected_entry()
-                        .unwrap_or_default()
-                        .saturating_sub(list_area.height.into()),
-                ),
+            &mut ScrollbarState::new(match &state.chosen {
+                Chosen::Find(find) => find.matches.len(),
+                _ => state.contents.len(),
+            })
+            .viewport_content_length(list_area.height.into())
+            .position(
+                state
+                    .selected_entry()
+                    .unwrap_or_default()
+                    .saturating_sub(list_area.height.into()),
+            ),
         );
 
         render_help(
@@ -422,12 +515,15 @@ impl<S: ChooserSource> StatefulWidget for FileChooser<S> {
             match &state.chosen {
                 Chosen::Default | Chosen::Selected(_) => {
                     if state.source.toggleable() {
-                        OPEN_FILE_TOGGLEABLE
+                        &OPEN_FILE_TOGGLEABLE
                     } else {
-                        OPEN_FILE
+                        &OPEN_FILE
                     }
                 }
                 Chosen::New(_) => CREATE_FILE,
+                Chosen::Find(_) => &FIND_FILE,
+                Chosen::NewDir(_) => CREATE_DIR,
+                Chosen::Filter(_) => FILTER_FILES,
             },
             |b| {
                 if state.show_hidden {
@@ -445,15 +541,17 @@ impl<S: ChooserSource> StatefulWidget for FileChooser<S> {
 }
 
 pub struct FileChooserState<S: ChooserSource> {
-    cwd: PathBuf,          // editor's current working directory
-    dir: PathBuf,          // directory we've navigated to
-    contents: Vec<Entry>,  // directory entry
-    dir_count: usize,      // number of directories in contents
-    index: Option<usize>,  // index in directory entries
-    chosen: Chosen,        // either new file or chosen entries
-    error: Option<String>, // error message
-    source: S,             // file source
-    show_hidden: bool,     // whether to display hidden files
+    cwd: PathBuf,                     // editor's current working directory
+    dir: PathBuf,                     // directory we've navigated to
+    contents: Vec<Entry>,             // directory entry
+    dir_count: usize,                 // number of directories in contents
+    index: Option<usize>,             // index in directory entries
+    chosen: Chosen,                   // either new file or chosen entries
+    error: Option<String>,            // error message
+    source: S,                        // file source
+    show_hidden: bool,                // whether to display hidden files
+    completions: Option<Completions>, // filename completions being cycled
+    filter: Option<String>,           // glob which listed files must match
 }
 
 impl<S: ChooserSource> FileChooserState<S> {
@@ -475,12 +573,18 @@ impl<S: ChooserSource> FileChooserState<S> {
             error: None,
             source,
             show_hidden: false,
+            completions: None,
+            filter: None,
         })
     }
 
+    /// Reads new directory, retaining the current filter
     pub fn update_dir(&mut self, new_dir: PathBuf) {
         match self.source.read_dir(&new_dir, self.show_hidden) {
-            Ok(contents) => {
+            Ok(mut contents) => {
+                if let Some(filter) = &self.filter {
+                    contents.retain(|e| e.is_dir || glob_match(filter, &e.name));
+                }
                 self.dir_count = contents.iter().take_while(|e| e.is_dir).count();
                 self.contents = contents;
                 self.index = None;
@@ -511,7 +615,10 @@ impl<S: ChooserSource> FileChooserState<S> {
     }
 
     pub fn arrow_up(&mut self) {
-        if matches!(self.chosen, Chosen::Default | Chosen::Selected(_)) {
+        if matches!(
+            self.chosen,
+            Chosen::Default | Chosen::Selected(_) | Chosen::Find(_)
+        ) {
             self.index = match self.index {
                 None => max_index(&self.chosen, &self.contents, self.dir_count).checked_sub(1),
                 Some(i) => i.checked_sub(1).or_else(|| {
@@ -522,7 +629,10 @@ impl<S: ChooserSource> FileChooserState<S> {
     }
 
     pub fn arrow_down(&mut self) {
-        if matches!(self.chosen, Chosen::Default | Chosen::Selected(_)) {
+        if matches!(
+            self.chosen,
+            Chosen::Default | Chosen::Selected(_) | Chosen::Find(_)
+        ) {
             self.index = (match self.index {
                 None => Some(0),
                 Some(i) => Some(i + 1),
@@ -532,7 +642,10 @@ impl<S: ChooserSource> FileChooserState<S> {
     }
 
     pub fn page_up(&mut self) {
-        if matches!(self.chosen, Chosen::Default | Chosen::Selected(_)) {
+        if matches!(
+            self.chosen,
+            Chosen::Default | Chosen::Selected(_) | Chosen::Find(_)
+        ) {
             self.index = (match self.index {
                 None => Some(0),
                 Some(idx) => Some(idx.saturating_sub(PAGE_SIZE)),
@@ -542,7 +655,10 @@ impl<S: ChooserSource> FileChooserState<S> {
     }
 
     pub fn page_down(&mut self) {
-        if matches!(self.chosen, Chosen::Default | Chosen::Selected(_)) {
+        if matches!(
+            self.chosen,
+            Chosen::Default | Chosen::Selected(_) | Chosen::Find(_)
+        ) {
             self.index = match max_index(&self.chosen, &self.contents, self.dir_count) {
                 0 => None,
                 max => match self.index {
@@ -555,7 +671,7 @@ impl<S: ChooserSource> FileChooserState<S> {
 
     pub fn home(&mut self) {
         match &mut self.chosen {
-            Chosen::New(filename) => {
+            Chosen::New(filename) | Chosen::NewDir(filename) | Chosen::Filter(filename) => {
                 filename.cursor_home();
             }
             _ => {
@@ -569,7 +685,7 @@ impl<S: ChooserSource> FileChooserState<S> {
 
     pub fn end(&mut self) {
         match &mut self.chosen {
-            Chosen::New(filename) => {
+            Chosen::New(filename) | Chosen::NewDir(filename) | Chosen::Filter(filename) => {
                 filename.cursor_end();
             }
             _ => {
@@ -580,7 +696,12 @@ impl<S: ChooserSource> FileChooserState<S> {
 
     pub fn arrow_right(&mut self) {
         match &mut self.chosen {
-            Chosen::New(filename) => {
+            Chosen::New(filename)
+            | Chosen::NewDir(filename)
+            | Chosen::Filter(filename)
+            | Chosen::Find(Find {
+                prompt: filename, ..
+            }) => {
                 filename.cursor_forward();
             }
             _ => {
@@ -597,7 +718,12 @@ impl<S: ChooserSource> FileChooserState<S> {
 
     pub fn arrow_left(&mut self) {
         match &mut self.chosen {
-            Chosen::New(filename) => {
+            Chosen::New(filename)
+            | Chosen::NewDir(filename)
+            | Chosen::Filter(filename)
+            | Chosen::Find(Find {
+                prompt: filename, ..
+            }) => {
                 filename.cursor_back();
             }
             _ => {
@@ -611,6 +737,7 @@ impl<S: ChooserSource> FileChooserState<S> {
     }
 
     pub fn insert_char(&mut self, c: char) {
+        self.completions = None;
         match &mut self.chosen {
             Chosen::Default => {
                 self.chosen = Chosen::New({
@@ -624,15 +751,189 @@ impl<S: ChooserSource> FileChooserState<S> {
                 prompt.insert_char(c);
                 self.index = None;
             }
+            Chosen::Find(find) => {
+                find.prompt.insert_char(c);
+                find.update_matches();
+                self.index = (!find.matches.is_empty()).then_some(0);
+            }
+            Chosen::NewDir(prompt) | Chosen::Filter(prompt) => {
+                prompt.insert_char(c);
+            }
             Chosen::Selected(_) => { /* do nothing */ }
         }
     }
 
     pub fn backspace(&mut self) {
-        if let Chosen::New(prompt) = &mut self.chosen {
-            prompt.backspace();
-            if prompt.is_empty() {
-                self.chosen = Chosen::Default;
+        self.completions = None;
+        match &mut self.chosen {
+            Chosen::New(prompt) => {
+                prompt.backspace();
+                if prompt.is_empty() {
+                    self.chosen = Chosen::Default;
+                }
+            }
+            Chosen::Find(find) => {
+                find.prompt.backspace();
+                find.update_matches();
+                self.index = (!find.matches.is_empty()).then_some(0);
+            }
+            Chosen::NewDir(prompt) | Chosen::Filter(prompt) => {
+                prompt.backspace();
+            }
+            Chosen::Default | Chosen::Selected(_) => { /* do nothing */ }
+        }
+    }
+
+    /// Completes new filename, if any, or toggles selected file
+    pub fn tab(&mut self) {
+        match &mut self.chosen {
+            Chosen::New(_) => self.complete(),
+            Chosen::Find(_) | Chosen::NewDir(_) | Chosen::Filter(_) => { /* nothing to toggle */ }
+            Chosen::Default | Chosen::Selected(_) => self.toggle_selected(),
+        }
+    }
+
+    /// Begins prompting for new directory to create in the current one
+    pub fn new_dir(&mut self) {
+        self.index = None;
+        self.completions = None;
+        self.chosen = Chosen::NewDir(TextField::default());
+    }
+
+    /// Begins editing the glob which listed files must match
+    ///
+    /// The filter remains in effect when changing directories
+    /// until cleared by applying an empty one.
+    pub fn edit_filter(&mut self) {
+        let mut filter = TextField::default();
+        if let Some(glob) = &self.filter {
+            filter.paste(glob);
+        }
+        self.index = None;
+        self.completions = None;
+        self.chosen = Chosen::Filter(filter);
+    }
+
+    /// Toggles finding files anywhere below the current directory
+    pub fn toggle_find(&mut self) {
+        self.index = None;
+        self.completions = None;
+        self.chosen = match self.chosen {
+            Chosen::Find(_) => Chosen::Default,
+            _ => Chosen::Find(Find {
+                prompt: TextField::default(),
+                matches: vec![],
+                files: self.find_files(),
+            }),
+        };
+    }
+
+    /// Walks the current directory's tree, breadth-first,
+    /// skipping hidden directories and those of generated files
+    fn find_files(&self) -> Vec<Entry> {
+        let mut files = vec![];
+        let mut dirs = std::collections::VecDeque::from([(self.dir.clone(), 0)]);
+
+        while let Some((dir, depth)) = dirs.pop_front() {
+            let Ok(entries) = self.source.read_dir(&dir, false) else {
+                continue;
+            };
+            for entry in entries {
+                if entry.is_dir {
+                    if depth < FIND_MAX_DEPTH
+                        && !entry
+                            .path
+                            .file_name()
+                            .is_some_and(|n| FIND_SKIPPED_DIRS.iter().any(|s| n == *s))
+                    {
+                        dirs.push_back((entry.path, depth + 1));
+                    }
+                } else {
+                    files.push(Entry {
+                        name: match entry.path.strip_prefix(&self.dir) {
+                            Ok(relative) => relative.display().to_string(),
+                            Err(_) => entry.name,
+                        },
+                        path: entry.path,
+                        is_dir: false,
+                    });
+                    if files.len() >= FIND_MAX_FILES {
+                        return files;
+                    }
+                }
+            }
+        }
+
+        files
+    }
+
+    /// Completes new filename's final path component against
+    /// the entries of its directory, filling in the longest
+    /// shared prefix of multiple matches, or cycling through
+    /// those matches if there's no prefix left to fill
+    fn complete(&mut self) {
+        let Chosen::New(prompt) = &mut self.chosen else {
+            return;
+        };
+
+        if let Some(Completions {
+            offset,
+            current,
+            matches,
+            index,
+        }) = &mut self.completions
+        {
+            *index = (*index + 1) % matches.len();
+            prompt.autocomplete(*offset, current, &matches[*index]);
+            *current = matches[*index].clone();
+            return;
+        }
+
+        let text = prompt.chars().collect::<String>();
+        let (dir, base) = match text.rfind(['/', std::path::MAIN_SEPARATOR]) {
+            Some(idx) => text.split_at(idx + 1),
+            None => ("", text.as_str()),
+        };
+
+        let Ok(entries) = self.source.read_dir(
+            &self.dir.join(dir),
+            self.show_hidden || base.starts_with('.'),
+        ) else {
+            return;
+        };
+
+        let matches = entries
+            .into_iter()
+            .map(|e| e.name)
+            .filter(|name| name.starts_with(base))
+            .collect::<Vec<_>>();
+
+        let offset = dir.chars().count();
+        match matches.as_slice() {
+            [] => { /* nothing to complete */ }
+            [only] => prompt.autocomplete(offset, base, only),
+            [first, rest @ ..] => {
+                let prefix = rest.iter().fold(first.as_str(), |prefix, name| {
+                    let len = prefix
+                        .char_indices()
+                        .zip(name.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map(|((idx, _), _)| idx)
+                        .unwrap_or(prefix.len().min(name.len()));
+                    &prefix[..len]
+                });
+
+                if prefix.len() > base.len() {
+                    prompt.autocomplete(offset, base, prefix);
+                } else {
+                    prompt.autocomplete(offset, base, first);
+                    self.completions = Some(Completions {
+                        offset,
+                        current: first.clone(),
+                        matches,
+                        index: 0,
+                    });
+                }
             }
         }
     }
@@ -658,7 +959,9 @@ impl<S: ChooserSource> FileChooserState<S> {
                         }
                     }
                 }
-                Chosen::New(_) => { /* this shouldn't be possible */ }
+                Chosen::New(_) | Chosen::NewDir(_) | Chosen::Find(_) | Chosen::Filter(_) => {
+                    /* this shouldn't be possible */
+                }
             }
         }
     }
@@ -695,13 +998,52 @@ impl<S: ChooserSource> FileChooserState<S> {
                     .map(|path| self.source.open(strip_cwd(&self.cwd, &path)))
                     .collect(),
             ),
+            Chosen::NewDir(name) => {
+                if let Some(name) = name.value() {
+                    let path = self.dir.join(name);
+                    match self.source.make_dir(&path) {
+                        Ok(()) => {
+                            self.update_dir(self.dir.clone());
+                            self.index = self.contents.iter().position(|e| e.path == path);
+                        }
+                        Err(err) => {
+                            self.error = Some(err.to_string());
+                        }
+                    }
+                }
+                None
+            }
+            Chosen::Filter(filter) => {
+                self.filter = filter.value();
+                self.update_dir(self.dir.clone());
+                None
+            }
+            Chosen::Find(find) => {
+                let found = find
+                    .matches
+                    .get(self.index.unwrap_or(0))
+                    .and_then(|idx| find.files.get(*idx))
+                    .map(|entry| vec![self.source.open(strip_cwd(&self.cwd, &entry.path))]);
+                if found.is_none() {
+                    self.chosen = Chosen::Find(find);
+                }
+                found
+            }
         }
     }
 
     pub fn cursor_position(&self) -> (u16, u16) {
         match &self.chosen {
             Chosen::Default => (1, 1),
-            Chosen::New(filename) => ((filename.cursor_column() as u16).min(TEXT_WIDTH) + 1, 1),
+            Chosen::New(filename)
+            | Chosen::NewDir(filename)
+            | Chosen::Filter(filename)
+            | Chosen::Find(Find {
+                prompt: filename, ..
+            }) => (
+                (filename.cursor_column(*crate::buffer::SPACES_PER_TAB) as u16).min(TEXT_WIDTH) + 1,
+                1,
+            ),
             Chosen::Selected(_) => (1, 1),
         }
     }
@@ -721,8 +1063,9 @@ impl<S: ChooserSource> FileChooserState<S> {
 
 fn max_index(chosen: &Chosen, contents: &[Entry], dir_count: usize) -> usize {
     match chosen {
-        Chosen::Default | Chosen::Selected(_) => contents.len(),
-        Chosen::New(_) => dir_count,
+        Chosen::Default | Chosen::Selected(_) | Chosen::Filter(_) => contents.len(),
+        Chosen::New(_) | Chosen::NewDir(_) => dir_count,
+        Chosen::Find(find) => find.matches.len(),
     }
 }
 
@@ -784,10 +1127,148 @@ impl From<(bool, PathBuf)> for Entry {
     }
 }
 
+/// Filename completions, cycled through by repeated tabs
+struct Completions {
+    offset: usize,        // character offset of completed component
+    current: String,      // completion currently in filename
+    matches: Vec<String>, // all possible completions
+    index: usize,         // index of current completion
+}
+
 #[derive(Default)]
 enum Chosen {
     #[default]
     Default, // nothing selected
     New(TextField),              // new file
     Selected(BTreeSet<PathBuf>), // selected existing file(s)
+    Find(Find),                  // file found anywhere in tree
+    NewDir(TextField),           // new directory
+    Filter(TextField),           // glob to filter files by
+}
+
+/// Files found below the current directory, filtered as typed
+struct Find {
+    prompt: TextField,   // characters to find
+    files: Vec<Entry>,   // all files found, by relative path
+    matches: Vec<usize>, // indexes of matching files, best first
+}
+
+impl Find {
+    fn update_matches(&mut self) {
+        let query = self.prompt.chars().collect::<String>();
+        let mut scored = self
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| Some((fuzzy_score(&query, &entry.name)?, idx)))
+            .collect::<Vec<_>>();
+        scored.sort_by(|(a_score, a_idx), (b_score, b_idx)| {
+            b_score.cmp(a_score).then_with(|| {
+                let (a, b) = (&self.files[*a_idx].name, &self.files[*b_idx].name);
+                a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+            })
+        });
+        self.matches = match query.is_empty() {
+            true => vec![],
+            false => scored.into_iter().map(|(_, idx)| idx).collect(),
+        };
+    }
+}
+
+/// Whether name matches glob, where * matches any run
+/// of characters and ? matches any single character
+fn glob_match(glob: &str, name: &str) -> bool {
+    let glob = glob.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    let (mut g, mut n) = (0, 0);
+    let mut star = None; // glob position after last *, and name position
+
+    while n < name.len() {
+        match glob.get(g) {
+            Some('*') => {
+                g += 1;
+                star = Some((g, n));
+            }
+            Some(c) if *c == '?' || *c == name[n] => {
+                g += 1;
+                n += 1;
+            }
+            _ => match &mut star {
+                Some((star_g, star_n)) => {
+                    *star_n += 1;
+                    g = *star_g;
+                    n = *star_n;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    glob[g..].iter().all(|c| *c == '*')
+}
+
+/// If all of query's characters appear in candidate, in order,
+/// returns a score which favors consecutive characters
+/// and those at the start of path components or words
+fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut consecutive = false;
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+
+    for c in candidate.chars() {
+        let Some(q) = query.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(*q)) {
+            query.next();
+            score += 1;
+            if consecutive {
+                score += 2;
+            }
+            if previous.is_none_or(|p| matches!(p, '/' | '\\' | '_' | '-' | '.' | ' ')) {
+                score += 3;
+            }
+            consecutive = true;
+        } else {
+            consecutive = false;
+        }
+        previous = Some(c);
+    }
+
+    query.peek().is_none().then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_scores() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "buffer.rs"), None);
+        // characters must appear in order
+        assert_eq!(fuzzy_score("sr", "rs"), None);
+        assert!(fuzzy_score("BUF", "src/buffer.rs").is_some());
+
+        // consecutive characters beat scattered ones
+        assert!(fuzzy_score("buf", "buffer.rs") > fuzzy_score("buf", "breakup_file.rs"));
+        // as do those starting path components
+        assert!(fuzzy_score("ed", "src/editor.rs") > fuzzy_score("ed", "src/need.rs"));
+    }
+
+    #[test]
+    fn glob_matches() {
+        assert!(glob_match("*.rs", "main.rs"));
+        assert!(glob_match("*.rs", ".rs"));
+        assert!(!glob_match("*.rs", "main.rsx"));
+        assert!(glob_match("?ain.*", "main.rs"));
+        assert!(!glob_match("?ain.*", "ain.rs"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("", "main.rs"));
+        // a star backtracks to match later occurrences
+        assert!(glob_match("*a*b", "aXaYb"));
+        assert!(glob_match("m**n.rs", "main.rs"));
+        assert!(!glob_match("*a*b", "aXaYbc"));
+    }
 }
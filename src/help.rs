@@ -6,22 +6,23 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::key;
-use crate::key::CtrlBinding;
+use crate::key::{Action, KEYMAP};
 use ratatui::widgets::Block;
+use std::borrow::Cow;
+use std::sync::LazyLock;
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct Keybinding {
     action: &'static str,
     modifier: Option<&'static str>,
-    keys: &'static [&'static str],
+    keys: Cow<'static, [&'static str]>,
     f: &'static str,
 }
 
 pub const fn shift(keys: &'static [&'static str], action: &'static str) -> Keybinding {
     Keybinding {
         modifier: Some("Shift"),
-        keys,
+        keys: Cow::Borrowed(keys),
         action,
         f: "",
     }
@@ -30,34 +31,69 @@ pub const fn shift(keys: &'static [&'static str], action: &'static str) -> Keybi
 pub const fn ctrl(keys: &'static [&'static str], action: &'static str) -> Keybinding {
     Keybinding {
         modifier: Some("Ctrl"),
-        keys,
+        keys: Cow::Borrowed(keys),
         action,
         f: "",
     }
 }
 
-pub const fn keybind<B: key::Binding>(action: &'static str) -> Keybinding {
+pub const fn alt(keys: &'static [&'static str], action: &'static str) -> Keybinding {
     Keybinding {
-        modifier: Some("Ctrl"),
-        keys: &[B::PRIMARY_LABEL],
+        modifier: Some("Alt"),
+        keys: Cow::Borrowed(keys),
         action,
-        f: B::SECONDARY_LABEL,
+        f: "",
     }
 }
 
-pub const fn ctrl_keybind<B: key::CtrlBinding>(action: &'static str) -> Keybinding {
+/// Help for the keys bound to the given action by the active keymap
+///
+/// Its primary shortcut is shown with other keys
+/// and any secondary shortcut alongside the function keys
+pub fn keybind(bound: Action, action: &'static str) -> Keybinding {
+    let shortcuts = KEYMAP.shortcuts(bound);
+
     Keybinding {
-        modifier: Some("Ctrl"),
-        keys: &[B::LABEL],
+        modifier: shortcuts.first().and_then(|s| s.modifier),
+        keys: match shortcuts.first() {
+            Some(primary) => Cow::Borrowed(std::slice::from_ref(&primary.key)),
+            None => Cow::Borrowed(&[]),
+        },
         action,
-        f: "",
+        f: shortcuts
+            .get(1)
+            .map(|s| s.label.as_str())
+            .unwrap_or_default(),
+    }
+}
+
+/// Help for several actions' primary shortcuts on a single line
+pub fn keybinds(bound: &[Action], action: &'static str) -> Keybinding {
+    let primary = bound
+        .iter()
+        .filter_map(|a| KEYMAP.shortcuts(*a).first())
+        .collect::<Vec<_>>();
+
+    match primary.split_first() {
+        Some((first, rest)) if rest.iter().all(|s| s.modifier == first.modifier) => Keybinding {
+            modifier: first.modifier,
+            keys: primary.iter().map(|s| s.key).collect(),
+            action,
+            f: "",
+        },
+        _ => Keybinding {
+            modifier: None,
+            keys: primary.iter().map(|s| s.label.as_str()).collect(),
+            action,
+            f: "",
+        },
     }
 }
 
 pub const fn none(keys: &'static [&'static str], action: &'static str) -> Keybinding {
     Keybinding {
         modifier: None,
-        keys,
+        keys: Cow::Borrowed(keys),
         action,
         f: "",
     }
@@ -195,7 +231,8 @@ pub fn render_main_help(
         widgets::{BorderType, Widget},
     };
 
-    let (f_keys, non_f_keys): (Vec<_>, Vec<_>) = keybindings.iter().partition(|k| !k.f.is_empty());
+    let (f_keys, non_f_keys): (Vec<_>, Vec<_>) =
+        keybindings.iter().cloned().partition(|k| !k.f.is_empty());
 
     let [_, non_f_area, f_area] = Layout::horizontal([
         Min(0),
@@ -217,41 +254,52 @@ pub fn render_main_help(
     }
 }
 
-static UP: &str = "\u{2191}";
-static DOWN: &str = "\u{2193}";
-static LEFT: &str = "\u{2190}";
-static RIGHT: &str = "\u{2192}";
-
-pub static EDITING_0: &[Keybinding] = &[
-    keybind::<key::Open>("Open File"),
-    keybind::<key::Save>("Save File"),
-];
-
-pub static F10: Keybinding = keybind::<key::SplitPane>("Manage Panes");
-
-pub static EDITING_2: &[Keybinding] = &[
-    keybind::<key::Reload>("Reload File"),
-    keybind::<key::Quit>("Quit File"),
-    keybind::<key::Bookmark>("Toggle Bookmark"),
-    shift(&[LEFT, DOWN, UP, RIGHT], "Highlight Text"),
-    ctrl_keybind::<key::Mark>("Set Mark"),
-];
-
-pub static EDITING_3: &[Keybinding] = &[
-    ctrl(
-        &[key::Cut::LABEL, key::Copy::LABEL, key::Paste::LABEL],
-        "Cut / Copy / Paste",
-    ),
-    ctrl(&[key::Undo::LABEL, key::Redo::LABEL], "Undo / Redo"),
-];
-
-pub static MARK_SET: &[Keybinding] = &[
-    none(
-        &[LEFT, DOWN, UP, RIGHT, "PgUp", "PgDn", "Home", "End"],
-        "Highlight Text",
-    ),
-    ctrl_keybind::<key::Mark>("Finish"),
-];
+const UP: &str = "\u{2191}";
+const DOWN: &str = "\u{2193}";
+const LEFT: &str = "\u{2190}";
+const RIGHT: &str = "\u{2192}";
+
+pub static EDITING_0: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        keybind(Action::Open, "Open File"),
+        keybind(Action::Save, "Save File"),
+        keybind(Action::Palette, "Command Palette"),
+    ]
+});
+
+pub static F10: LazyLock<Keybinding> = LazyLock::new(|| keybind(Action::SplitPane, "Manage Panes"));
+
+pub static EDITING_2: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        keybind(Action::Reload, "Reload File"),
+        keybind(Action::Quit, "Quit File"),
+        keybind(Action::Bookmark, "Toggle Bookmark"),
+        shift(&[LEFT, DOWN, UP, RIGHT], "Highlight Text"),
+        keybind(Action::Mark, "Set Mark"),
+        keybind(Action::SelectAll, "Select All"),
+    ]
+});
+
+pub static EDITING_3: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        keybinds(
+            &[Action::Cut, Action::Copy, Action::Paste],
+            "Cut / Copy / Paste",
+        ),
+        keybind(Action::Kill, "Cut to End of Line"),
+        keybinds(&[Action::Undo, Action::Redo], "Undo / Redo"),
+    ]
+});
+
+pub static MARK_SET: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(
+            &[LEFT, DOWN, UP, RIGHT, "PgUp", "PgDn", "Home", "End"],
+            "Highlight Text",
+        ),
+        keybind(Action::Mark, "Finish"),
+    ]
+});
 
 pub static SWITCH_PANE: Keybinding = ctrl(&[LEFT, DOWN, UP, RIGHT], "Switch Pane");
 
@@ -274,6 +322,7 @@ pub static SPLIT_PANE: &[Keybinding] = &[
     none(&[LEFT, RIGHT], "Split Vertically \u{25e7} / \u{25e8}"),
     none(&[UP, DOWN], "Split Horizontally \u{2b12} / \u{2b13}"),
     ctrl(&[LEFT, DOWN, UP, RIGHT], "Switch Pane in Direction"),
+    none(&["Tab"], "Switch to Next Pane"),
     shift(&[LEFT, DOWN, UP, RIGHT], "Swap Panes in Direction"),
     none(&["+", "-"], "Change Size Ratio"),
     none(&["Del"], "Delete Current Pane"),
@@ -291,93 +340,162 @@ pub static SELECT_INSIDE: &[Keybinding] = &[
     none(&["Esc"], "Cancel"),
 ];
 
-pub static SELECT_LINE: &[Keybinding] = &[
-    none(&["Enter"], "Select Line"),
-    none(&["Home"], "Goto First Line"),
-    none(&["End"], "Goto Last Line"),
-    keybind::<key::Find>("Find Text"),
+pub static SURROUND_WITH: &[Keybinding] = &[
+    none(&["(", ")"], "Surround With ( \u{2026} )"),
+    none(&["[", "]"], "Surround With [ \u{2026} ]"),
+    none(&["{", "}"], "Surround With { \u{2026} }"),
+    none(&["<", ">"], "Surround With < \u{2026} >"),
+    none(&["\""], "Surround With \" \u{2026} \""),
+    none(&["'"], "Surround With ' \u{2026} '"),
+    none(&["Del"], "Delete Surround"),
     none(&["Esc"], "Cancel"),
 ];
 
-pub static SELECT_LINE_BOOKMARKED: &[Keybinding] = &[
-    none(&["Enter"], "Select Line"),
-    none(&["Home"], "Goto First Line"),
-    none(&["End"], "Goto Last Line"),
-    none(&[UP, DOWN], "Select Bookmark"),
-    none(&["Del"], "Delete Bookmark"),
-    keybind::<key::Find>("Find Text"),
+pub static SELECT_LINE: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(&["Enter"], "Select Line"),
+        none(&["Home"], "Goto First Line"),
+        none(&["End"], "Goto Last Line"),
+        keybind(Action::Find, "Find Text"),
+        none(&["Esc"], "Cancel"),
+    ]
+});
+
+pub static SELECT_LINE_BOOKMARKED: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(&["Enter"], "Select Line"),
+        none(&["Home"], "Goto First Line"),
+        none(&["End"], "Goto Last Line"),
+        none(&[UP, DOWN], "Select Bookmark"),
+        none(&["Del"], "Delete Bookmark"),
+        keybind(Action::Find, "Find Text"),
+        none(&["Esc"], "Cancel"),
+    ]
+});
+
+pub static OPEN_FILE: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(&[DOWN, UP], "Navigate Entries"),
+        none(&[LEFT], "Up Directory"),
+        none(&[RIGHT], "Down Directory"),
+        none(&["Tab"], "Toggle File to Open"),
+        ctrl(&["H"], "Toggle Show Hidden Files"),
+        keybind(Action::Find, "Find File in Subdirectories"),
+        ctrl(&["G"], "Filter Files by Glob"),
+        ctrl(&["D"], "Create New Directory"),
+        none(&["Enter"], "Select File(s)"),
+        none(&["Esc"], "Cancel"),
+    ]
+});
+
+pub static OPEN_FILE_TOGGLEABLE: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(&[DOWN, UP], "Navigate Entries"),
+        none(&[LEFT], "Up Directory"),
+        none(&[RIGHT], "Down Directory"),
+        none(&["Tab"], "Toggle File to Open"),
+        ctrl(&["H"], "Toggle Show Hidden Files"),
+        keybind(Action::Find, "Find File in Subdirectories"),
+        ctrl(&["G"], "Filter Files by Glob"),
+        ctrl(&["D"], "Create New Directory"),
+        keybind(Action::Open, "Toggle Local/SSH Files"),
+        none(&["Enter"], "Select File(s)"),
+        none(&["Esc"], "Cancel"),
+    ]
+});
+
+pub static CREATE_DIR: &[Keybinding] = &[
+    none(&["Enter"], "Create New Directory"),
     none(&["Esc"], "Cancel"),
 ];
 
-pub static OPEN_FILE: &[Keybinding] = &[
-    none(&[DOWN, UP], "Navigate Entries"),
-    none(&[LEFT], "Up Directory"),
-    none(&[RIGHT], "Down Directory"),
-    none(&["Tab"], "Toggle File to Open"),
-    ctrl(&["H"], "Toggle Show Hidden Files"),
-    none(&["Enter"], "Select File(s)"),
+pub static FILTER_FILES: &[Keybinding] = &[
+    none(&["Enter"], "Apply Filter (Empty to Clear)"),
     none(&["Esc"], "Cancel"),
 ];
 
-pub static OPEN_FILE_TOGGLEABLE: &[Keybinding] = &[
-    none(&[DOWN, UP], "Navigate Entries"),
-    none(&[LEFT], "Up Directory"),
-    none(&[RIGHT], "Down Directory"),
-    none(&["Tab"], "Toggle File to Open"),
-    ctrl(&["H"], "Toggle Show Hidden Files"),
-    keybind::<key::Open>("Toggle Local/SSH Files"),
-    none(&["Enter"], "Select File(s)"),
-    none(&["Esc"], "Cancel"),
-];
+pub static FIND_FILE: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(&[DOWN, UP], "Navigate Matches"),
+        keybind(Action::Find, "Browse Directories"),
+        none(&["Enter"], "Select File"),
+        none(&["Esc"], "Cancel"),
+    ]
+});
 
 pub static CREATE_FILE: &[Keybinding] = &[
+    none(&["Tab"], "Complete Filename"),
     none(&["Enter"], "Create New File"),
     none(&["Esc"], "Cancel"),
 ];
 
-pub static REPLACE_MATCHES: &[Keybinding] = &[
-    none(&[UP, DOWN], "Select Match"),
-    ctrl(&["Del"], "Remove Match"),
-    keybind::<key::Find>("New Search"),
-    keybind::<key::SelectInside>("Select Inside Pairs"),
-    keybind::<key::WidenSelection>("Widen Selections"),
-    keybind::<key::Bookmark>("Bookmark Positions"),
-    none(&[LEFT, RIGHT], "Move Cursors"),
-    shift(&[LEFT, RIGHT], "Highlight Text"),
-    ctrl_keybind::<key::Mark>("Set Mark"),
-    ctrl(
-        &[key::Cut::LABEL, key::Copy::LABEL, key::Paste::LABEL],
-        "Cut / Copy / Paste",
-    ),
-    none(&["Enter"], "Finish"),
+pub static REPLACE_MATCHES: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(&[UP, DOWN], "Select Match"),
+        ctrl(&["Del"], "Remove Match"),
+        keybind(Action::Find, "New Search"),
+        keybind(Action::SelectInside, "Select Inside Pairs"),
+        keybind(Action::WidenSelection, "Widen Selections"),
+        keybind(Action::Bookmark, "Bookmark Positions"),
+        none(&[LEFT, RIGHT], "Move Cursors"),
+        shift(&[LEFT, RIGHT], "Highlight Text"),
+        keybind(Action::Mark, "Set Mark"),
+        keybinds(
+            &[Action::Cut, Action::Copy, Action::Paste],
+            "Cut / Copy / Paste",
+        ),
+        alt(&["V"], "Paste Matching Case"),
+        alt(&["E"], "Interpret Escapes"),
+        none(&["Enter"], "Finish"),
+    ]
+});
+
+pub static MULTICURSOR_MARK_SET: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(&[LEFT, RIGHT, "Home", "End"], "Highlight Text"),
+        keybind(Action::Mark, "Finish"),
+    ]
+});
+
+pub static PASTE_GROUP: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        keybind(Action::Paste, "Paste From Cut Buffer"),
+        none(&["0"], "Paste From Capture Group 0"),
+        none(&["1"], "Paste From Capture Group 1"),
+        none(&["2"], "Paste From Capture Group 2"),
+        none(&["3"], "Paste From Capture Group 3"),
+        none(&["4"], "Paste From Capture Group 4"),
+        none(&["5"], "Paste From Capture Group 5"),
+        none(&["6"], "Paste From Capture Group 6"),
+        none(&["7"], "Paste From Capture Group 7"),
+        none(&["8"], "Paste From Capture Group 8"),
+        none(&["9"], "Paste From Capture Group 9"),
+    ]
+});
+
+pub static SELECT_SYNTAX: &[Keybinding] = &[
+    none(&["A\u{2026}Z"], "Filter Syntaxes by Name"),
+    none(&[UP, DOWN], "Choose Syntax"),
+    none(&["Enter"], "Select Chosen Syntax"),
 ];
 
-pub static MULTICURSOR_MARK_SET: &[Keybinding] = &[
-    none(&[LEFT, RIGHT, "Home", "End"], "Highlight Text"),
-    ctrl_keybind::<key::Mark>("Finish"),
-];
+pub static SHELL_FILTER: &[Keybinding] = &[none(&["Enter"], "Filter Selection Through Command")];
 
-pub static PASTE_GROUP: &[Keybinding] = &[
-    ctrl(&[key::Paste::LABEL], "Paste From Cut Buffer"),
-    none(&["0"], "Paste From Capture Group 0"),
-    none(&["1"], "Paste From Capture Group 1"),
-    none(&["2"], "Paste From Capture Group 2"),
-    none(&["3"], "Paste From Capture Group 3"),
-    none(&["4"], "Paste From Capture Group 4"),
-    none(&["5"], "Paste From Capture Group 5"),
-    none(&["6"], "Paste From Capture Group 6"),
-    none(&["7"], "Paste From Capture Group 7"),
-    none(&["8"], "Paste From Capture Group 8"),
-    none(&["9"], "Paste From Capture Group 9"),
+pub static COMMAND_PALETTE: &[Keybinding] = &[
+    none(&["A\u{2026}Z"], "Filter Commands by Name"),
+    none(&[UP, DOWN], "Choose Command"),
+    none(&["Enter"], "Perform Chosen Command"),
 ];
 
-pub static SELECT_BUFFER: &[Keybinding] = &[
-    none(&["0\u{2026}9", "A\u{2026}Z"], "Select Buffer by Letter"),
-    none(&[UP, DOWN], "Choose Buffer"),
-    ctrl(&[UP, DOWN], "Swap Buffer Locations"),
-    none(&["Enter"], "Select Chosen Buffer"),
-    keybind::<key::Save>("Save All Buffers"),
-    keybind::<key::Find>("Find in All Buffers"),
-    keybind::<key::Reload>("Reload All Buffers"),
-    keybind::<key::Quit>("Quit All Buffers"),
-];
+pub static SELECT_BUFFER: LazyLock<Vec<Keybinding>> = LazyLock::new(|| {
+    vec![
+        none(&["0\u{2026}9", "A\u{2026}Z"], "Select Buffer by Letter"),
+        none(&[UP, DOWN], "Choose Buffer"),
+        ctrl(&[UP, DOWN], "Swap Buffer Locations"),
+        none(&["Enter"], "Select Chosen Buffer"),
+        keybind(Action::Save, "Save All Buffers"),
+        keybind(Action::Find, "Find in All Buffers"),
+        keybind(Action::Reload, "Reload All Buffers"),
+        keybind(Action::Quit, "Quit All Buffers"),
+    ]
+});
@@ -6,66 +6,403 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crossterm::event::KeyCode;
+use crate::config::CONFIG;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::collections::HashMap;
+use std::sync::LazyLock;
 
-pub trait Binding {
-    const PRIMARY_KEY: KeyCode;
-    const SECONDARY_KEY: KeyCode;
-    const PRIMARY_LABEL: &'static str;
-    const SECONDARY_LABEL: &'static str;
+/// The active keymap, which is the default bindings
+/// with any overrides from the config file applied
+pub static KEYMAP: LazyLock<Keymap> = LazyLock::new(|| {
+    let mut keymap = Keymap::default();
+    for (action, shortcuts) in CONFIG.keys.iter() {
+        keymap.bind(*action, shortcuts.clone());
+    }
+    keymap
+});
+
+/// An editor action which may be bound to keys
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Open,
+    Save,
+    GotoLine,
+    Find,
+    UpdateLines,
+    GotoPair,
+    SelectInside,
+    WidenSelection,
+    SplitPane,
+    Reload,
+    Quit,
+    Bookmark,
+    Cut,
+    Copy,
+    Kill,
+    Surround,
+    SelectAll,
+    Paste,
+    Undo,
+    Redo,
+    Mark,
+    Palette,
+    SaveAs,
+    UpdateBlock,
+    JumpBack,
+    JumpForward,
+    SelectBuffer,
+    PreviousBuffer,
+    NextBuffer,
+    PreviousPane,
+    NextPane,
+    Overwrite,
+    MoveLinesUp,
+    MoveLinesDown,
+    ToggleComment,
+    Reflow,
+    UpperCase,
+    LowerCase,
+    ToggleCase,
+    Filter,
+    SelectSyntax,
+    Highlighting,
+    LineNumbers,
+    Whitespace,
+    SoftWrap,
+    LineEndings,
+    Help,
 }
 
-macro_rules! binding {
-    ($name:ident, $primary:ident, $secondary:ident) => {
-        pub struct $name;
+impl Action {
+    const ALL: [Self; 47] = [
+        Self::Open,
+        Self::Save,
+        Self::GotoLine,
+        Self::Find,
+        Self::UpdateLines,
+        Self::GotoPair,
+        Self::SelectInside,
+        Self::WidenSelection,
+        Self::SplitPane,
+        Self::Reload,
+        Self::Quit,
+        Self::Bookmark,
+        Self::Cut,
+        Self::Copy,
+        Self::Kill,
+        Self::Surround,
+        Self::SelectAll,
+        Self::Paste,
+        Self::Undo,
+        Self::Redo,
+        Self::Mark,
+        Self::Palette,
+        Self::SaveAs,
+        Self::UpdateBlock,
+        Self::JumpBack,
+        Self::JumpForward,
+        Self::SelectBuffer,
+        Self::PreviousBuffer,
+        Self::NextBuffer,
+        Self::PreviousPane,
+        Self::NextPane,
+        Self::Overwrite,
+        Self::MoveLinesUp,
+        Self::MoveLinesDown,
+        Self::ToggleComment,
+        Self::Reflow,
+        Self::UpperCase,
+        Self::LowerCase,
+        Self::ToggleCase,
+        Self::Filter,
+        Self::SelectSyntax,
+        Self::Highlighting,
+        Self::LineNumbers,
+        Self::Whitespace,
+        Self::SoftWrap,
+        Self::LineEndings,
+        Self::Help,
+    ];
 
-        impl Binding for $name {
-            const PRIMARY_KEY: KeyCode = Key::$primary.to_char();
-            const SECONDARY_KEY: KeyCode = Key::$secondary.to_char();
-            const PRIMARY_LABEL: &'static str = Key::$primary.to_str();
-            const SECONDARY_LABEL: &'static str = Key::$secondary.to_str();
+    /// The action's name in the config file
+    fn name(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Save => "save",
+            Self::GotoLine => "goto_line",
+            Self::Find => "find",
+            Self::UpdateLines => "update_lines",
+            Self::GotoPair => "goto_pair",
+            Self::SelectInside => "select_inside",
+            Self::WidenSelection => "widen_selection",
+            Self::SplitPane => "split_pane",
+            Self::Reload => "reload",
+            Self::Quit => "quit",
+            Self::Bookmark => "bookmark",
+            Self::Cut => "cut",
+            Self::Copy => "copy",
+            Self::Kill => "kill",
+            Self::Surround => "surround",
+            Self::SelectAll => "select_all",
+            Self::Paste => "paste",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::Mark => "mark",
+            Self::Palette => "palette",
+            Self::SaveAs => "save_as",
+            Self::UpdateBlock => "update_block",
+            Self::JumpBack => "jump_back",
+            Self::JumpForward => "jump_forward",
+            Self::SelectBuffer => "select_buffer",
+            Self::PreviousBuffer => "previous_buffer",
+            Self::NextBuffer => "next_buffer",
+            Self::PreviousPane => "previous_pane",
+            Self::NextPane => "next_pane",
+            Self::Overwrite => "overwrite",
+            Self::MoveLinesUp => "move_lines_up",
+            Self::MoveLinesDown => "move_lines_down",
+            Self::ToggleComment => "toggle_comment",
+            Self::Reflow => "reflow",
+            Self::UpperCase => "upper_case",
+            Self::LowerCase => "lower_case",
+            Self::ToggleCase => "toggle_case",
+            Self::Filter => "filter",
+            Self::SelectSyntax => "select_syntax",
+            Self::Highlighting => "highlighting",
+            Self::LineNumbers => "line_numbers",
+            Self::Whitespace => "whitespace",
+            Self::SoftWrap => "soft_wrap",
+            Self::LineEndings => "line_endings",
+            Self::Help => "help",
         }
-    };
+    }
+
+    /// Keys bound to the action if the config file doesn't change them
+    fn default_keys(self) -> &'static [(KeyModifiers, Key)] {
+        const CTRL: KeyModifiers = KeyModifiers::CONTROL;
+        const NONE: KeyModifiers = KeyModifiers::NONE;
+        const ALT: KeyModifiers = KeyModifiers::ALT;
+
+        match self {
+            Self::Open => &[(CTRL, Key::O), (NONE, Key::F2)],
+            Self::Save => &[(CTRL, Key::S), (NONE, Key::F3)],
+            Self::GotoLine => &[(CTRL, Key::T), (NONE, Key::F4)],
+            Self::Find => &[(CTRL, Key::F), (NONE, Key::F5)],
+            Self::UpdateLines => &[(CTRL, Key::U), (NONE, Key::F6), (CTRL, Key::R)],
+            Self::GotoPair => &[(CTRL, Key::P), (NONE, Key::F7)],
+            Self::SelectInside => &[(CTRL, Key::E), (NONE, Key::F8)],
+            Self::WidenSelection => &[(CTRL, Key::W), (NONE, Key::F9)],
+            Self::SplitPane => &[(CTRL, Key::N), (NONE, Key::F10)],
+            Self::Reload => &[(CTRL, Key::L), (NONE, Key::F11)],
+            Self::Quit => &[(CTRL, Key::Q), (NONE, Key::F12)],
+            Self::Bookmark => &[(CTRL, Key::B), (NONE, Key::Insert)],
+            Self::Cut => &[(CTRL, Key::X)],
+            Self::Copy => &[(CTRL, Key::C)],
+            Self::Kill => &[(CTRL, Key::K)],
+            Self::Surround => &[(CTRL, Key::D)],
+            Self::SelectAll => &[(CTRL, Key::A)],
+            Self::Paste => &[(CTRL, Key::V)],
+            Self::Undo => &[(CTRL, Key::Z)],
+            Self::Redo => &[(CTRL, Key::Y)],
+            Self::Mark => &[(CTRL, Key::Space)],
+            Self::Palette => &[(ALT, Key::P)],
+            Self::SaveAs => &[(ALT, Key::A)],
+            Self::UpdateBlock => &[(ALT, Key::U)],
+            Self::JumpBack => &[(ALT, Key::Comma)],
+            Self::JumpForward => &[(ALT, Key::Period)],
+            Self::SelectBuffer => &[(CTRL, Key::RightBracket)],
+            Self::PreviousBuffer => &[(CTRL, Key::PageUp)],
+            Self::NextBuffer => &[(CTRL, Key::PageDown)],
+            Self::PreviousPane => &[(ALT, Key::PageUp)],
+            Self::NextPane => &[(ALT, Key::PageDown)],
+            Self::Overwrite => &[(ALT, Key::Insert)],
+            Self::MoveLinesUp => &[(ALT, Key::Up)],
+            Self::MoveLinesDown => &[(ALT, Key::Down)],
+            Self::ToggleComment => &[(CTRL, Key::Slash), (CTRL, Key::Seven)],
+            Self::Reflow => &[(ALT, Key::Q)],
+            Self::UpperCase => &[(ALT, Key::C)],
+            Self::LowerCase => &[(ALT, Key::L)],
+            Self::ToggleCase => &[(ALT, Key::T)],
+            Self::Filter => &[(ALT, Key::X)],
+            Self::SelectSyntax => &[(ALT, Key::S)],
+            Self::Highlighting => &[(ALT, Key::H)],
+            Self::LineNumbers => &[(ALT, Key::N)],
+            Self::Whitespace => &[(ALT, Key::W)],
+            Self::SoftWrap => &[(ALT, Key::Z)],
+            Self::LineEndings => &[(ALT, Key::R)],
+            Self::Help => &[(NONE, Key::F1)],
+        }
+    }
+
+    /// Whether the event is a keypress bound to this action
+    pub fn pressed(self, event: &Event) -> bool {
+        KEYMAP.action(event) == Some(self)
+    }
+}
+
+impl std::str::FromStr for Action {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or(())
+    }
 }
 
-binding!(Open, O, F2);
-binding!(Save, S, F3);
-binding!(GotoLine, T, F4);
-binding!(Find, F, F5);
-binding!(UpdateLines, U, F6);
-binding!(GotoPair, P, F7);
-binding!(SelectInside, E, F8);
-binding!(WidenSelection, W, F9);
-binding!(SplitPane, N, F10);
-binding!(Reload, L, F11);
-binding!(Quit, Q, F12);
-binding!(Bookmark, B, Insert);
-
-pub trait CtrlBinding {
-    const KEY: KeyCode;
-    const LABEL: &'static str;
+/// A key combination which may be bound to an action
+#[derive(Clone)]
+pub struct Shortcut {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+    pub modifier: Option<&'static str>, // modifier's label, if any
+    pub key: &'static str,              // key's label
+    pub label: String,                  // combined label, like "Ctrl-S"
 }
 
-macro_rules! ctrl_binding {
-    ($name:ident, $key:ident) => {
-        pub struct $name;
+impl Shortcut {
+    fn new(modifiers: KeyModifiers, key: Key) -> Self {
+        let modifier = if modifiers == KeyModifiers::CONTROL {
+            Some("Ctrl")
+        } else if modifiers == KeyModifiers::ALT {
+            Some("Alt")
+        } else {
+            None
+        };
 
-        impl CtrlBinding for $name {
-            const KEY: KeyCode = Key::$key.to_char();
-            const LABEL: &'static str = Key::$key.to_str();
+        Self {
+            code: key.to_char(),
+            modifiers,
+            modifier,
+            key: key.to_str(),
+            label: match modifier {
+                Some(modifier) => format!("{modifier}-{}", key.to_str()),
+                None => key.to_str().to_string(),
+            },
         }
-    };
+    }
 }
 
-ctrl_binding!(Cut, X);
-ctrl_binding!(Copy, C);
-ctrl_binding!(Paste, V);
-ctrl_binding!(Undo, Z);
-ctrl_binding!(Redo, Y);
-ctrl_binding!(Mark, Space);
+impl std::str::FromStr for Shortcut {
+    type Err = ();
+
+    /// Parses shortcuts like "ctrl-s", "alt-w" or "f3"
+    ///
+    /// Only function keys and Insert may be bound
+    /// without a modifier, since other keys insert text
+    fn from_str(s: &str) -> Result<Self, ()> {
+        fn key(s: &str) -> Result<Key, ()> {
+            Key::ALL
+                .into_iter()
+                .find(|key| key.to_str().eq_ignore_ascii_case(s))
+                .ok_or(())
+        }
+
+        match s.rsplit_once('-') {
+            Some((modifier, k)) if modifier.eq_ignore_ascii_case("ctrl") => {
+                Ok(Self::new(KeyModifiers::CONTROL, key(k)?))
+            }
+            Some((modifier, k)) if modifier.eq_ignore_ascii_case("alt") => {
+                Ok(Self::new(KeyModifiers::ALT, key(k)?))
+            }
+            Some(_) => Err(()),
+            None => match key(s)? {
+                k @ (Key::F1
+                | Key::F2
+                | Key::F3
+                | Key::F4
+                | Key::F5
+                | Key::F6
+                | Key::F7
+                | Key::F8
+                | Key::F9
+                | Key::F10
+                | Key::F11
+                | Key::F12
+                | Key::Insert) => Ok(Self::new(KeyModifiers::NONE, k)),
+                _ => Err(()),
+            },
+        }
+    }
+}
+
+/// Which keys are bound to which actions
+pub struct Keymap {
+    actions: HashMap<(KeyCode, KeyModifiers), Action>,
+    shortcuts: HashMap<Action, Vec<Shortcut>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let shortcuts = Action::ALL
+            .into_iter()
+            .map(|action| {
+                (
+                    action,
+                    action
+                        .default_keys()
+                        .iter()
+                        .map(|(modifiers, key)| Shortcut::new(*modifiers, *key))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        Self {
+            actions: shortcuts
+                .iter()
+                .flat_map(|(action, shortcuts)| {
+                    shortcuts.iter().map(|s| ((s.code, s.modifiers), *action))
+                })
+                .collect(),
+            shortcuts,
+        }
+    }
+}
+
+impl Keymap {
+    /// Replaces action's keys with the given shortcuts,
+    /// unbinding those shortcuts from any other action
+    fn bind(&mut self, action: Action, shortcuts: Vec<Shortcut>) {
+        for old in self.shortcuts.remove(&action).into_iter().flatten() {
+            self.actions.remove(&(old.code, old.modifiers));
+        }
+
+        for new in shortcuts.iter() {
+            if let Some(previous) = self.actions.insert((new.code, new.modifiers), action)
+                && let Some(previous) = self.shortcuts.get_mut(&previous)
+            {
+                previous.retain(|s| (s.code, s.modifiers) != (new.code, new.modifiers));
+            }
+        }
+
+        self.shortcuts.insert(action, shortcuts);
+    }
+
+    /// The action bound to the event's keypress, if any
+    pub fn action(&self, event: &Event) -> Option<Action> {
+        match event {
+            Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) => self.actions.get(&(*code, *modifiers)).copied(),
+            _ => None,
+        }
+    }
+
+    /// The shortcuts bound to the action, primary shortcut first
+    pub fn shortcuts(&self, action: Action) -> &[Shortcut] {
+        self.shortcuts
+            .get(&action)
+            .map(|s| s.as_slice())
+            .unwrap_or_default()
+    }
+}
 
 #[derive(Copy, Clone)]
-#[allow(unused)]
 enum Key {
     A,
     B,
@@ -108,9 +445,71 @@ enum Key {
     Insert,
     Space,
     Tab,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Comma,
+    Period,
+    Slash,
+    Seven,
+    RightBracket,
 }
 
 impl Key {
+    const ALL: [Self; 50] = [
+        Self::A,
+        Self::B,
+        Self::C,
+        Self::D,
+        Self::E,
+        Self::F,
+        Self::G,
+        Self::H,
+        Self::I,
+        Self::J,
+        Self::K,
+        Self::L,
+        Self::M,
+        Self::N,
+        Self::O,
+        Self::P,
+        Self::Q,
+        Self::R,
+        Self::S,
+        Self::T,
+        Self::U,
+        Self::V,
+        Self::W,
+        Self::X,
+        Self::Y,
+        Self::Z,
+        Self::F1,
+        Self::F2,
+        Self::F3,
+        Self::F4,
+        Self::F5,
+        Self::F6,
+        Self::F7,
+        Self::F8,
+        Self::F9,
+        Self::F10,
+        Self::F11,
+        Self::F12,
+        Self::Insert,
+        Self::Space,
+        Self::Tab,
+        Self::Up,
+        Self::Down,
+        Self::PageUp,
+        Self::PageDown,
+        Self::Comma,
+        Self::Period,
+        Self::Slash,
+        Self::Seven,
+        Self::RightBracket,
+    ];
+
     const fn to_char(self) -> KeyCode {
         match self {
             Self::A => KeyCode::Char('a'),
@@ -154,6 +553,17 @@ impl Key {
             Self::Insert => KeyCode::Insert,
             Self::Space => KeyCode::Char(' '),
             Self::Tab => KeyCode::Tab,
+            Self::Up => KeyCode::Up,
+            Self::Down => KeyCode::Down,
+            Self::PageUp => KeyCode::PageUp,
+            Self::PageDown => KeyCode::PageDown,
+            Self::Comma => KeyCode::Char(','),
+            Self::Period => KeyCode::Char('.'),
+            // terminals report Ctrl-/ as Ctrl-/ or Ctrl-7,
+            // and Ctrl-] as Ctrl-5
+            Self::Slash => KeyCode::Char('/'),
+            Self::Seven => KeyCode::Char('7'),
+            Self::RightBracket => KeyCode::Char('5'),
         }
     }
     const fn to_str(self) -> &'static str {
@@ -199,6 +609,60 @@ impl Key {
             Self::Insert => "Ins",
             Self::Space => "Space",
             Self::Tab => "Tab",
+            Self::Up => "Up",
+            Self::Down => "Down",
+            Self::PageUp => "PgUp",
+            Self::PageDown => "PgDn",
+            Self::Comma => ",",
+            Self::Period => ".",
+            Self::Slash => "/",
+            Self::Seven => "7",
+            Self::RightBracket => "]",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_keys_distinct() {
+        let keymap = Keymap::default();
+
+        for action in Action::ALL {
+            assert_eq!(action.name().parse(), Ok(action));
+            for (modifiers, key) in action.default_keys() {
+                assert_eq!(
+                    keymap.actions.get(&(key.to_char(), *modifiers)),
+                    Some(&action),
+                    "{} bound twice",
+                    Shortcut::new(*modifiers, *key).label,
+                );
+            }
         }
     }
+
+    #[test]
+    fn rebinding_unbinds() {
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::Filter, vec!["ctrl-]".parse().unwrap()]);
+
+        assert!(keymap.shortcuts(Action::SelectBuffer).is_empty());
+        assert_eq!(keymap.shortcuts(Action::Filter)[0].label, "Ctrl-]");
+        assert_eq!(
+            keymap.action(&Event::Key(KeyEvent::new(
+                KeyCode::Char('5'),
+                KeyModifiers::CONTROL
+            ))),
+            Some(Action::Filter)
+        );
+        assert_eq!(
+            keymap.action(&Event::Key(KeyEvent::new(
+                KeyCode::Char('x'),
+                KeyModifiers::ALT
+            ))),
+            None
+        );
+    }
 }
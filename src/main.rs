@@ -11,22 +11,34 @@
 #![forbid(unsafe_code)]
 
 mod buffer;
+mod clipboard;
+mod config;
 mod editor;
+mod encoding;
 mod endings;
 mod files;
 mod help;
 mod key;
 mod prompt;
 mod scrollbar;
+mod session;
 mod syntax;
 mod truncate;
 
 use editor::{Editor, LineNumber};
 
+/// How long to sit idle before checking whether
+/// the current file has been changed by something else
+const CHANGE_CHECK: std::time::Duration = std::time::Duration::from_secs(1);
+
 fn main() {
-    use crossterm::event::{Event, MouseEvent, MouseEventKind, poll, read};
+    use crossterm::event::{poll, read};
     use std::time::Duration;
 
+    // report any config or theme file errors before the terminal is taken over
+    std::sync::LazyLock::force(&config::CONFIG);
+    std::sync::LazyLock::force(&syntax::color::THEME);
+
     let mut editor = match open_editor() {
         Ok(editor) => editor,
         Err(err) => {
@@ -45,18 +57,20 @@ fn main() {
             None => {
                 while editor.has_open_buffers() {
                     let area = editor.display(terminal)?;
-                    editor.process_event(
-                        area,
-                        loop {
-                            match read()? {
-                                Event::Mouse(MouseEvent {
-                                    kind: MouseEventKind::Moved | MouseEventKind::Up(_),
-                                    ..
-                                }) => { /* ignore mouse movement events */ }
-                                event => break event,
+                    loop {
+                        match poll(CHANGE_CHECK)? {
+                            true => match read()? {
+                                event if ignored(&event) => { /* ignore mouse movement events */ }
+                                event => break editor.process_event(area, event),
+                            },
+                            false => {
+                                if editor.check_changed() {
+                                    break;
+                                }
                             }
-                        },
-                    )
+                        }
+                    }
+                    process_pending(&mut editor, area)?;
                 }
             }
             Some(max_wait) => {
@@ -65,19 +79,17 @@ fn main() {
                     loop {
                         match poll(max_wait)? {
                             true => match read()? {
-                                Event::Mouse(MouseEvent {
-                                    kind: MouseEventKind::Moved | MouseEventKind::Up(_),
-                                    ..
-                                }) => { /* ignore mouse movement events */ }
+                                event if ignored(&event) => { /* ignore mouse movement events */ }
                                 event => break editor.process_event(area, event),
                             },
                             false => {
-                                if editor.auto_save() {
+                                if editor.check_changed() | editor.auto_save() {
                                     break;
                                 }
                             }
                         }
                     }
+                    process_pending(&mut editor, area)?;
                 }
             }
         }
@@ -86,6 +98,46 @@ fn main() {
     }) {
         eprintln!("{err}");
     }
+
+    if let Err(err) = editor.save_session() {
+        eprintln!("* {err}");
+    }
+}
+
+/// Whether the event is mouse movement or release not worth processing,
+/// since drag selections arrive as drag events of their own
+fn ignored(event: &crossterm::event::Event) -> bool {
+    use crossterm::event::{Event, MouseEvent, MouseEventKind};
+
+    matches!(
+        event,
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Moved | MouseEventKind::Up(_),
+            ..
+        })
+    )
+}
+
+/// Processes any events already waiting without redrawing,
+/// so a burst of input (like key repeat) is drawn just once
+///
+/// A resize ends the burst, since later events need the new area.
+fn process_pending(editor: &mut Editor, area: ratatui::layout::Rect) -> std::io::Result<()> {
+    use crossterm::event::{Event, poll, read};
+    use std::time::Duration;
+
+    while editor.has_open_buffers() && poll(Duration::ZERO)? {
+        match read()? {
+            event if ignored(&event) => { /* ignore mouse movement events */ }
+            event @ Event::Resize(..) => {
+                editor.process_event(area, event);
+                break;
+            }
+            event => editor.process_event(area, event),
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(not(feature = "ssh"))]
@@ -101,16 +153,34 @@ fn open_editor() -> Result<Editor, Box<dyn std::error::Error>> {
         line: Option<LineNumber>,
         #[clap(long = "test", help = "display terminal test")]
         test: bool,
+        #[clap(
+            long = "session",
+            help = "restore last session",
+            conflicts_with = "files"
+        )]
+        session: bool,
         files: Vec<PathBuf>,
     }
 
-    let Opt { line, files, test } = Opt::parse();
-    let editor = Editor::new(
-        files
-            .into_iter()
-            .map(buffer::Source::from)
-            .chain(test.then_some(buffer::Source::Test)),
-    )?;
+    let Opt {
+        line,
+        files,
+        test,
+        session,
+    } = Opt::parse();
+    let files = file_lines(files);
+    let stdin = piped_stdin();
+    let editor = match saved_session(session, files.is_empty() && stdin.is_none() && !test)? {
+        Some(session) => Editor::from_session(session)?,
+        None => Editor::new(
+            files
+                .iter()
+                .map(|(file, _)| buffer::Source::from(file.clone()))
+                .chain(stdin)
+                .chain(test.then_some(buffer::Source::Test)),
+        )?
+        .at_lines(files),
+    };
     Ok(match line {
         None => editor,
         Some(line) => editor.at_line(line),
@@ -132,6 +202,12 @@ fn open_editor() -> Result<Editor, Box<dyn std::error::Error>> {
         line: Option<LineNumber>,
         #[clap(long = "test", help = "display terminal test")]
         test: bool,
+        #[clap(
+            long = "session",
+            help = "restore last session",
+            conflicts_with = "files"
+        )]
+        session: bool,
         files: Vec<PathBuf>,
         #[clap(
             short = 's',
@@ -167,16 +243,25 @@ fn open_editor() -> Result<Editor, Box<dyn std::error::Error>> {
             files,
             test,
             line,
+            session,
             host: None,
             username: None,
             ..
         } => {
-            let editor = Editor::new(
-                files
-                    .into_iter()
-                    .map(buffer::Source::from)
-                    .chain(test.then_some(buffer::Source::Test)),
-            )?;
+            let files = file_lines(files);
+            let stdin = piped_stdin();
+            let editor = match saved_session(session, files.is_empty() && stdin.is_none() && !test)?
+            {
+                Some(session) => Editor::from_session(session)?,
+                None => Editor::new(
+                    files
+                        .iter()
+                        .map(|(file, _)| buffer::Source::from(file.clone()))
+                        .chain(stdin)
+                        .chain(test.then_some(buffer::Source::Test)),
+                )?
+                .at_lines(files),
+            };
             Ok(match line {
                 None => editor,
                 Some(line) => editor.at_line(line),
@@ -192,11 +277,14 @@ fn open_editor() -> Result<Editor, Box<dyn std::error::Error>> {
             private_key,
             public_key,
             port,
+            ..
         } => {
+            let files = file_lines(files);
             let editor = Editor::new_remote(
                 files
-                    .into_iter()
-                    .map(buffer::Source::from)
+                    .iter()
+                    .map(|(file, _)| buffer::Source::from(file.clone()))
+                    .chain(piped_stdin())
                     .chain(test.then_some(buffer::Source::Test)),
                 match private_key {
                     Some(private_key) => {
@@ -231,7 +319,8 @@ fn open_editor() -> Result<Editor, Box<dyn std::error::Error>> {
                 },
                 username,
                 host,
-            )?;
+            )?
+            .at_lines(files);
 
             Ok(match line {
                 None => editor,
@@ -256,6 +345,80 @@ fn open_editor() -> Result<Editor, Box<dyn std::error::Error>> {
     }
 }
 
+/// The saved session, if requested or if nothing else is being opened
+///
+/// A requested session must load, but an unrequested one
+/// which doesn't is reported and otherwise ignored.
+fn saved_session(
+    requested: bool,
+    nothing_opened: bool,
+) -> Result<Option<session::Session>, Box<dyn std::error::Error>> {
+    let Some(path) = session::session_path() else {
+        return Ok(None);
+    };
+
+    if requested {
+        session::Session::load(&path)
+            .map(Some)
+            .map_err(|err| format!("{}: {err}", path.display()).into())
+    } else if nothing_opened && path.exists() {
+        Ok(session::Session::load(&path)
+            .inspect_err(|err| eprintln!("* {}: {err}", path.display()))
+            .ok())
+    } else {
+        Ok(None)
+    }
+}
+
+/// Standard input as a source, if something has been piped to it
+fn piped_stdin() -> Option<buffer::Source> {
+    use std::io::IsTerminal;
+
+    (!std::io::stdin().is_terminal()).then_some(buffer::Source::Stdin)
+}
+
+/// Splits starting line numbers from files given as
+/// "file:line", "file:line:column" or "+line file"
+///
+/// Paths which already exist are always taken as-is.
+fn file_lines(files: Vec<std::path::PathBuf>) -> Vec<(std::path::PathBuf, Option<LineNumber>)> {
+    use std::path::PathBuf;
+
+    fn split_line(file: &str) -> Option<(&str, LineNumber)> {
+        // try "file:line:column" before "file:line"
+        let colons = file.rmatch_indices(':').take(2).collect::<Vec<_>>();
+        colons.into_iter().rev().find_map(|(idx, _)| {
+            let (path, line) = (&file[..idx], &file[idx + 1..]);
+            match line.parse() {
+                Ok(line) if !path.is_empty() => Some((path, line)),
+                _ => None,
+            }
+        })
+    }
+
+    let mut pending = None;
+    let mut split = vec![];
+
+    for file in files {
+        if file.exists() {
+            split.push((file, pending.take()));
+        } else if let Some(line) = file
+            .to_str()
+            .and_then(|f| f.strip_prefix('+'))
+            .and_then(|l| l.parse().ok())
+        {
+            pending = Some(line);
+        } else if let Some((path, line)) = file.to_str().and_then(split_line) {
+            split.push((PathBuf::from(path), Some(line)));
+            pending = None;
+        } else {
+            split.push((file, pending.take()));
+        }
+    }
+
+    split
+}
+
 /// Sets up terminal, executes editor, and automatically cleans up afterward
 fn execute_terminal<T>(
     f: impl FnOnce(&mut ratatui::DefaultTerminal) -> std::io::Result<T>,
@@ -285,3 +448,37 @@ fn execute_terminal<T>(
     ratatui::restore();
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn splits_file_lines() {
+        let line = |line: &str| line.parse::<LineNumber>().ok();
+        let existing = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let files = [
+            "missing.rs:12",
+            "missing.rs:4:7",
+            "+3",
+            "other.rs",
+            "+5",
+            existing,
+            "dir:name.rs",
+            "plain.rs",
+        ];
+
+        assert_eq!(
+            file_lines(files.into_iter().map(PathBuf::from).collect()),
+            vec![
+                (PathBuf::from("missing.rs"), line("12")),
+                (PathBuf::from("missing.rs"), line("4:7")),
+                (PathBuf::from("other.rs"), line("3")),
+                (PathBuf::from(existing), line("5")),
+                (PathBuf::from("dir:name.rs"), None),
+                (PathBuf::from("plain.rs"), None),
+            ]
+        );
+    }
+}
@@ -89,13 +89,14 @@ impl TextField {
         self.chars.iter().copied()
     }
 
-    pub fn cursor_column(&self) -> usize {
+    /// Cursor's display column, with tabs the given width
+    pub fn cursor_column(&self, tab_width: usize) -> usize {
         use unicode_width::UnicodeWidthChar;
 
         self.chars()
             .take(self.cursor)
             .map(|c| match c {
-                '\t' => *crate::buffer::SPACES_PER_TAB,
+                '\t' => tab_width,
                 c => c.width().unwrap_or(0),
             })
             .sum()
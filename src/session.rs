@@ -0,0 +1,247 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::config::{InvalidRon, fields, items, unquote};
+use std::path::{Path, PathBuf};
+
+/// Either $XDG_STATE_HOME or ~/.local/state, followed by vle/session.ron
+pub fn session_path() -> Option<PathBuf> {
+    std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })
+        .map(|state| state.join("vle").join("session.ron"))
+}
+
+/// Files open in each pane, their cursor positions
+/// and how the panes are laid out
+pub enum Session {
+    Single {
+        files: Vec<(PathBuf, usize)>, // files and their cursor positions
+        current: usize,               // index of file being edited
+    },
+    Horizontal {
+        top: Box<Session>,
+        top_fill: u16,
+        bottom: Box<Session>,
+        bottom_fill: u16,
+        bottom_focused: bool,
+    },
+    Vertical {
+        left: Box<Session>,
+        left_fill: u16,
+        right: Box<Session>,
+        right_fill: u16,
+        right_focused: bool,
+    },
+}
+
+impl Session {
+    /// Reads session from RON file
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Writes session to RON file, creating its directory if necessary
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_ron(0) + "\n")
+    }
+
+    fn to_ron(&self, indent: usize) -> String {
+        fn quote(path: &Path) -> String {
+            let mut quoted = String::from('"');
+            for c in path.to_string_lossy().chars() {
+                if matches!(c, '"' | '\\') {
+                    quoted.push('\\');
+                }
+                quoted.push(c);
+            }
+            quoted.push('"');
+            quoted
+        }
+
+        let pad = "    ".repeat(indent + 1);
+        let end = "    ".repeat(indent);
+
+        match self {
+            Self::Single { files, current } => format!(
+                "Single(\n{pad}files: [\n{}{pad}],\n{pad}current: {current},\n{end})",
+                files
+                    .iter()
+                    .map(|(path, cursor)| format!("{pad}    ({}, {cursor}),\n", quote(path)))
+                    .collect::<String>(),
+            ),
+            Self::Horizontal {
+                top,
+                top_fill,
+                bottom,
+                bottom_fill,
+                bottom_focused,
+            } => format!(
+                "Horizontal(\n{pad}top: {},\n{pad}top_fill: {top_fill},\n\
+                 {pad}bottom: {},\n{pad}bottom_fill: {bottom_fill},\n\
+                 {pad}focused: {},\n{end})",
+                top.to_ron(indent + 1),
+                bottom.to_ron(indent + 1),
+                if *bottom_focused { "Bottom" } else { "Top" },
+            ),
+            Self::Vertical {
+                left,
+                left_fill,
+                right,
+                right_fill,
+                right_focused,
+            } => format!(
+                "Vertical(\n{pad}left: {},\n{pad}left_fill: {left_fill},\n\
+                 {pad}right: {},\n{pad}right_fill: {right_fill},\n\
+                 {pad}focused: {},\n{end})",
+                left.to_ron(indent + 1),
+                right.to_ron(indent + 1),
+                if *right_focused { "Right" } else { "Left" },
+            ),
+        }
+    }
+
+    fn parse(ron: &str) -> Result<Self, InvalidRon> {
+        fn field<'r>(fields: &[(&str, &'r str)], name: &str) -> Result<&'r str, InvalidRon> {
+            fields
+                .iter()
+                .find_map(|(n, value)| (*n == name).then_some(*value))
+                .ok_or_else(|| InvalidRon(format!("missing field {name}")))
+        }
+
+        fn number<T: std::str::FromStr>(
+            fields: &[(&str, &str)],
+            name: &str,
+        ) -> Result<T, InvalidRon> {
+            field(fields, name)?
+                .parse()
+                .map_err(|_| InvalidRon(format!("invalid value for {name}")))
+        }
+
+        fn file(ron: &str) -> Result<(PathBuf, usize), InvalidRon> {
+            match items(ron, '(', ')')?.as_slice() {
+                [path, cursor] => Ok((
+                    unquote(path)
+                        .map(PathBuf::from)
+                        .ok_or_else(|| InvalidRon(format!("expected quoted path, got {path}")))?,
+                    cursor
+                        .parse()
+                        .map_err(|_| InvalidRon(format!("invalid cursor {cursor}")))?,
+                )),
+                _ => Err(InvalidRon(format!("expected (path, cursor), got {ron}"))),
+            }
+        }
+
+        let ron = ron.trim();
+        let fields = fields(ron)?;
+
+        match ron.split_once('(').map(|(variant, _)| variant.trim()) {
+            Some("Single") => Ok(Self::Single {
+                files: items(field(&fields, "files")?, '[', ']')?
+                    .into_iter()
+                    .map(file)
+                    .collect::<Result<_, _>>()?,
+                current: number(&fields, "current")?,
+            }),
+            Some("Horizontal") => Ok(Self::Horizontal {
+                top: Box::new(Self::parse(field(&fields, "top")?)?),
+                top_fill: number(&fields, "top_fill")?,
+                bottom: Box::new(Self::parse(field(&fields, "bottom")?)?),
+                bottom_fill: number(&fields, "bottom_fill")?,
+                bottom_focused: field(&fields, "focused")? == "Bottom",
+            }),
+            Some("Vertical") => Ok(Self::Vertical {
+                left: Box::new(Self::parse(field(&fields, "left")?)?),
+                left_fill: number(&fields, "left_fill")?,
+                right: Box::new(Self::parse(field(&fields, "right")?)?),
+                right_fill: number(&fields, "right_fill")?,
+                right_focused: field(&fields, "focused")? == "Right",
+            }),
+            variant => Err(InvalidRon(format!(
+                "unknown layout {}",
+                variant.unwrap_or_default()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The files in each pane, in order, along with the layout's shape
+    fn describe(session: &Session) -> String {
+        match session {
+            Session::Single { files, current } => format!("{files:?} @ {current}"),
+            Session::Horizontal {
+                top,
+                top_fill,
+                bottom,
+                bottom_fill,
+                bottom_focused,
+            } => format!(
+                "H({}: {top_fill}, {}: {bottom_fill}, {bottom_focused})",
+                describe(top),
+                describe(bottom)
+            ),
+            Session::Vertical {
+                left,
+                left_fill,
+                right,
+                right_fill,
+                right_focused,
+            } => format!(
+                "V({}: {left_fill}, {}: {right_fill}, {right_focused})",
+                describe(left),
+                describe(right)
+            ),
+        }
+    }
+
+    #[test]
+    fn round_trips() {
+        let single = |names: &[&str], current| Session::Single {
+            files: names
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (PathBuf::from(name), i * 10))
+                .collect(),
+            current,
+        };
+        let session = Session::Vertical {
+            left: Box::new(single(&["/plain.rs", "/with \"quotes\".txt"], 1)),
+            left_fill: 2,
+            right: Box::new(Session::Horizontal {
+                top: Box::new(single(&["/a, b (c) [d].md", "/back\\slash"], 0)),
+                top_fill: 1,
+                bottom: Box::new(single(&["/new\nline", "/tab\tand // slashes: here"], 1)),
+                bottom_fill: 3,
+                bottom_focused: true,
+            }),
+            right_fill: 1,
+            right_focused: false,
+        };
+
+        let ron = session.to_ron(0);
+        let parsed = Session::parse(&ron).unwrap_or_else(|err| panic!("{err:?} in {ron}"));
+        assert_eq!(describe(&parsed), describe(&session));
+    }
+
+    #[test]
+    fn bad_sessions() {
+        assert!(Session::parse("Single(files: [(\"a\", 1)])").is_err());
+        assert!(Session::parse("Single(files: [(a, 1)], current: 0)").is_err());
+        assert!(Session::parse("Single(files: [(\"a\", x)], current: 0)").is_err());
+        assert!(Session::parse("Diagonal(files: [], current: 0)").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::syntax::{Highlight, HighlightState, color};
+use logos::Logos;
+
+// GAS and NASM differ in plenty of ways, so this aims
+// for a common subset of both which covers typical x86 and ARM code
+
+#[derive(Logos, Debug)]
+#[logos(skip r"[ \t\n]+")]
+enum AsmToken {
+    #[regex(r"[A-Za-z_.$@?][A-Za-z0-9_.$@?]*:")]
+    #[regex(r"[0-9]+:")]
+    Label,
+    #[regex(r"\.[A-Za-z_][A-Za-z0-9_]*")]
+    #[regex(r"%[A-Za-z_]+")]
+    #[regex(r"#[A-Za-z]+")]
+    #[regex(
+        "(?i:section|segment|global|extern|bits|default|org|align|times|equ|incbin|d[bwdqt]|res[bwdqt])",
+        priority = 10
+    )]
+    Directive,
+    #[regex(
+        r"%?(?i:[re]?[abcd]x|[abcd][lh]|[re]?(si|di|sp|bp|ip)|(si|di|bp)l|spl|r([0-9]|1[0-5])[dwb]?|[cdefgs]s|[xyz]mm([12]?[0-9]|3[01])|k[0-7]|[cd]r[0-9]|[xw]([12]?[0-9]|30)|lr|pc|xzr|wzr)",
+        priority = 20
+    )]
+    Register,
+    #[regex(r"[A-Za-z_@?][A-Za-z0-9_.$@?]*")]
+    Identifier,
+    #[regex(r"[$#]?[-+]?[0-9][0-9_]*")]
+    #[regex(r"[$#]?[-+]?0[xX][0-9A-Fa-f_]+")]
+    #[regex(r"[$#]?[-+]?0[bB][01_]+")]
+    #[regex(r"[0-9][0-9A-Fa-f_]*[hH]")]
+    Number,
+    #[regex(r#""([^"\\]|\\.)*""#)]
+    #[regex(r"'([^'\\]|\\.)*'")]
+    #[regex(r"`([^`\\]|\\.)*`")]
+    String,
+    #[regex(";.*", allow_greedy = true)]
+    #[regex("//.*", allow_greedy = true)]
+    #[regex("#([^A-Za-z0-9$+-].*)?", allow_greedy = true)]
+    Comment,
+}
+
+/// Where a token falls within its line
+#[derive(Copy, Clone)]
+enum Position {
+    Start,      // nothing lexed yet
+    AfterLabel, // only a label lexed so far
+    Operands,   // past the mnemonic or directive
+}
+
+/// Assembly highlighting, whose dialects differ mostly
+/// in how they start a comment
+#[derive(Debug)]
+pub struct Assembly {
+    name: &'static str,
+    comment: &'static str,
+}
+
+impl Assembly {
+    /// The GNU assembler's syntax, usually in .s files
+    pub const GAS: Self = Self {
+        name: "GAS Assembly",
+        comment: "#",
+    };
+
+    /// The Netwide assembler's syntax, usually in .asm files
+    pub const NASM: Self = Self {
+        name: "NASM Assembly",
+        comment: ";",
+    };
+}
+
+impl std::fmt::Display for Assembly {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.name.fmt(f)
+    }
+}
+
+impl crate::syntax::Highlighter for Assembly {
+    fn highlight<'s>(
+        &self,
+        s: &'s str,
+        _state: &'s mut HighlightState,
+    ) -> Box<dyn Iterator<Item = (Highlight, std::ops::Range<usize>)> + 's> {
+        let mut position = Position::Start;
+
+        Box::new(AsmToken::lexer(s).spanned().filter_map(move |(t, r)| {
+            let t = t.ok()?;
+            let highlight = match (t, position) {
+                // a label only counts as one if it begins the line
+                // (which also leaves segment overrides like es: alone)
+                (AsmToken::Label, Position::Start) => {
+                    position = Position::AfterLabel;
+                    return Some((*color::CONSTANT, r));
+                }
+                // and the first word after it is the instruction,
+                // even one spelled like an x86 register (such as ARM's bl)
+                (
+                    AsmToken::Identifier | AsmToken::Register,
+                    Position::Start | Position::AfterLabel,
+                ) => Some(*color::FLOW),
+                (AsmToken::Label | AsmToken::Identifier, _) => None,
+                (AsmToken::Directive, _) => Some(*color::KEYWORD),
+                (AsmToken::Register, _) => Some(*color::TYPE),
+                (AsmToken::Number, _) => Some(*color::NUMBER),
+                (AsmToken::String, _) => Some(*color::STRING),
+                (AsmToken::Comment, _) => Some(*color::COMMENT),
+            };
+            position = Position::Operands;
+            highlight.map(|c| (c, r))
+        }))
+    }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Some(self.comment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::highlighted;
+
+    #[test]
+    fn mnemonics_are_not_registers() {
+        let spans = highlighted(&Assembly::GAS, "loop: bl func\n\tbx lr\n\tmov bl, al\n");
+        assert!(spans.contains(&("bl".into(), color::FLOW.color)));
+        assert!(spans.contains(&("bx".into(), color::FLOW.color)));
+        assert!(spans.contains(&("lr".into(), color::TYPE.color)));
+        // but the same word is a register as an operand
+        assert!(spans.contains(&("bl".into(), color::TYPE.color)));
+        assert!(spans.contains(&("al".into(), color::TYPE.color)));
+    }
+
+    #[test]
+    fn dialects_comment_differently() {
+        use crate::syntax::Highlighter;
+
+        assert_eq!(Assembly::GAS.line_comment(), Some("#"));
+        assert_eq!(Assembly::NASM.line_comment(), Some(";"));
+        assert_eq!(Assembly::NASM.to_string(), "NASM Assembly");
+    }
+}
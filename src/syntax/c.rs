@@ -112,15 +112,15 @@ impl TryFrom<CToken> for Highlight {
 
     fn try_from(t: CToken) -> Result<Highlight, ()> {
         match t {
-            CToken::Constant => Ok(color::CONSTANT),
-            CToken::Integer | CToken::Boolean => Ok(color::NUMBER),
-            CToken::Keyword => Ok(color::KEYWORD),
-            CToken::Flowcontrol1 | CToken::Flowcontrol2 => Ok(color::FLOW),
-            CToken::Comment => Ok(color::COMMENT),
-            CToken::String => Ok(color::STRING),
+            CToken::Constant => Ok(*color::CONSTANT),
+            CToken::Integer | CToken::Boolean => Ok(*color::NUMBER),
+            CToken::Keyword => Ok(*color::KEYWORD),
+            CToken::Flowcontrol1 | CToken::Flowcontrol2 => Ok(*color::FLOW),
+            CToken::Comment => Ok(*color::COMMENT),
+            CToken::String => Ok(*color::STRING),
             CToken::Variable => Err(()),
             CToken::Preprocessor => Ok(Color::LightCyan.into()),
-            CToken::StartComment | CToken::EndComment => Ok(color::COMMENT),
+            CToken::StartComment | CToken::EndComment => Ok(*color::COMMENT),
         }
     }
 }
@@ -135,11 +135,12 @@ impl std::fmt::Display for C {
 }
 
 highlighter!(
+    "//";
     C,
     CToken,
     StartComment,
     EndComment,
     "/*",
     "*/",
-    color::COMMENT
+    *color::COMMENT
 );
@@ -0,0 +1,105 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::highlighter;
+use crate::syntax::{Highlight, color};
+use logos::Logos;
+use ratatui::style::Color;
+
+#[derive(Logos, Debug)]
+#[logos(skip r"[ \t\n]+")]
+enum CMakeToken {
+    #[regex("[[:alpha:]_][[:alnum:]_]*")]
+    Identifier,
+    #[token("add_compile_definitions", ignore(case))]
+    #[token("add_compile_options", ignore(case))]
+    #[token("add_custom_command", ignore(case))]
+    #[token("add_custom_target", ignore(case))]
+    #[token("add_definitions", ignore(case))]
+    #[token("add_dependencies", ignore(case))]
+    #[token("add_executable", ignore(case))]
+    #[token("add_library", ignore(case))]
+    #[token("add_subdirectory", ignore(case))]
+    #[token("add_test", ignore(case))]
+    #[token("cmake_minimum_required", ignore(case))]
+    #[token("configure_file", ignore(case))]
+    #[token("enable_testing", ignore(case))]
+    #[token("find_library", ignore(case))]
+    #[token("find_package", ignore(case))]
+    #[token("find_path", ignore(case))]
+    #[token("find_program", ignore(case))]
+    #[token("include", ignore(case))]
+    #[token("include_directories", ignore(case))]
+    #[token("install", ignore(case))]
+    #[token("link_directories", ignore(case))]
+    #[token("list", ignore(case))]
+    #[token("message", ignore(case))]
+    #[token("option", ignore(case))]
+    #[token("project", ignore(case))]
+    #[token("set", ignore(case))]
+    #[token("set_property", ignore(case))]
+    #[token("set_target_properties", ignore(case))]
+    #[token("string", ignore(case))]
+    #[token("target_compile_definitions", ignore(case))]
+    #[token("target_compile_features", ignore(case))]
+    #[token("target_compile_options", ignore(case))]
+    #[token("target_include_directories", ignore(case))]
+    #[token("target_link_libraries", ignore(case))]
+    #[token("target_sources", ignore(case))]
+    #[token("unset", ignore(case))]
+    Command,
+    #[token("break", ignore(case))]
+    #[token("continue", ignore(case))]
+    #[token("else", ignore(case))]
+    #[token("elseif", ignore(case))]
+    #[token("endforeach", ignore(case))]
+    #[token("endfunction", ignore(case))]
+    #[token("endif", ignore(case))]
+    #[token("endmacro", ignore(case))]
+    #[token("endwhile", ignore(case))]
+    #[token("foreach", ignore(case))]
+    #[token("function", ignore(case))]
+    #[token("if", ignore(case))]
+    #[token("macro", ignore(case))]
+    #[token("return", ignore(case))]
+    #[token("while", ignore(case))]
+    Flow,
+    #[regex(r"\$\{[^}]*\}")]
+    #[regex(r"\$ENV\{[^}]*\}")]
+    Variable,
+    #[regex(r#"\"([^\\\"]|\\.)*\""#)]
+    String,
+    #[regex("#.*", allow_greedy = true)]
+    Comment,
+}
+
+impl TryFrom<CMakeToken> for Highlight {
+    type Error = ();
+
+    fn try_from(t: CMakeToken) -> Result<Highlight, ()> {
+        match t {
+            CMakeToken::Command => Ok(*color::KEYWORD),
+            CMakeToken::Flow => Ok(*color::FLOW),
+            CMakeToken::Variable => Ok(Color::Cyan.into()),
+            CMakeToken::String => Ok(*color::STRING),
+            CMakeToken::Comment => Ok(*color::COMMENT),
+            CMakeToken::Identifier => Err(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CMake;
+
+impl std::fmt::Display for CMake {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        "CMake".fmt(f)
+    }
+}
+
+highlighter!("#"; CMake, CMakeToken);
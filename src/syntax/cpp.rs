@@ -166,17 +166,17 @@ impl TryFrom<CppToken> for Highlight {
 
     fn try_from(t: CppToken) -> Result<Highlight, ()> {
         match t {
-            CppToken::Constant | CppToken::Operator => Ok(color::CONSTANT),
-            CppToken::Integer | CppToken::Literal => Ok(color::NUMBER),
-            CppToken::Keyword => Ok(color::KEYWORD),
-            CppToken::Flowcontrol1 => Ok(color::FLOW),
-            CppToken::Flowcontrol2 => Ok(color::FLOW),
-            CppToken::Flowcontrol3 => Ok(color::FLOW),
-            CppToken::Comment => Ok(color::COMMENT),
-            CppToken::String => Ok(color::STRING),
+            CppToken::Constant | CppToken::Operator => Ok(*color::CONSTANT),
+            CppToken::Integer | CppToken::Literal => Ok(*color::NUMBER),
+            CppToken::Keyword => Ok(*color::KEYWORD),
+            CppToken::Flowcontrol1 => Ok(*color::FLOW),
+            CppToken::Flowcontrol2 => Ok(*color::FLOW),
+            CppToken::Flowcontrol3 => Ok(*color::FLOW),
+            CppToken::Comment => Ok(*color::COMMENT),
+            CppToken::String => Ok(*color::STRING),
             CppToken::Variable => Err(()),
             CppToken::Preprocessor => Ok(Color::LightCyan.into()),
-            CppToken::StartComment | CppToken::EndComment => Ok(color::COMMENT),
+            CppToken::StartComment | CppToken::EndComment => Ok(*color::COMMENT),
         }
     }
 }
@@ -191,11 +191,12 @@ impl std::fmt::Display for Cpp {
 }
 
 highlighter!(
+    "//";
     Cpp,
     CppToken,
     StartComment,
     EndComment,
     "/*",
     "*/",
-    color::COMMENT
+    *color::COMMENT
 );
@@ -44,7 +44,7 @@ impl TryFrom<CssToken> for Highlight {
     fn try_from(t: CssToken) -> Result<Highlight, ()> {
         match t {
             CssToken::Property => Ok(Color::Yellow.into()),
-            CssToken::StartComment | CssToken::EndComment => Ok(color::COMMENT),
+            CssToken::StartComment | CssToken::EndComment => Ok(*color::COMMENT),
             CssToken::Syntax => Ok(Color::Green.into()),
             CssToken::Class => Ok(Color::Red.into()),
             CssToken::Id => Ok(Color::Magenta.into()),
@@ -69,5 +69,5 @@ highlighter!(
     EndComment,
     "/*",
     "*/",
-    color::COMMENT
+    *color::COMMENT
 );
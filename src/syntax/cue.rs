@@ -38,10 +38,10 @@ impl TryFrom<CuesheetToken> for Highlight {
 
     fn try_from(t: CuesheetToken) -> Result<Highlight, ()> {
         match t {
-            CuesheetToken::Name => Ok(color::KEYWORD),
+            CuesheetToken::Name => Ok(*color::KEYWORD),
             CuesheetToken::Type => Ok(Color::Magenta.into()),
-            CuesheetToken::String => Ok(color::STRING),
-            CuesheetToken::Comment => Ok(color::COMMENT),
+            CuesheetToken::String => Ok(*color::STRING),
+            CuesheetToken::Comment => Ok(*color::COMMENT),
         }
     }
 }
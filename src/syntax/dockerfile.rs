@@ -0,0 +1,117 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::syntax::{Highlight, HighlightState, color};
+use logos::Logos;
+use ratatui::style::Color;
+
+#[derive(Logos, Debug)]
+#[logos(skip r"[ \t\n]+")]
+enum DockerfileToken {
+    #[regex("[[:alpha:]_][[:alnum:]_-]*")]
+    Identifier,
+    #[token("ADD")]
+    #[token("ARG")]
+    #[token("CMD")]
+    #[token("COPY")]
+    #[token("ENTRYPOINT")]
+    #[token("ENV")]
+    #[token("EXPOSE")]
+    #[token("HEALTHCHECK")]
+    #[token("LABEL")]
+    #[token("MAINTAINER")]
+    #[token("RUN")]
+    #[token("SHELL")]
+    #[token("STOPSIGNAL")]
+    #[token("USER")]
+    #[token("VOLUME")]
+    #[token("WORKDIR")]
+    Instruction,
+    #[token("FROM")]
+    From,
+    #[token("AS")]
+    As,
+    #[token("ONBUILD")]
+    Onbuild,
+    #[regex(r"\$\{[[:alpha:]_][[:alnum:]_]*(:[-+][^}]*)?\}")]
+    #[regex(r"\$[[:alpha:]_][[:alnum:]_]*")]
+    Variable,
+    #[regex("(-[[:alpha:]]|--[[:alpha:]-]+)")]
+    Option,
+    #[regex(r#"\"([^\\\"]|\\.)*\""#)]
+    String,
+    #[regex("#.*", allow_greedy = true)]
+    Comment,
+}
+
+/// Where a token falls within its line
+#[derive(Copy, Clone)]
+enum Position {
+    Start,     // nothing lexed yet, or only ONBUILD
+    From,      // within a FROM instruction
+    Arguments, // past some other instruction
+}
+
+#[derive(Debug)]
+pub struct Dockerfile;
+
+impl std::fmt::Display for Dockerfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        "Dockerfile".fmt(f)
+    }
+}
+
+impl crate::syntax::Highlighter for Dockerfile {
+    fn highlight<'s>(
+        &self,
+        s: &'s str,
+        _state: &'s mut HighlightState,
+    ) -> Box<dyn Iterator<Item = (Highlight, std::ops::Range<usize>)> + 's> {
+        let mut position = Position::Start;
+
+        Box::new(
+            DockerfileToken::lexer(s)
+                .spanned()
+                .filter_map(move |(t, r)| {
+                    let highlight = match (t.ok()?, position) {
+                        // instructions only count as such when they begin the line
+                        // (or follow ONBUILD), and AS only within FROM
+                        (DockerfileToken::Onbuild, Position::Start) => {
+                            return Some((*color::KEYWORD, r));
+                        }
+                        (DockerfileToken::From, Position::Start) => {
+                            position = Position::From;
+                            return Some((*color::KEYWORD, r));
+                        }
+                        (DockerfileToken::As, Position::From) => Some(*color::KEYWORD),
+                        (DockerfileToken::Instruction, Position::Start) => Some(*color::KEYWORD),
+                        (
+                            DockerfileToken::Identifier
+                            | DockerfileToken::Instruction
+                            | DockerfileToken::From
+                            | DockerfileToken::As
+                            | DockerfileToken::Onbuild,
+                            _,
+                        ) => None,
+                        (DockerfileToken::Variable, _) => Some(Color::Cyan.into()),
+                        (DockerfileToken::Option, _) => Some(Color::LightMagenta.into()),
+                        (DockerfileToken::String, _) => Some(*color::STRING),
+                        (DockerfileToken::Comment, _) => Some(*color::COMMENT),
+                    };
+                    if !matches!(position, Position::From) {
+                        position = Position::Arguments;
+                    }
+                    highlight.map(|c| (c, r))
+                }),
+        )
+    }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Some("#")
+    }
+}
@@ -64,9 +64,9 @@ impl TryFrom<FishToken> for Highlight {
 
     fn try_from(t: FishToken) -> Result<Highlight, ()> {
         match t {
-            FishToken::Comment => Ok(color::COMMENT),
-            FishToken::Keyword => Ok(color::KEYWORD),
-            FishToken::Loop => Ok(color::FLOW),
+            FishToken::Comment => Ok(*color::COMMENT),
+            FishToken::Keyword => Ok(*color::KEYWORD),
+            FishToken::Loop => Ok(*color::FLOW),
             FishToken::Variable => Ok(Color::Cyan.into()),
             FishToken::Misc => Err(()),
         }
@@ -82,4 +82,4 @@ impl std::fmt::Display for Fish {
     }
 }
 
-highlighter!(Fish, FishToken);
+highlighter!("#"; Fish, FishToken);
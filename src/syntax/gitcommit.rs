@@ -0,0 +1,126 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::syntax::{Highlight, HighlightState, Modifier, MultiCommentType, color};
+use logos::Logos;
+use ratatui::style::Color;
+
+/// Recommended maximum width of the summary line
+const SUMMARY_WIDTH: usize = 50;
+
+/// Recommended maximum width of body lines
+const BODY_WIDTH: usize = 72;
+
+/// Tags the state of lines past the summary
+const BODY: &str = "body";
+
+#[derive(Logos, Debug)]
+enum GitCommitToken {
+    #[regex(
+        "#[[:blank:]]*(Changes to be committed|Changes not staged for commit|Untracked files|Unmerged paths|Conflicts|On branch|Your branch).*",
+        allow_greedy = true,
+        priority = 10
+    )]
+    Heading,
+    #[regex("Conflicts:.*", allow_greedy = true)]
+    Conflicts,
+    #[regex("#.*", allow_greedy = true)]
+    Comment,
+}
+
+impl TryFrom<GitCommitToken> for Highlight {
+    type Error = ();
+
+    fn try_from(t: GitCommitToken) -> Result<Highlight, ()> {
+        match t {
+            GitCommitToken::Heading => Ok(Highlight {
+                color: color::COMMENT.color,
+                modifier: Modifier::Bold,
+            }),
+            GitCommitToken::Conflicts => Ok(Color::Yellow.into()),
+            GitCommitToken::Comment => Ok(*color::COMMENT),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GitCommit;
+
+impl std::fmt::Display for GitCommit {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        "Git Commit".fmt(f)
+    }
+}
+
+impl crate::syntax::Highlighter for GitCommit {
+    fn highlight<'s>(
+        &self,
+        s: &'s str,
+        state: &'s mut HighlightState,
+    ) -> Box<dyn Iterator<Item = (Highlight, std::ops::Range<usize>)> + 's> {
+        // only the first line is the summary,
+        // so every line after it continues the body
+        let width = match state {
+            HighlightState::Continuing(BODY) => BODY_WIDTH,
+            _ => SUMMARY_WIDTH,
+        };
+        *state = HighlightState::Continuing(BODY);
+
+        // git only strips comments which begin a line
+        match GitCommitToken::lexer(s).spanned().next() {
+            Some((Ok(t), r)) if r.start == 0 => {
+                Box::new(Highlight::try_from(t).ok().map(|c| (c, r)).into_iter())
+            }
+            _ => {
+                let end = s.trim_end_matches(['\r', '\n']).len();
+                Box::new(
+                    s.char_indices()
+                        .nth(width)
+                        .map(|(start, _)| start)
+                        .filter(|start| *start < end)
+                        .map(|start| (Color::LightRed.into(), start..end))
+                        .into_iter(),
+                )
+            }
+        }
+    }
+
+    fn multicomment(&self) -> Option<MultiCommentType> {
+        Some(MultiCommentType::Unidirectional(|_, _| {
+            HighlightState::Continuing(BODY)
+        }))
+    }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Some("#")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::highlighted;
+
+    #[test]
+    fn summary_and_body_widths() {
+        let summary = "s".repeat(SUMMARY_WIDTH);
+        let body = "b".repeat(BODY_WIDTH);
+        let spans = highlighted(
+            &GitCommit,
+            &format!("{summary}!!\n\n{summary}\n{body}??\n# {body}\n"),
+        );
+        assert_eq!(
+            spans,
+            [
+                ("!!".into(), Some(Color::LightRed)),
+                ("??".into(), Some(Color::LightRed)),
+                (format!("# {body}"), color::COMMENT.color),
+            ]
+        );
+    }
+}
@@ -98,13 +98,13 @@ impl TryFrom<GoToken> for Highlight {
 
     fn try_from(t: GoToken) -> Result<Highlight, ()> {
         match t {
-            GoToken::Type => Ok(color::TYPE),
-            GoToken::Keyword => Ok(color::KEYWORD),
-            GoToken::Flow => Ok(color::FLOW),
+            GoToken::Type => Ok(*color::TYPE),
+            GoToken::Keyword => Ok(*color::KEYWORD),
+            GoToken::Flow => Ok(*color::FLOW),
             GoToken::Declaration => Ok(Color::LightCyan.into()),
             GoToken::Literal => Ok(Color::Red.into()),
-            GoToken::String => Ok(color::STRING),
-            GoToken::Comment | GoToken::StartComment | GoToken::EndComment => Ok(color::COMMENT),
+            GoToken::String => Ok(*color::STRING),
+            GoToken::Comment | GoToken::StartComment | GoToken::EndComment => Ok(*color::COMMENT),
             GoToken::Identifier => Err(()),
         }
     }
@@ -130,12 +130,13 @@ impl std::fmt::Display for Go {
 }
 
 highlighter!(
+    "//", tabs_required;
     Go,
     GoToken,
     StartComment,
     EndComment,
     "/*",
     "*/",
-    color::COMMENT,
+    *color::COMMENT,
     underliner!(s, GoDef)
 );
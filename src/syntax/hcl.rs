@@ -0,0 +1,106 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::syntax::{Commenting, Highlight, Plain, color};
+use crate::{highlighter, underliner};
+use logos::Logos;
+
+#[derive(Logos, Debug)]
+#[logos(skip r"[ \t\n]+")]
+enum HclToken {
+    #[regex("[[:alpha:]_][[:alnum:]_-]*")]
+    Identifier,
+    #[token("data")]
+    #[token("locals")]
+    #[token("module")]
+    #[token("output")]
+    #[token("provider")]
+    #[token("resource")]
+    #[token("terraform")]
+    #[token("variable")]
+    Keyword,
+    #[token("for")]
+    #[token("if")]
+    #[token("in")]
+    Flow,
+    #[token("bool")]
+    #[token("list")]
+    #[token("map")]
+    #[token("number")]
+    #[token("object")]
+    #[token("set")]
+    #[token("string")]
+    #[token("tuple")]
+    Type,
+    #[token("true")]
+    #[token("false")]
+    #[token("null")]
+    Constant,
+    #[regex("-?[0-9]+(\\.[0-9]+)?([eE][+-]?[0-9]+)?")]
+    Number,
+    #[regex(r#"\"([^\\\"$]|\\.|\$\{[^}]*\}|\$)*\""#)]
+    String,
+    #[regex("#.*", allow_greedy = true)]
+    #[regex("//.*", allow_greedy = true)]
+    Comment,
+    #[token("/*")]
+    StartComment,
+    #[token("*/")]
+    EndComment,
+}
+
+impl TryFrom<HclToken> for Highlight {
+    type Error = ();
+
+    fn try_from(t: HclToken) -> Result<Highlight, ()> {
+        match t {
+            HclToken::Keyword => Ok(*color::KEYWORD),
+            HclToken::Flow => Ok(*color::FLOW),
+            HclToken::Type => Ok(*color::TYPE),
+            HclToken::Constant => Ok(*color::CONSTANT),
+            HclToken::Number => Ok(*color::NUMBER),
+            HclToken::String => Ok(*color::STRING),
+            HclToken::Identifier => Err(()),
+            HclToken::Comment | HclToken::StartComment | HclToken::EndComment => {
+                Ok(*color::COMMENT)
+            }
+        }
+    }
+}
+
+#[derive(Logos, Debug)]
+#[logos(skip r"[ \t\n]+")]
+enum HclDef {
+    #[regex(r#"resource \"[^\"]*\" \"[^\"]*\""#)]
+    #[regex(r#"data \"[^\"]*\" \"[^\"]*\""#)]
+    #[regex(r#"module \"[^\"]*\""#)]
+    #[regex(r#"variable \"[^\"]*\""#)]
+    #[regex(r#"output \"[^\"]*\""#)]
+    Definition,
+}
+
+#[derive(Debug)]
+pub struct Hcl;
+
+impl std::fmt::Display for Hcl {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        "HCL".fmt(f)
+    }
+}
+
+highlighter!(
+    "#";
+    Hcl,
+    HclToken,
+    StartComment,
+    EndComment,
+    "/*",
+    "*/",
+    *color::COMMENT,
+    underliner!(s, HclDef)
+);
@@ -39,9 +39,9 @@ impl TryFrom<HtmlToken> for Highlight {
         match t {
             HtmlToken::TagStart | HtmlToken::TagEnd => Ok(Color::Cyan.into()),
             HtmlToken::CharRef => Ok(Color::Red.into()),
-            HtmlToken::StartComment | HtmlToken::EndComment => Ok(color::COMMENT),
+            HtmlToken::StartComment | HtmlToken::EndComment => Ok(*color::COMMENT),
             HtmlToken::FieldName => Ok(Color::Blue.into()),
-            HtmlToken::String => Ok(color::STRING),
+            HtmlToken::String => Ok(*color::STRING),
         }
     }
 }
@@ -62,5 +62,5 @@ highlighter!(
     EndComment,
     "<!--",
     "-->",
-    color::COMMENT
+    *color::COMMENT
 );
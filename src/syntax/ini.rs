@@ -29,7 +29,7 @@ impl TryFrom<IniToken> for Highlight {
     fn try_from(t: IniToken) -> Result<Highlight, ()> {
         match t {
             IniToken::Key => Ok(Color::Blue.into()),
-            IniToken::Comment => Ok(color::COMMENT),
+            IniToken::Comment => Ok(*color::COMMENT),
             IniToken::Section => Ok(Color::Green.into()),
         }
     }
@@ -45,6 +45,7 @@ impl std::fmt::Display for Ini {
 }
 
 highlighter!(
+    ";";
     Ini,
     IniToken,
     Some(|s| {
@@ -80,13 +80,13 @@ impl TryFrom<JavaToken> for Highlight {
 
     fn try_from(t: JavaToken) -> Result<Highlight, ()> {
         match t {
-            JavaToken::Type => Ok(color::TYPE),
-            JavaToken::Flow => Ok(color::FLOW),
-            JavaToken::Keyword => Ok(color::KEYWORD),
-            JavaToken::String => Ok(color::STRING),
+            JavaToken::Type => Ok(*color::TYPE),
+            JavaToken::Flow => Ok(*color::FLOW),
+            JavaToken::Keyword => Ok(*color::KEYWORD),
+            JavaToken::String => Ok(*color::STRING),
             JavaToken::Annotation => Ok(Color::Magenta.into()),
             JavaToken::Comment | JavaToken::StartComment | JavaToken::EndComment => {
-                Ok(color::COMMENT)
+                Ok(*color::COMMENT)
             }
         }
     }
@@ -102,11 +102,12 @@ impl std::fmt::Display for Java {
 }
 
 highlighter!(
+    "//";
     Java,
     JavaToken,
     StartComment,
     EndComment,
     "/*",
     "*/",
-    color::COMMENT
+    *color::COMMENT
 );
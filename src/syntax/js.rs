@@ -6,9 +6,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::syntax::{Commenting, Highlight, Plain, color};
-use crate::{highlighter, underliner};
+use crate::syntax::{
+    Continued, Highlight, HighlightState, Highlighter, MultiCommentType, Plain, color,
+    highlight_continued,
+};
 use logos::Logos;
+use ratatui::style::Color;
+use std::ops::Range;
 
 #[derive(Logos, Debug)]
 #[logos(skip r"[ \t\n]+")]
@@ -56,6 +60,8 @@ enum JavaScriptToken {
     #[regex("'([^']|\\')*'")]
     #[regex(r#"\"([^\\\"]|\\.)*\""#)]
     String,
+    #[token("`")]
+    StartTemplate,
     #[regex("[[:lower:][:upper:]][[:lower:][:upper:][:digit:]_]*")]
     Identifier,
     #[regex("//.*", allow_greedy = true)]
@@ -73,11 +79,11 @@ impl TryFrom<JavaScriptToken> for Highlight {
         match t {
             JavaScriptToken::Comment
             | JavaScriptToken::StartComment
-            | JavaScriptToken::EndComment => Ok(color::COMMENT),
-            JavaScriptToken::Keyword => Ok(color::KEYWORD),
-            JavaScriptToken::Flow | JavaScriptToken::Break => Ok(color::FLOW),
-            JavaScriptToken::String => Ok(color::STRING),
-            JavaScriptToken::Number => Ok(color::NUMBER),
+            | JavaScriptToken::EndComment => Ok(*color::COMMENT),
+            JavaScriptToken::Keyword => Ok(*color::KEYWORD),
+            JavaScriptToken::Flow | JavaScriptToken::Break => Ok(*color::FLOW),
+            JavaScriptToken::String | JavaScriptToken::StartTemplate => Ok(*color::STRING),
+            JavaScriptToken::Number => Ok(*color::NUMBER),
             JavaScriptToken::Identifier => Err(()),
         }
     }
@@ -101,13 +107,168 @@ impl std::fmt::Display for JavaScript {
     }
 }
 
-highlighter!(
-    JavaScript,
-    JavaScriptToken,
-    StartComment,
-    EndComment,
-    "/*",
-    "*/",
-    color::COMMENT,
-    underliner!(s, JavaScriptDef)
-);
+impl Plain for JavaScriptToken {
+    fn is_comment_start(&self) -> bool {
+        matches!(self, Self::StartComment)
+    }
+}
+
+impl Continued for JavaScriptToken {
+    fn continues(&self) -> Option<&'static str> {
+        matches!(self, Self::StartTemplate).then_some(TEMPLATE)
+    }
+
+    fn resume(
+        s: &str,
+        start: usize,
+        _: &'static str,
+        state: &mut HighlightState,
+        spans: &mut Vec<(Highlight, Range<usize>)>,
+    ) -> usize {
+        template::<Self>(s, start, state, spans)
+    }
+}
+
+/// Closes a template string continuing from an earlier line
+pub(super) const TEMPLATE: &str = "`";
+
+/// Highlights template string's text and its ${...} expressions
+/// until the closing backtick, returning the position after it
+pub(super) fn template<'s, T>(
+    s: &'s str,
+    start: usize,
+    state: &mut HighlightState,
+    spans: &mut Vec<(Highlight, Range<usize>)>,
+) -> usize
+where
+    T: Logos<'s, Source = str, Extras: Default>,
+    Highlight: TryFrom<T>,
+{
+    const BRACE: Highlight = Highlight {
+        color: Some(Color::Yellow),
+        modifier: crate::syntax::Modifier::Plain,
+    };
+
+    let bytes = s.as_bytes();
+    let mut literal_start = start;
+    let mut idx = start;
+
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' => {
+                idx += 2;
+            }
+            b'`' => {
+                spans.push((*color::STRING, literal_start..idx + 1));
+                *state = HighlightState::Normal;
+                return idx + 1;
+            }
+            b'$' if bytes.get(idx + 1) == Some(&b'{') => {
+                let mut depth = 0;
+                match bytes[idx + 2..]
+                    .iter()
+                    .position(|b| match b {
+                        b'{' => {
+                            depth += 1;
+                            false
+                        }
+                        b'}' if depth == 0 => true,
+                        b'}' => {
+                            depth -= 1;
+                            false
+                        }
+                        _ => false,
+                    })
+                    .map(|end| idx + 2 + end)
+                {
+                    Some(end) => {
+                        if literal_start < idx {
+                            spans.push((*color::STRING, literal_start..idx));
+                        }
+                        spans.push((BRACE, idx..idx + 2));
+                        spans.extend(T::lexer(&s[idx + 2..end]).spanned().filter_map(|(t, r)| {
+                            t.ok()
+                                .and_then(|t| Highlight::try_from(t).ok())
+                                .map(|c| (c, r.start + idx + 2..r.end + idx + 2))
+                        }));
+                        spans.push((BRACE, end..end + 1));
+                        idx = end + 1;
+                        literal_start = idx;
+                    }
+                    None => {
+                        idx += 2;
+                    }
+                }
+            }
+            _ => {
+                idx += 1;
+            }
+        }
+    }
+
+    if literal_start < s.len() {
+        spans.push((*color::STRING, literal_start..s.len()));
+    }
+
+    s.len()
+}
+
+impl Highlighter for JavaScript {
+    fn highlight<'s>(
+        &self,
+        s: &'s str,
+        state: &'s mut HighlightState,
+    ) -> Box<dyn Iterator<Item = (Highlight, Range<usize>)> + 's> {
+        Box::new(highlight_continued::<JavaScriptToken>(s, state).into_iter())
+    }
+
+    fn underline(
+        &self,
+    ) -> Option<for<'s> fn(&'s str) -> Box<dyn Iterator<Item = Range<usize>> + 's>> {
+        Some(|s| {
+            Box::new(
+                JavaScriptDef::lexer(s)
+                    .spanned()
+                    .filter_map(|(t, r)| t.ok().map(|_| r)),
+            )
+        })
+    }
+
+    fn multicomment(&self) -> Option<MultiCommentType> {
+        Some(MultiCommentType::Unidirectional(|mut state, s| {
+            highlight_continued::<JavaScriptToken>(s, &mut state);
+            state
+        }))
+    }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Some("//")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::highlighted;
+
+    #[test]
+    fn template_interpolation() {
+        let spans = highlighted(&JavaScript, "let s = `hello ${name}!`;\n");
+        assert!(spans.contains(&("hello ".into(), color::STRING.color)));
+        assert!(spans.contains(&("${".into(), Some(Color::Yellow))));
+        assert!(spans.contains(&("}".into(), Some(Color::Yellow))));
+        assert!(spans.contains(&("!`".into(), color::STRING.color)));
+        // the interpolated identifier isn't colored as the string's text
+        assert!(!spans.iter().any(|(text, _)| text.contains("name")));
+    }
+
+    #[test]
+    fn template_continues_across_lines() {
+        let spans = highlighted(&JavaScript, "let s = `one\n${two} /* three\n` + four;\n");
+        assert!(spans.contains(&("one\n".into(), color::STRING.color)));
+        assert!(spans.contains(&(" /* three\n".into(), color::STRING.color)));
+        assert!(spans.contains(&("`".into(), color::STRING.color)));
+        // code resumes once the template closes
+        assert!(!spans.iter().any(|(text, _)| text.contains("four")));
+    }
+}
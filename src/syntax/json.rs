@@ -39,9 +39,9 @@ impl TryFrom<JsonToken> for Highlight {
 
     fn try_from(t: JsonToken) -> Result<Highlight, ()> {
         match t {
-            JsonToken::Name => Ok(color::TYPE),
-            JsonToken::String => Ok(color::STRING),
-            JsonToken::Number => Ok(color::NUMBER),
+            JsonToken::Name => Ok(*color::TYPE),
+            JsonToken::String => Ok(*color::STRING),
+            JsonToken::Number => Ok(*color::NUMBER),
             JsonToken::Literal => Ok(Color::Red.into()),
             JsonToken::Punctuation1 => Ok(Color::LightBlue.into()),
             JsonToken::Punctuation2 => Ok(Color::LightRed.into()),
@@ -0,0 +1,223 @@
+// Copyright 2026 Brian Langenberger
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::syntax::{
+    Continued, Highlight, HighlightState, Highlighter, MultiCommentType, Plain, color,
+    highlight_continued,
+};
+use logos::Logos;
+use ratatui::style::Color;
+use std::ops::Range;
+
+#[derive(Logos, Debug)]
+#[logos(skip r"[ \t\n]+")]
+enum KotlinToken {
+    #[regex("[[:upper:][:lower:]_][[:upper:][:lower:][:digit:]_]*")]
+    Identifier,
+    #[regex(r#"\"([^\\\"]|\\.)*\""#)]
+    #[regex(r"'([^\\\']|\\.){0,2}'")]
+    String,
+    #[token("abstract")]
+    #[token("annotation")]
+    #[token("as")]
+    #[token("by")]
+    #[token("class")]
+    #[token("companion")]
+    #[token("const")]
+    #[token("constructor")]
+    #[token("data")]
+    #[token("enum")]
+    #[token("external")]
+    #[token("false")]
+    #[token("final")]
+    #[token("fun")]
+    #[token("get")]
+    #[token("import")]
+    #[token("in")]
+    #[token("infix")]
+    #[token("init")]
+    #[token("inline")]
+    #[token("inner")]
+    #[token("interface")]
+    #[token("internal")]
+    #[token("is")]
+    #[token("lateinit")]
+    #[token("noinline")]
+    #[token("null")]
+    #[token("object")]
+    #[token("open")]
+    #[token("operator")]
+    #[token("out")]
+    #[token("override")]
+    #[token("package")]
+    #[token("private")]
+    #[token("protected")]
+    #[token("public")]
+    #[token("reified")]
+    #[token("sealed")]
+    #[token("set")]
+    #[token("super")]
+    #[token("suspend")]
+    #[token("this")]
+    #[token("true")]
+    #[token("typealias")]
+    #[token("val")]
+    #[token("value")]
+    #[token("var")]
+    #[token("vararg")]
+    #[token("where")]
+    Keyword,
+    #[token("break")]
+    #[token("catch")]
+    #[token("continue")]
+    #[token("do")]
+    #[token("else")]
+    #[token("finally")]
+    #[token("for")]
+    #[token("if")]
+    #[token("return")]
+    #[token("throw")]
+    #[token("try")]
+    #[token("when")]
+    #[token("while")]
+    Flow,
+    #[token("Any")]
+    #[token("Array")]
+    #[token("Boolean")]
+    #[token("Byte")]
+    #[token("Char")]
+    #[token("Double")]
+    #[token("Float")]
+    #[token("Int")]
+    #[token("List")]
+    #[token("Long")]
+    #[token("Map")]
+    #[token("Nothing")]
+    #[token("Set")]
+    #[token("Short")]
+    #[token("String")]
+    #[token("UInt")]
+    #[token("ULong")]
+    #[token("Unit")]
+    Type,
+    #[regex("@[[:alpha:]_][[:alnum:]_.]*")]
+    Annotation,
+    #[regex("//.*", allow_greedy = true)]
+    Comment,
+    #[token("/*")]
+    StartComment,
+    #[token("*/")]
+    EndComment,
+    #[token("\"\"\"")]
+    StartRawString,
+}
+
+impl TryFrom<KotlinToken> for Highlight {
+    type Error = ();
+
+    fn try_from(t: KotlinToken) -> Result<Highlight, ()> {
+        match t {
+            KotlinToken::String | KotlinToken::StartRawString => Ok(*color::STRING),
+            KotlinToken::Keyword => Ok(*color::KEYWORD),
+            KotlinToken::Flow => Ok(*color::FLOW),
+            KotlinToken::Type => Ok(*color::TYPE),
+            KotlinToken::Annotation => Ok(Color::Magenta.into()),
+            KotlinToken::Identifier => Err(()),
+            KotlinToken::Comment | KotlinToken::StartComment | KotlinToken::EndComment => {
+                Ok(*color::COMMENT)
+            }
+        }
+    }
+}
+
+#[derive(Logos, Debug)]
+#[logos(skip r"[ \t\n]+")]
+enum KotlinDef {
+    #[regex("fun [[:upper:][:lower:]_][[:upper:][:lower:][:digit:]_]*")]
+    #[regex("class [[:upper:][:lower:]_][[:upper:][:lower:][:digit:]_]*")]
+    #[regex("object [[:upper:][:lower:]_][[:upper:][:lower:][:digit:]_]*")]
+    #[regex("interface [[:upper:][:lower:]_][[:upper:][:lower:][:digit:]_]*")]
+    Definition,
+}
+
+#[derive(Debug)]
+pub struct Kotlin;
+
+impl std::fmt::Display for Kotlin {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        "Kotlin".fmt(f)
+    }
+}
+
+impl Plain for KotlinToken {
+    fn is_comment_start(&self) -> bool {
+        matches!(self, Self::StartComment)
+    }
+}
+
+impl Continued for KotlinToken {
+    fn continues(&self) -> Option<&'static str> {
+        matches!(self, Self::StartRawString).then_some(RAW_STRING)
+    }
+}
+
+/// Closes a raw string continuing from an earlier line
+const RAW_STRING: &str = "\"\"\"";
+
+impl Highlighter for Kotlin {
+    fn highlight<'s>(
+        &self,
+        s: &'s str,
+        state: &'s mut HighlightState,
+    ) -> Box<dyn Iterator<Item = (Highlight, Range<usize>)> + 's> {
+        Box::new(highlight_continued::<KotlinToken>(s, state).into_iter())
+    }
+
+    fn underline(
+        &self,
+    ) -> Option<for<'s> fn(&'s str) -> Box<dyn Iterator<Item = Range<usize>> + 's>> {
+        Some(|s| {
+            Box::new(
+                KotlinDef::lexer(s)
+                    .spanned()
+                    .filter_map(|(t, r)| t.ok().map(|_| r)),
+            )
+        })
+    }
+
+    fn multicomment(&self) -> Option<MultiCommentType> {
+        Some(MultiCommentType::Unidirectional(|mut state, s| {
+            highlight_continued::<KotlinToken>(s, &mut state);
+            state
+        }))
+    }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Some("//")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::highlighted;
+
+    #[test]
+    fn raw_string_continues_across_lines() {
+        let spans = highlighted(
+            &Kotlin,
+            "val s = \"\"\"one\n\"two\" /* three\n\"\"\" + four\n",
+        );
+        let fence = ("\"\"\"".to_string(), color::STRING.color);
+        assert_eq!(spans.iter().filter(|span| **span == fence).count(), 2);
+        assert!(spans.contains(&("one\n".into(), color::STRING.color)));
+        assert!(spans.contains(&("\"two\" /* three\n".into(), color::STRING.color)));
+        // code resumes once the raw string closes
+        assert!(!spans.iter().any(|(text, _)| text.contains("four")));
+    }
+}
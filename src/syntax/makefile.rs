@@ -28,7 +28,7 @@ impl TryFrom<MakefileToken> for Highlight {
         match t {
             MakefileToken::Variable => Ok(Color::Cyan.into()),
             MakefileToken::Assignment => Ok(Color::Red.into()),
-            MakefileToken::Comment => Ok(color::COMMENT),
+            MakefileToken::Comment => Ok(*color::COMMENT),
         }
     }
 }
@@ -58,4 +58,8 @@ impl crate::syntax::Highlighter for Makefile {
     fn tabs_required(&self) -> bool {
         true
     }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Some("#")
+    }
 }
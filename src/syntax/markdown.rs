@@ -7,9 +7,10 @@
 // except according to those terms.
 
 // use crate::highlighter;
-use crate::syntax::{Highlight, Highlighter};
+use crate::syntax::{DefaultHighlighter, Highlight, HighlightState, Highlighter, MultiCommentType};
 use logos::Logos;
 use ratatui::style::Color;
+use std::rc::Rc;
 
 #[derive(Logos, Debug)]
 #[logos(skip r"[ \t\n]+")]
@@ -19,6 +20,7 @@ enum MarkdownToken {
     #[regex(r"\*[^*]+\*")]
     Emphasis,
     Heading,
+    Fence,
     #[regex(r"\[[^]]+\]\([^)]+\)")]
     Url,
     #[regex(r"\[[^]]+\]")]
@@ -44,6 +46,7 @@ impl TryFrom<MarkdownToken> for Highlight {
                 color: Some(Color::Blue),
                 modifier: Modifier::Underlined,
             }),
+            MarkdownToken::Fence => Ok(Color::Cyan.into()),
             MarkdownToken::Url => Ok(Color::Blue.into()),
             MarkdownToken::Link => Ok(Color::Magenta.into()),
         }
@@ -59,13 +62,48 @@ impl std::fmt::Display for Markdown {
     }
 }
 
+/// Returns highlighter for a fenced code block's language tag
+fn fence_syntax(tag: &str) -> Rc<dyn Highlighter> {
+    match tag.split_whitespace().next() {
+        Some(lang) => crate::syntax::syntax_by_extension(match lang.to_lowercase().as_str() {
+            "rust" => "rs",
+            "python" | "python3" => "py",
+            "javascript" => "js",
+            "typescript" => "ts",
+            "bash" | "shell" | "zsh" => "sh",
+            "golang" => "go",
+            "kotlin" => "kt",
+            "perl" => "pl",
+            "yml" => "yaml",
+            "c++" => "cpp",
+            "terraform" => "tf",
+            _ => lang,
+        })
+        .into(),
+        None => Rc::new(DefaultHighlighter),
+    }
+}
+
 impl Highlighter for Markdown {
     fn highlight<'s>(
         &self,
         s: &'s str,
-        _state: &'s mut crate::syntax::HighlightState,
+        state: &'s mut HighlightState,
     ) -> Box<dyn Iterator<Item = (Highlight, std::ops::Range<usize>)> + 's> {
-        if s.starts_with('#') {
+        if let Some(tag) = s.trim_start().strip_prefix("```") {
+            *state = match state {
+                HighlightState::Fenced(..) => HighlightState::Normal,
+                _ => HighlightState::Fenced(fence_syntax(tag), Box::default()),
+            };
+            Box::new(
+                Highlight::try_from(MarkdownToken::Fence)
+                    .ok()
+                    .map(|h| (h, 0..s.len()))
+                    .into_iter(),
+            )
+        } else if let HighlightState::Fenced(syntax, state) = state {
+            syntax.highlight(s, state)
+        } else if s.starts_with('#') {
             Box::new(
                 Highlight::try_from(MarkdownToken::Heading)
                     .ok()
@@ -87,4 +125,55 @@ impl Highlighter for Markdown {
             }))
         }
     }
+
+    fn multicomment(&self) -> Option<MultiCommentType> {
+        Some(MultiCommentType::Unidirectional(|state, s| {
+            match (s.trim_start().strip_prefix("```"), state) {
+                (Some(_), HighlightState::Fenced(..)) => HighlightState::Normal,
+                (Some(tag), _) => HighlightState::Fenced(fence_syntax(tag), Box::default()),
+                // the fenced syntax carries its own state from line to line
+                (None, HighlightState::Fenced(syntax, mut state)) => {
+                    *state = match syntax.multicomment() {
+                        Some(MultiCommentType::Unidirectional(f)) => f(*state, s),
+                        Some(MultiCommentType::Bidirectional) => {
+                            syntax.highlight(s, &mut state).for_each(drop);
+                            *state
+                        }
+                        None => HighlightState::default(),
+                    };
+                    HighlightState::Fenced(syntax, state)
+                }
+                (None, state) => state,
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::{color, highlighted};
+
+    #[test]
+    fn fenced_code_blocks() {
+        let spans = highlighted(
+            &Markdown,
+            "# Title\n```rust\n/* open\n# still comment\n*/ fn *f*() {}\n```\n*emphasis*\n",
+        );
+        assert!(spans.contains(&("```rust\n".into(), Some(Color::Cyan))));
+        assert!(spans.contains(&("```\n".into(), Some(Color::Cyan))));
+        // the block's lines are highlighted as Rust, including its comment's state
+        assert_eq!(
+            spans
+                .iter()
+                .filter(|(_, c)| *c == color::COMMENT.color)
+                .map(|(text, _)| text.as_str())
+                .collect::<String>(),
+            "/*open#stillcomment*/"
+        );
+        assert!(spans.contains(&("fn".into(), color::KEYWORD.color)));
+        assert!(!spans.iter().any(|(text, _)| text == "*f*"));
+        // while Markdown resumes after the block
+        assert!(spans.iter().any(|(text, _)| text == "*emphasis*"));
+    }
 }
@@ -9,20 +9,27 @@
 use crate::buffer::Source;
 use logos::{Lexer, Logos};
 use ratatui::style::Color;
+use std::rc::Rc;
 
+mod asm;
 mod c;
+mod cmake;
 mod cpp;
 mod css;
 mod csv;
 mod cue;
+mod dockerfile;
 mod fish;
 mod flac;
+mod gitcommit;
 mod go;
+mod hcl;
 mod html;
 mod ini;
 mod java;
 mod js;
 mod json;
+mod kotlin;
 mod makefile;
 mod markdown;
 mod patch;
@@ -53,21 +60,25 @@ mod zig;
 // Boldface is also difficult to detect in a dark color scheme
 // and shouldn't be relied upon.
 
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub enum HighlightState {
     #[default]
     Normal,
     Commenting,
-}
-
-/// A multi-line comment start or end
-pub enum MultiComment {
-    Start,
-    End,
+    /// Within some other construct of a particular syntax
+    /// which may continue onto following lines,
+    /// tagged by whatever tells that syntax's constructs apart,
+    /// such as the marker which closes a template string
+    Continuing(&'static str),
+    /// Within a fenced code block highlighted by its own syntax
+    Fenced(Rc<dyn Highlighter>, Box<HighlightState>),
 }
 
 pub enum MultiCommentType {
-    Bidirectional(fn(&str) -> Option<MultiComment>),
+    /// Comments which open and close with distinct markers,
+    /// whose state is tracked by the highlighter itself
+    Bidirectional,
+    /// Function which updates the state after a given line
     Unidirectional(fn(HighlightState, &str) -> HighlightState),
 }
 
@@ -129,18 +140,23 @@ pub trait Highlighter: std::fmt::Debug + std::fmt::Display {
         None
     }
 
-    /// Returns true if the format requires actual tabs instead of spaces
-    /// (pretty sure this only applies to Makefiles)
+    /// Returns true if the format requires actual tabs instead of spaces,
+    /// like Makefiles, or is conventionally indented with them, like Go
     fn tabs_required(&self) -> bool {
         false
     }
 
     /// If format supports multi-line comments,
-    /// returns function which returns the first one that
-    /// exists in a line, if any
+    /// returns how their state carries from line to line
     fn multicomment(&self) -> Option<MultiCommentType> {
         None
     }
+
+    /// If format supports single-line comments,
+    /// returns the marker which starts them
+    fn line_comment(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 impl Highlighter for Box<dyn Highlighter> {
@@ -165,6 +181,10 @@ impl Highlighter for Box<dyn Highlighter> {
     fn multicomment(&self) -> Option<MultiCommentType> {
         Box::as_ref(self).multicomment()
     }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Box::as_ref(self).line_comment()
+    }
 }
 
 #[derive(Debug)]
@@ -187,6 +207,7 @@ impl std::fmt::Display for DefaultHighlighter {
 }
 
 pub use regex::Regex;
+pub use sh::Shell;
 pub use test::Test;
 pub use tutorial::Tutorial;
 
@@ -210,8 +231,8 @@ where
 {
     pub fn new(state: &HighlightState, source: &'s <P as Logos<'s>>::Source) -> Self {
         match state {
-            HighlightState::Normal => Self::Plain(Lexer::new(source)),
             HighlightState::Commenting => Self::Commenting(Lexer::new(source)),
+            _ => Self::Plain(Lexer::new(source)),
         }
     }
 }
@@ -255,7 +276,217 @@ where
     }
 }
 
-pub fn syntax(source: &Source) -> Box<dyn Highlighter> {
+/// Implemented for tokens of languages whose /* */ comments
+/// and some other constructs, like template or raw strings,
+/// may continue onto following lines
+pub trait Continued: Plain {
+    /// If the token opens such a construct, the marker which closes it
+    fn continues(&self) -> Option<&'static str>;
+
+    /// Highlights the construct continuing from start
+    /// until the given marker closes it, if it does,
+    /// returning the position after what was highlighted
+    ///
+    /// By default, the whole construct is highlighted as a string
+    fn resume(
+        s: &str,
+        start: usize,
+        terminator: &'static str,
+        state: &mut HighlightState,
+        spans: &mut Vec<(Highlight, std::ops::Range<usize>)>,
+    ) -> usize {
+        close(s, start, terminator, *color::STRING, state, spans)
+    }
+}
+
+/// Highlights a line whose comments and other constructs
+/// may continue from earlier lines or onto following ones
+pub fn highlight_continued<'s, T>(
+    s: &'s str,
+    state: &mut HighlightState,
+) -> Vec<(Highlight, std::ops::Range<usize>)>
+where
+    T: Logos<'s, Source = str, Extras: Default> + Continued,
+    Highlight: TryFrom<T>,
+{
+    let mut spans = vec![];
+    let mut pos = 0;
+
+    while pos < s.len() {
+        match *state {
+            HighlightState::Commenting => {
+                pos = close(s, pos, "*/", *color::COMMENT, state, &mut spans);
+            }
+            HighlightState::Continuing(terminator) => {
+                pos = T::resume(s, pos, terminator, state, &mut spans);
+            }
+            _ => {
+                let start = pos;
+                let mut lexer = T::lexer(&s[start..]);
+                pos = s.len();
+                while let Some(t) = lexer.next() {
+                    let r = lexer.span().start + start..lexer.span().end + start;
+                    match t {
+                        Ok(t) if t.is_comment_start() => {
+                            spans.push((*color::COMMENT, r.clone()));
+                            *state = HighlightState::Commenting;
+                            pos = r.end;
+                            break;
+                        }
+                        Ok(t) => match t.continues() {
+                            Some(terminator) => {
+                                spans.push((*color::STRING, r.clone()));
+                                *state = HighlightState::Continuing(terminator);
+                                pos = r.end;
+                                break;
+                            }
+                            None => {
+                                if let Ok(c) = Highlight::try_from(t) {
+                                    spans.push((c, r));
+                                }
+                            }
+                        },
+                        Err(_) => { /* unhighlighted */ }
+                    }
+                }
+            }
+        }
+    }
+
+    spans
+}
+
+/// Highlights from start through the given terminator,
+/// or to the end of the line if it isn't found,
+/// returning the position after what was highlighted
+fn close(
+    s: &str,
+    start: usize,
+    terminator: &str,
+    color: Highlight,
+    state: &mut HighlightState,
+    spans: &mut Vec<(Highlight, std::ops::Range<usize>)>,
+) -> usize {
+    match s[start..].find(terminator) {
+        Some(end) => {
+            let end = start + end + terminator.len();
+            spans.push((color, start..end));
+            *state = HighlightState::Normal;
+            end
+        }
+        None => {
+            spans.push((color, start..s.len()));
+            s.len()
+        }
+    }
+}
+
+/// Returns highlighter for the given source,
+/// falling back to its first line's shebang, if any
+pub fn syntax(source: &Source, rope: &ropey::Rope) -> Box<dyn Highlighter> {
+    if matches!(source, Source::Test) {
+        return Box::new(Test);
+    }
+
+    match source.file_name().as_deref().and_then(syntax_by_file_name) {
+        Some(syntax) => syntax,
+        None => match source.extension() {
+            Some(extension) => syntax_by_extension(extension),
+            None => syntax_by_shebang(rope).unwrap_or(Box::new(DefaultHighlighter)),
+        },
+    }
+}
+
+/// Returns highlighter for well-known file names
+/// which have no extension or a misleading one
+fn syntax_by_file_name(file_name: &str) -> Option<Box<dyn Highlighter>> {
+    match file_name {
+        "Makefile" | "makefile" | "GNUmakefile" => Some(Box::new(makefile::Makefile)),
+        "Dockerfile" | "Containerfile" => Some(Box::new(dockerfile::Dockerfile)),
+        "CMakeLists.txt" => Some(Box::new(cmake::CMake)),
+        "COMMIT_EDITMSG" | "MERGE_MSG" | "TAG_EDITMSG" => Some(Box::new(gitcommit::GitCommit)),
+        ".bashrc" | ".bash_profile" | ".bash_login" | ".bash_logout" | ".bash_aliases"
+        | ".profile" | ".zshrc" | ".zprofile" | ".zshenv" => Some(Box::new(sh::Shell)),
+        ".gitconfig" | ".gitmodules" | ".editorconfig" => Some(Box::new(ini::Ini)),
+        _ => None,
+    }
+}
+
+/// Returns highlighter for the interpreter named
+/// in a "#!" line at the start of the file, if any
+fn syntax_by_shebang(rope: &ropey::Rope) -> Option<Box<dyn Highlighter>> {
+    let line = std::borrow::Cow::<str>::from(rope.lines().next()?);
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let interpreter = match words.next()?.rsplit('/').next()? {
+        // skip any flags to env, such as -S
+        "env" => words.find(|w| !w.starts_with('-'))?,
+        interpreter => interpreter,
+    };
+
+    match interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.') {
+        "python" => Some(syntax_by_extension("py")),
+        "sh" | "bash" | "dash" | "ksh" | "zsh" => Some(syntax_by_extension("sh")),
+        "fish" => Some(syntax_by_extension("fish")),
+        "perl" => Some(syntax_by_extension("pl")),
+        "node" | "nodejs" => Some(syntax_by_extension("js")),
+        "php" => Some(syntax_by_extension("php")),
+        _ => None,
+    }
+}
+
+/// Returns every highlighter whose name contains the given text,
+/// ignoring case, for choosing one manually
+pub fn syntaxes_matching(name: &str) -> Vec<Box<dyn Highlighter>> {
+    let name = name.to_lowercase();
+
+    let mut syntaxes: Vec<Box<dyn Highlighter>> = vec![
+        Box::new(DefaultHighlighter),
+        Box::new(asm::Assembly::GAS),
+        Box::new(asm::Assembly::NASM),
+        Box::new(c::C),
+        Box::new(cmake::CMake),
+        Box::new(cpp::Cpp),
+        Box::new(css::Css),
+        Box::new(csv::Csv),
+        Box::new(cue::Cuesheet),
+        Box::new(dockerfile::Dockerfile),
+        Box::new(fish::Fish),
+        Box::new(flac::Analysis),
+        Box::new(gitcommit::GitCommit),
+        Box::new(go::Go),
+        Box::new(hcl::Hcl),
+        Box::new(html::Html),
+        Box::new(ini::Ini),
+        Box::new(java::Java),
+        Box::new(js::JavaScript),
+        Box::new(json::Json),
+        Box::new(kotlin::Kotlin),
+        Box::new(makefile::Makefile),
+        Box::new(markdown::Markdown),
+        Box::new(patch::Patch),
+        Box::new(perl::Perl),
+        Box::new(php::Php),
+        Box::new(python::Python),
+        Box::new(ron::Ron),
+        Box::new(rust::Rust),
+        Box::new(sh::Shell),
+        Box::new(sql::Sql),
+        Box::new(swift::Swift),
+        Box::new(tex::Tex),
+        Box::new(toml::Toml),
+        Box::new(ts::TypeScript),
+        Box::new(xml::Xml),
+        Box::new(yaml::Yaml),
+        Box::new(zig::Zig),
+    ];
+
+    syntaxes.retain(|syntax| syntax.to_string().to_lowercase().contains(&name));
+    syntaxes.sort_by_cached_key(|syntax| syntax.to_string());
+    syntaxes
+}
+
+/// Returns highlighter for the given file extension
+pub fn syntax_by_extension(extension: &str) -> Box<dyn Highlighter> {
     use std::collections::HashMap;
     use std::sync::LazyLock;
 
@@ -275,60 +506,62 @@ pub fn syntax(source: &Source) -> Box<dyn Highlighter> {
             .unwrap_or_default()
     });
 
-    if matches!(source, Source::Test) {
-        return Box::new(Test);
-    }
-
-    match source
-        .extension()
-        .map(|ext| EXT_MAP.get(ext).map(|s| s.as_str()).unwrap_or(ext))
+    match EXT_MAP
+        .get(extension)
+        .map(|s| s.as_str())
+        .unwrap_or(extension)
     {
-        None => match source.file_name() {
-            Some(file_name) => match file_name.as_ref() {
-                "Makefile" | "makefile" => Box::new(makefile::Makefile),
-                _ => Box::new(DefaultHighlighter),
-            },
-            None => Box::new(DefaultHighlighter),
-        },
-        Some("rs") => Box::new(rust::Rust),
-        Some("c" | "h" | "C" | "H") => Box::new(c::C),
-        Some("cpp" | "cc" | "cxx" | "c++" | "hh" | "hpp" | "hxx" | "h++") => Box::new(cpp::Cpp),
-        Some("py") => Box::new(python::Python),
-        Some("json") => Box::new(json::Json),
-        Some("ron") => Box::new(ron::Ron),
-        Some("md" | "markdown") => Box::new(markdown::Markdown),
-        Some("html" | "htm") => Box::new(html::Html),
-        Some("xml" | "svg") => Box::new(xml::Xml),
-        Some("sql") => Box::new(sql::Sql),
-        Some("css") => Box::new(css::Css),
-        Some("js") => Box::new(js::JavaScript),
-        Some("ts") => Box::new(ts::TypeScript),
-        Some("php") => Box::new(php::Php),
-        Some("yaml") => Box::new(yaml::Yaml),
-        Some("java") => Box::new(java::Java),
-        Some("go") => Box::new(go::Go),
-        Some("patch" | "diff") => Box::new(patch::Patch),
-        Some("csv") => Box::new(csv::Csv),
-        Some("toml") => Box::new(toml::Toml),
-        Some("ini") => Box::new(ini::Ini),
-        Some("fish") => Box::new(fish::Fish),
-        Some("sh") => Box::new(sh::Shell),
-        Some("zig") => Box::new(zig::Zig),
-        Some("swift") => Box::new(swift::Swift),
-        Some("pl" | "pm") => Box::new(perl::Perl),
-        Some("tex") => Box::new(tex::Tex),
-        Some("ana") => Box::new(flac::Analysis),
-        Some("cue" | "CUE") => Box::new(cue::Cuesheet),
+        "rs" => Box::new(rust::Rust),
+        "c" | "h" | "C" | "H" => Box::new(c::C),
+        "cpp" | "cc" | "cxx" | "c++" | "hh" | "hpp" | "hxx" | "h++" => Box::new(cpp::Cpp),
+        "py" => Box::new(python::Python),
+        "json" => Box::new(json::Json),
+        "ron" => Box::new(ron::Ron),
+        "md" | "markdown" => Box::new(markdown::Markdown),
+        "html" | "htm" => Box::new(html::Html),
+        "xml" | "svg" => Box::new(xml::Xml),
+        "sql" => Box::new(sql::Sql),
+        "css" => Box::new(css::Css),
+        "js" => Box::new(js::JavaScript),
+        "ts" => Box::new(ts::TypeScript),
+        "php" => Box::new(php::Php),
+        "yaml" => Box::new(yaml::Yaml),
+        "java" => Box::new(java::Java),
+        "go" => Box::new(go::Go),
+        "patch" | "diff" => Box::new(patch::Patch),
+        "csv" => Box::new(csv::Csv),
+        "toml" => Box::new(toml::Toml),
+        "ini" => Box::new(ini::Ini),
+        "fish" => Box::new(fish::Fish),
+        "sh" => Box::new(sh::Shell),
+        "zig" => Box::new(zig::Zig),
+        "swift" => Box::new(swift::Swift),
+        "kt" | "kts" => Box::new(kotlin::Kotlin),
+        "pl" | "pm" => Box::new(perl::Perl),
+        "tex" => Box::new(tex::Tex),
+        "ana" => Box::new(flac::Analysis),
+        "cue" | "CUE" => Box::new(cue::Cuesheet),
+        "dockerfile" => Box::new(dockerfile::Dockerfile),
+        "cmake" => Box::new(cmake::CMake),
+        "tf" | "hcl" => Box::new(hcl::Hcl),
+        "s" | "S" => Box::new(asm::Assembly::GAS),
+        "asm" | "nasm" => Box::new(asm::Assembly::NASM),
         _ => Box::new(DefaultHighlighter),
     }
 }
 
 #[macro_export]
 macro_rules! highlighter {
-    ($syntax:ty, $token:ty) => {
-        highlighter!($syntax, $token, None);
+    ($line_comment:literal, tabs_required; $($rest:tt)*) => {
+        highlighter!(@ Some($line_comment), true; $($rest)*);
+    };
+    ($line_comment:literal; $($rest:tt)*) => {
+        highlighter!(@ Some($line_comment), false; $($rest)*);
+    };
+    (@ $line_comment:expr, $tabs_required:expr; $syntax:ty, $token:ty) => {
+        highlighter!(@ $line_comment, $tabs_required; $syntax, $token, None);
     };
-    ($syntax:ty, $token:ty, $underliner:expr) => {
+    (@ $line_comment:expr, $tabs_required:expr; $syntax:ty, $token:ty, $underliner:expr) => {
         impl $crate::syntax::Highlighter for $syntax {
             fn highlight<'s>(
                 &self,
@@ -347,12 +580,20 @@ macro_rules! highlighter {
             ) -> Option<for<'s> fn(&'s str) -> Box<dyn Iterator<Item = std::ops::Range<usize>> + 's>> {
                 $underliner
             }
+
+            fn tabs_required(&self) -> bool {
+                $tabs_required
+            }
+
+            fn line_comment(&self) -> Option<&'static str> {
+                $line_comment
+            }
         }
     };
-    ($syntax:ty, $token:ty, $comment_start:ident, $comment_end:ident, $start:literal, $end:literal, $comment_color:expr) => {
-        highlighter!($syntax, $token, $comment_start, $comment_end, $start, $end, $comment_color, None);
+    (@ $line_comment:expr, $tabs_required:expr; $syntax:ty, $token:ty, $comment_start:ident, $comment_end:ident, $start:literal, $end:literal, $comment_color:expr) => {
+        highlighter!(@ $line_comment, $tabs_required; $syntax, $token, $comment_start, $comment_end, $start, $end, $comment_color, None);
     };
-    ($syntax:ty, $token:ty, $comment_start:ident, $comment_end:ident, $start:literal, $end:literal, $comment_color:expr, $underliner:expr) => {
+    (@ $line_comment:expr, $tabs_required:expr; $syntax:ty, $token:ty, $comment_start:ident, $comment_end:ident, $start:literal, $end:literal, $comment_color:expr, $underliner:expr) => {
         impl Plain for $token {
             fn is_comment_start(&self) -> bool {
                 matches!(self, Self::$comment_start)
@@ -398,7 +639,14 @@ macro_rules! highlighter {
 
                 Box::new(lexer.filter_map(move |(t, r)| {
                     match state {
-                        HighlightState::Normal => t
+                        HighlightState::Commenting => Some(match t {
+                            Ok(end) if end.is_comment_end() => {
+                                *state = HighlightState::default();
+                                (Highlight::try_from(end).ok()?, r)
+                            }
+                            _ => ($comment_color, r),
+                        }),
+                        _ => t
                             .ok()
                             .inspect(|t| {
                                 if t.is_comment_start() {
@@ -407,13 +655,6 @@ macro_rules! highlighter {
                             })
                             .and_then(|t| Highlight::try_from(t).ok())
                             .map(|c| (c, r)),
-                        HighlightState::Commenting => Some(match t {
-                            Ok(end) if end.is_comment_end() => {
-                                *state = HighlightState::default();
-                                (Highlight::try_from(end).ok()?, r)
-                            }
-                            _ => ($comment_color, r),
-                        }),
                     }
                 }))
             }
@@ -425,32 +666,21 @@ macro_rules! highlighter {
             }
 
             fn multicomment(&self) -> Option<$crate::syntax::MultiCommentType> {
-                use $crate::syntax::{MultiComment, MultiCommentType};
-
-                #[derive(Logos, Debug)]
-                #[logos(skip r"[ \t\n]+")]
-                enum Comment {
-                    #[token($start)]
-                    Start,
-                    #[token($end)]
-                    End,
-                }
+                Some($crate::syntax::MultiCommentType::Bidirectional)
+            }
 
-                impl From<Comment> for MultiComment {
-                    fn from(c: Comment) -> MultiComment {
-                        match c {
-                            Comment::Start => MultiComment::Start,
-                            Comment::End => MultiComment::End,
-                        }
-                    }
-                }
+            fn tabs_required(&self) -> bool {
+                $tabs_required
+            }
 
-                Some(MultiCommentType::Bidirectional(|s: &str| {
-                    Comment::lexer(s).find_map(|token| token.ok().map(|t| t.into()))
-                }))
+            fn line_comment(&self) -> Option<&'static str> {
+                $line_comment
             }
         }
     };
+    ($($rest:tt)*) => {
+        highlighter!(@ None, false; $($rest)*);
+    };
 }
 
 #[macro_export]
@@ -466,38 +696,270 @@ macro_rules! underliner {
     };
 }
 
+/// Highlights text line by line, carrying state from line to line
+/// just as a buffer does, and returns each highlighted span's text
+/// along with its color
+#[cfg(test)]
+fn highlighted(syntax: &dyn Highlighter, text: &str) -> Vec<(String, Option<Color>)> {
+    let mut state = HighlightState::default();
+    let mut spans = vec![];
+
+    for line in text.split_inclusive('\n') {
+        let mut highlighting = state.clone();
+        spans.extend(
+            syntax
+                .highlight(line, &mut highlighting)
+                .map(|(h, r)| (line[r].to_string(), h.color)),
+        );
+        state = match syntax.multicomment() {
+            Some(MultiCommentType::Unidirectional(f)) => f(state, line),
+            Some(MultiCommentType::Bidirectional) => highlighting,
+            None => HighlightState::default(),
+        };
+    }
+
+    spans
+}
+
 pub mod color {
+    use crate::config::{InvalidRon, fields, strip_comments, unquote};
     use crate::syntax::{Highlight, Modifier};
     use ratatui::style::Color;
+    use std::sync::LazyLock;
 
     // A unified color scheme across common syntax items
 
-    pub const KEYWORD: Highlight = Highlight {
-        color: Some(Color::Blue),
-        modifier: Modifier::Plain,
-    };
-    pub const FLOW: Highlight = Highlight {
-        color: Some(Color::Blue),
-        modifier: Modifier::Plain,
-    };
-    pub const CONSTANT: Highlight = Highlight {
-        color: Some(Color::Red),
-        modifier: Modifier::Plain,
-    };
-    pub const TYPE: Highlight = Highlight {
-        color: Some(Color::Magenta),
-        modifier: Modifier::Plain,
-    };
-    pub const COMMENT: Highlight = Highlight {
-        color: Some(Color::DarkGray),
-        modifier: Modifier::Italic,
-    };
-    pub const STRING: Highlight = Highlight {
-        color: Some(Color::Green),
-        modifier: Modifier::Plain,
-    };
-    pub const NUMBER: Highlight = Highlight {
-        color: Some(Color::Cyan),
-        modifier: Modifier::Plain,
-    };
+    /// The colors for each common syntax item
+    pub struct Theme {
+        keyword: Highlight,
+        flow: Highlight,
+        constant: Highlight,
+        type_: Highlight,
+        comment: Highlight,
+        string: Highlight,
+        number: Highlight,
+    }
+
+    impl Default for Theme {
+        fn default() -> Self {
+            Self {
+                keyword: Color::Blue.into(),
+                flow: Color::Blue.into(),
+                constant: Color::Red.into(),
+                type_: Color::Magenta.into(),
+                comment: Highlight {
+                    color: Some(Color::DarkGray),
+                    modifier: Modifier::Italic,
+                },
+                string: Color::Green.into(),
+                number: Color::Cyan.into(),
+            }
+        }
+    }
+
+    impl Theme {
+        /// Parses theme from a RON struct of color names, like:
+        ///
+        /// (keyword: "LightBlue", string: "LightGreen")
+        ///
+        /// where omitted items keep their default colors
+        fn parse(ron: &str) -> Result<Self, InvalidRon> {
+            let mut theme = Self::default();
+
+            for (name, value) in fields(&strip_comments(ron))? {
+                let highlight = match name {
+                    "keyword" => &mut theme.keyword,
+                    "flow" => &mut theme.flow,
+                    "constant" => &mut theme.constant,
+                    "type" => &mut theme.type_,
+                    "comment" => &mut theme.comment,
+                    "string" => &mut theme.string,
+                    "number" => &mut theme.number,
+                    _ => return Err(InvalidRon(format!("unknown theme field {name}"))),
+                };
+
+                highlight.color = Some(
+                    parse_color(unquote(value).as_deref().unwrap_or(value))
+                        .ok_or_else(|| InvalidRon(format!("invalid color for {name}: {value}")))?,
+                );
+            }
+
+            Ok(theme)
+        }
+    }
+
+    /// Whether the terminal advertises 24-bit color support
+    static TRUE_COLOR: LazyLock<bool> = LazyLock::new(|| {
+        std::env::var("COLORTERM").is_ok_and(|c| matches!(c.as_str(), "truecolor" | "24bit"))
+    });
+
+    /// Parses a color name, a 0-255 index or a "#rrggbb" hex triple,
+    /// which is approximated by the nearest named color
+    /// if the terminal doesn't support true color
+    fn parse_color(s: &str) -> Option<Color> {
+        match s.strip_prefix('#') {
+            Some(hex) => {
+                let (r, g, b) = parse_hex(hex)?;
+                Some(match *TRUE_COLOR {
+                    true => Color::Rgb(r, g, b),
+                    false => nearest_named(r, g, b),
+                })
+            }
+            None => s.parse().ok(),
+        }
+    }
+
+    /// Parses "rrggbb" into its red, green and blue components
+    fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+        if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        Some((
+            u8::from_str_radix(&hex[0..2], 16).ok()?,
+            u8::from_str_radix(&hex[2..4], 16).ok()?,
+            u8::from_str_radix(&hex[4..6], 16).ok()?,
+        ))
+    }
+
+    /// Returns the named color closest to the given components
+    fn nearest_named(r: u8, g: u8, b: u8) -> Color {
+        // typical xterm values for the 16 named colors
+        const NAMED: [(Color, (u8, u8, u8)); 16] = [
+            (Color::Black, (0, 0, 0)),
+            (Color::Red, (205, 0, 0)),
+            (Color::Green, (0, 205, 0)),
+            (Color::Yellow, (205, 205, 0)),
+            (Color::Blue, (0, 0, 238)),
+            (Color::Magenta, (205, 0, 205)),
+            (Color::Cyan, (0, 205, 205)),
+            (Color::Gray, (229, 229, 229)),
+            (Color::DarkGray, (127, 127, 127)),
+            (Color::LightRed, (255, 0, 0)),
+            (Color::LightGreen, (0, 255, 0)),
+            (Color::LightYellow, (255, 255, 0)),
+            (Color::LightBlue, (92, 92, 255)),
+            (Color::LightMagenta, (255, 0, 255)),
+            (Color::LightCyan, (0, 255, 255)),
+            (Color::White, (255, 255, 255)),
+        ];
+
+        NAMED
+            .into_iter()
+            .min_by_key(|(_, (nr, ng, nb))| {
+                [(r, *nr), (g, *ng), (b, *nb)]
+                    .into_iter()
+                    .map(|(x, y)| u32::from(x.abs_diff(y)).pow(2))
+                    .sum::<u32>()
+            })
+            .map(|(color, _)| color)
+            .unwrap_or(Color::White)
+    }
+
+    /// Theme loaded from the theme file at startup, if any
+    pub static THEME: LazyLock<Theme> = LazyLock::new(|| {
+        let Some(path) = std::env::var_os("VLE_THEME")
+            .map(std::path::PathBuf::from)
+            .or_else(|| crate::config::CONFIG.theme.clone())
+        else {
+            return Theme::default();
+        };
+
+        match std::fs::read_to_string(&path) {
+            Ok(ron) => Theme::parse(&ron).unwrap_or_else(|err| {
+                eprintln!("* {}: {err}", path.display());
+                Theme::default()
+            }),
+            Err(err) => {
+                eprintln!("* {}: {err}", path.display());
+                Theme::default()
+            }
+        }
+    });
+
+    pub static KEYWORD: LazyLock<Highlight> = LazyLock::new(|| THEME.keyword);
+    pub static FLOW: LazyLock<Highlight> = LazyLock::new(|| THEME.flow);
+    pub static CONSTANT: LazyLock<Highlight> = LazyLock::new(|| THEME.constant);
+    pub static TYPE: LazyLock<Highlight> = LazyLock::new(|| THEME.type_);
+    pub static COMMENT: LazyLock<Highlight> = LazyLock::new(|| THEME.comment);
+    pub static STRING: LazyLock<Highlight> = LazyLock::new(|| THEME.string);
+    pub static NUMBER: LazyLock<Highlight> = LazyLock::new(|| THEME.number);
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn theme_overrides_defaults() {
+            let theme = Theme::parse(
+                "// my theme\n(\n    keyword: \"LightYellow\",\n    comment: \"Gray\", // dimmer\n)\n",
+            )
+            .unwrap();
+            assert_eq!(theme.keyword.color, Some(Color::LightYellow));
+            assert_eq!(theme.comment.color, Some(Color::Gray));
+            // a recolored item keeps its modifier
+            assert!(matches!(theme.comment.modifier, Modifier::Italic));
+            // while omitted items keep their defaults
+            assert_eq!(theme.string.color, Theme::default().string.color);
+
+            assert!(Theme::parse("(keywords: \"Red\")").is_err());
+            assert!(Theme::parse("(keyword: \"Reddish\")").is_err());
+        }
+
+        #[test]
+        fn hex_colors() {
+            assert_eq!(parse_hex("ff8800"), Some((255, 136, 0)));
+            assert_eq!(parse_hex("FFfF00"), Some((255, 255, 0)));
+            for invalid in ["", "ff88", "ff88000", "gg8800", "+f8800", "ff88\u{e9}"] {
+                assert_eq!(parse_hex(invalid), None, "{invalid:?}");
+            }
+
+            assert_eq!(
+                parse_color("#ff8800"),
+                Some(match *TRUE_COLOR {
+                    true => Color::Rgb(255, 136, 0),
+                    false => Color::Yellow,
+                })
+            );
+            assert_eq!(parse_color("#ff88"), None);
+            assert_eq!(parse_color("ff8800"), None);
+            assert_eq!(nearest_named(0, 0, 250), Color::Blue);
+            assert_eq!(nearest_named(250, 250, 250), Color::White);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Name of the syntax chosen for a local file of the given name and text
+    fn syntax_name(name: &str, text: &str) -> String {
+        syntax(&Source::Local(name.into()), &ropey::Rope::from(text)).to_string()
+    }
+
+    #[test]
+    fn syntax_by_shebang() {
+        assert_eq!(syntax_name("script", "#!/bin/bash\necho\n"), "Shell");
+        assert_eq!(syntax_name("script", "#!/usr/bin/env python3\n"), "Python");
+        assert_eq!(syntax_name("script", "#!/usr/bin/env -S perl -w\n"), "Perl");
+        assert_eq!(syntax_name("script", "#!/usr/bin/unknown\n"), "Plain");
+        assert_eq!(syntax_name("script", "# not a shebang\n"), "Plain");
+        // an extension takes precedence over any shebang
+        assert_eq!(syntax_name("script.rs", "#!/bin/sh\n"), "Rust");
+    }
+
+    #[test]
+    fn syntax_by_file_name() {
+        let makefile = syntax(&Source::Local("src/Makefile".into()), &ropey::Rope::new());
+        assert_eq!(makefile.to_string(), "Makefile");
+        assert!(makefile.tabs_required());
+
+        assert_eq!(syntax_name("Dockerfile", ""), "Dockerfile");
+        assert_eq!(syntax_name("CMakeLists.txt", ""), "CMake");
+        assert_eq!(syntax_name(".bashrc", ""), "Shell");
+        assert_eq!(syntax_name(".profile", ""), "Shell");
+        // only the whole name counts
+        assert_eq!(syntax_name("Makefile.bak", ""), "Plain");
+    }
 }
@@ -67,12 +67,12 @@ impl TryFrom<PerlToken> for Highlight {
 
     fn try_from(t: PerlToken) -> Result<Highlight, ()> {
         match t {
-            PerlToken::Function => Ok(color::KEYWORD),
-            PerlToken::Flow => Ok(color::FLOW),
+            PerlToken::Function => Ok(*color::KEYWORD),
+            PerlToken::Flow => Ok(*color::FLOW),
             PerlToken::Variable => Ok(Color::Cyan.into()),
-            PerlToken::String => Ok(color::STRING),
+            PerlToken::String => Ok(*color::STRING),
             PerlToken::Regex => Ok(Color::Magenta.into()),
-            PerlToken::Comment => Ok(color::COMMENT),
+            PerlToken::Comment => Ok(*color::COMMENT),
         }
     }
 }
@@ -86,4 +86,4 @@ impl std::fmt::Display for Perl {
     }
 }
 
-highlighter!(Perl, PerlToken);
+highlighter!("#"; Perl, PerlToken);
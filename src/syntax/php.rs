@@ -110,12 +110,14 @@ impl TryFrom<PhpToken> for Highlight {
     fn try_from(t: PhpToken) -> Result<Highlight, ()> {
         match t {
             PhpToken::Variable => Ok(Color::Cyan.into()),
-            PhpToken::Type => Ok(color::TYPE),
-            PhpToken::Keyword => Ok(color::KEYWORD),
-            PhpToken::Flow => Ok(color::FLOW),
-            PhpToken::String => Ok(color::STRING),
-            PhpToken::Comment | PhpToken::StartComment | PhpToken::EndComment => Ok(color::COMMENT),
-            PhpToken::Constant => Ok(color::CONSTANT),
+            PhpToken::Type => Ok(*color::TYPE),
+            PhpToken::Keyword => Ok(*color::KEYWORD),
+            PhpToken::Flow => Ok(*color::FLOW),
+            PhpToken::String => Ok(*color::STRING),
+            PhpToken::Comment | PhpToken::StartComment | PhpToken::EndComment => {
+                Ok(*color::COMMENT)
+            }
+            PhpToken::Constant => Ok(*color::CONSTANT),
             PhpToken::Identifier => Err(()),
         }
     }
@@ -139,12 +141,13 @@ impl std::fmt::Display for Php {
 }
 
 highlighter!(
+    "//";
     Php,
     PhpToken,
     StartComment,
     EndComment,
     "/*",
     "*/",
-    color::COMMENT,
+    *color::COMMENT,
     underliner!(s, PhpDef)
 );
@@ -11,6 +11,7 @@ use crate::syntax::{
 };
 use logos::Logos;
 use ratatui::style::Color;
+use std::ops::Range;
 
 #[derive(Logos, Debug)]
 #[logos(skip r"[ \t\n]+")]
@@ -58,6 +59,9 @@ enum PythonToken {
     #[regex(r#"\"([^\\\"]|\\.)*\""#)]
     #[regex(r"'([^\\']|\\.)*'")]
     String,
+    #[regex(r#"([fF]|[fF][rR]|[rR][fF])\"([^\\\"]|\\.)*\""#)]
+    #[regex(r"([fF]|[fF][rR]|[rR][fF])'([^\\']|\\.)*'")]
+    FString,
     #[token("\"\"\"")]
     #[token("'''")]
     MultiLineString,
@@ -72,12 +76,14 @@ impl TryFrom<PythonToken> for Highlight {
 
     fn try_from(t: PythonToken) -> Result<Highlight, ()> {
         match t {
-            PythonToken::Keyword => Ok(color::KEYWORD),
-            PythonToken::Flow => Ok(color::FLOW),
+            PythonToken::Keyword => Ok(*color::KEYWORD),
+            PythonToken::Flow => Ok(*color::FLOW),
             PythonToken::Literal => Ok(Color::LightMagenta.into()),
             PythonToken::Decorator => Ok(Color::Cyan.into()),
-            PythonToken::String | PythonToken::MultiLineString => Ok(color::STRING),
-            PythonToken::Comment => Ok(color::COMMENT),
+            PythonToken::String | PythonToken::FString | PythonToken::MultiLineString => {
+                Ok(*color::STRING)
+            }
+            PythonToken::Comment => Ok(*color::COMMENT),
             PythonToken::Variable => Err(()),
         }
     }
@@ -134,6 +140,64 @@ enum PythonDef {
     Definition,
 }
 
+/// Splits f-string into its literal portions
+/// and its {...} interpolated expressions,
+/// which are highlighted as ordinary code
+fn interpolate(s: &str, range: Range<usize>) -> Vec<(Highlight, Range<usize>)> {
+    const BRACE: Highlight = Highlight {
+        color: Some(Color::Cyan),
+        modifier: crate::syntax::Modifier::Plain,
+    };
+
+    let mut spans = vec![];
+    let mut literal_start = range.start;
+    let mut depth = 0;
+    let mut chars = s[range.clone()].char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        let idx = range.start + idx;
+        match (c, depth) {
+            // doubled braces are literal braces
+            ('{' | '}', 0) if chars.next_if(|(_, n)| *n == c).is_some() => {}
+            ('{', 0) => {
+                if literal_start < idx {
+                    spans.push((*color::STRING, literal_start..idx));
+                }
+                spans.push((BRACE, idx..idx + 1));
+                literal_start = idx + 1;
+                depth = 1;
+            }
+            ('{', _) => {
+                depth += 1;
+            }
+            ('}', 1) => {
+                spans.extend(
+                    PythonToken::lexer(&s[literal_start..idx])
+                        .spanned()
+                        .filter_map(|(t, r)| {
+                            t.ok()
+                                .and_then(|t| Highlight::try_from(t).ok())
+                                .map(|c| (c, r.start + literal_start..r.end + literal_start))
+                        }),
+                );
+                spans.push((BRACE, idx..idx + 1));
+                literal_start = idx + 1;
+                depth = 0;
+            }
+            ('}', 2..) => {
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+
+    if literal_start < range.end {
+        spans.push((*color::STRING, literal_start..range.end));
+    }
+
+    spans
+}
+
 impl Highlighter for Python {
     fn highlight<'s>(
         &self,
@@ -144,26 +208,33 @@ impl Highlighter for Python {
 
         let lexer: EitherLexer<PythonToken, MultiLineString> = EitherLexer::new(state, s);
 
-        Box::new(lexer.filter_map(move |(t, r)| {
-            match state {
-                HighlightState::Normal => t
-                    .ok()
-                    .inspect(|t| {
-                        if t.is_comment_start() {
-                            *state = HighlightState::Commenting;
+        Box::new(
+            lexer
+                .filter_map(move |(t, r)| match state {
+                    HighlightState::Commenting => Some(match t {
+                        Ok(end) if end.is_comment_end() => {
+                            *state = HighlightState::default();
+                            (Highlight::try_from(end).ok()?, r, false)
                         }
-                    })
-                    .and_then(|t| Highlight::try_from(t).ok())
-                    .map(|c| (c, r)),
-                HighlightState::Commenting => Some(match t {
-                    Ok(end) if end.is_comment_end() => {
-                        *state = HighlightState::default();
-                        (Highlight::try_from(end).ok()?, r)
-                    }
-                    _ => (color::STRING, r),
+                        _ => (*color::STRING, r, false),
+                    }),
+                    _ => t
+                        .ok()
+                        .inspect(|t| {
+                            if t.is_comment_start() {
+                                *state = HighlightState::Commenting;
+                            }
+                        })
+                        .and_then(|t| {
+                            let fstring = matches!(t, PythonToken::FString);
+                            Highlight::try_from(t).ok().map(|c| (c, r, fstring))
+                        }),
+                })
+                .flat_map(move |(c, r, fstring)| match fstring {
+                    true => interpolate(s, r),
+                    false => vec![(c, r)],
                 }),
-            }
-        }))
+        )
     }
 
     fn underline(
@@ -182,11 +253,54 @@ impl Highlighter for Python {
         Some(MultiCommentType::Unidirectional(|acc, s| {
             MultiLineString::lexer(s).fold(acc, |acc, s| match s {
                 Ok(MultiLineString::StartEnd) => match acc {
-                    HighlightState::Normal => HighlightState::Commenting,
                     HighlightState::Commenting => HighlightState::Normal,
+                    _ => HighlightState::Commenting,
                 },
                 Err(()) => acc,
             })
         }))
     }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Some("#")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::highlighted;
+
+    #[test]
+    fn fstring_interpolation() {
+        let spans = highlighted(
+            &Python,
+            "print(f\"value is {x + 1} {{not}} {y if z else w}\")\n",
+        );
+        assert!(spans.contains(&("f\"value is ".into(), color::STRING.color)));
+        assert!(spans.contains(&(" {{not}} ".into(), color::STRING.color)));
+        assert!(spans.contains(&("\"".into(), color::STRING.color)));
+        assert!(spans.contains(&("{".into(), Some(Color::Cyan))));
+        // interpolated expressions are highlighted as code
+        assert!(spans.contains(&("if".into(), color::FLOW.color)));
+        assert!(!spans.iter().any(|(text, _)| text.contains('x')));
+    }
+
+    #[test]
+    fn docstring_spans_lines() {
+        let spans = highlighted(
+            &Python,
+            "def f():\n    \"\"\"one\n    if two:\n    three\"\"\"\n    return\n",
+        );
+        assert_eq!(
+            spans
+                .iter()
+                .filter(|(_, c)| *c == color::STRING.color)
+                .map(|(text, _)| text.as_str())
+                .collect::<String>(),
+            "\"\"\"oneiftwo:three\"\"\""
+        );
+        // code resumes once the docstring closes
+        assert!(spans.contains(&("return".into(), color::FLOW.color)));
+    }
 }
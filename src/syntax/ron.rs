@@ -49,11 +49,13 @@ impl TryFrom<RonToken> for Highlight {
 
     fn try_from(t: RonToken) -> Result<Highlight, ()> {
         match t {
-            RonToken::Type => Ok(color::TYPE),
-            RonToken::Comment | RonToken::StartComment | RonToken::EndComment => Ok(color::COMMENT),
-            RonToken::String => Ok(color::STRING),
-            RonToken::Number => Ok(color::NUMBER),
-            RonToken::Boolean => Ok(color::CONSTANT),
+            RonToken::Type => Ok(*color::TYPE),
+            RonToken::Comment | RonToken::StartComment | RonToken::EndComment => {
+                Ok(*color::COMMENT)
+            }
+            RonToken::String => Ok(*color::STRING),
+            RonToken::Number => Ok(*color::NUMBER),
+            RonToken::Boolean => Ok(*color::CONSTANT),
             RonToken::Variable => Err(()),
         }
     }
@@ -69,11 +71,12 @@ impl std::fmt::Display for Ron {
 }
 
 highlighter!(
+    "//";
     Ron,
     RonToken,
     StartComment,
     EndComment,
     "/*",
     "*/",
-    color::COMMENT
+    *color::COMMENT
 );
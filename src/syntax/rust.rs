@@ -74,18 +74,33 @@ enum RustToken {
     #[regex("[[:lower:]][[:lower:][:digit:]_]*")]
     Variable,
 
-    #[regex(r"-?[0-9][0-9_]*(u8|u16|u32|u64|u128|i8|i16|i32|i64|i128)?")]
-    #[regex(r"-?[0-9][0-9_]*\.[0-9_]+(e[+-][0-9]+)?")]
-    #[regex(r"-?0b[0-1][0-1_]*(u8|u16|u32|u64|u128|i8|i16|i32|i64|i128)?")]
-    #[regex(r"-?0x[0-9a-fA-F][0-9a-fA-F_]*(u8|u16|u32|u64|u128|i8|i16|i32|i64|i128)?")]
-    #[regex(r"-?0o[0-7][0-7_]*(u8|u16|u32|u64|u128|i8|i16|i32|i64|i128)?")]
+    #[regex(r"-?[0-9][0-9_]*(u8|u16|u32|u64|u128|usize|i8|i16|i32|i64|i128|isize)?")]
+    #[regex(r"-?[0-9][0-9_]*(f32|f64)")]
+    #[regex(r"-?[0-9][0-9_]*\.[0-9][0-9_]*([eE][+-]?[0-9_]+)?(f32|f64)?")]
+    #[regex(r"-?[0-9][0-9_]*[eE][+-]?[0-9_]+(f32|f64)?")]
+    #[regex(r"-?0b[0-1][0-1_]*(u8|u16|u32|u64|u128|usize|i8|i16|i32|i64|i128|isize)?")]
+    #[regex(r"-?0x[0-9a-fA-F][0-9a-fA-F_]*(u8|u16|u32|u64|u128|usize|i8|i16|i32|i64|i128|isize)?")]
+    #[regex(r"-?0o[0-7][0-7_]*(u8|u16|u32|u64|u128|usize|i8|i16|i32|i64|i128|isize)?")]
     Number,
 
     #[regex(r#"\"([^\\\"]|\\.)*\""#)]
     #[regex(r"'([^\\\']|\\.){0,1}'")]
     #[regex(r"'\\u\{[0-9A-Za-z]+\}'")]
+    #[regex(r#"b\"([^\\\"]|\\.)*\""#)]
+    #[regex(r"b'([^\\\']|\\.)'")]
+    #[regex(r#"b?r\"[^\"]*\""#)]
+    // logos always takes the longest match, so rather than a lazy .*?,
+    // raw strings' contents are whatever never completes their closing fence
+    #[regex(r###"b?r#"([^"]|"+[^"#])*"+#"###)]
+    #[regex(r###"b?r##"([^"]|"("|#")*([^"#]|#[^"#]))*"("|#")*##"###)]
     String,
 
+    #[regex("'[[:lower:]_][[:lower:][:digit:]_]*")]
+    Lifetime,
+
+    #[regex(r"#!?\[[^\]]*\]")]
+    Attribute,
+
     #[regex("[[:lower:]_]+!")]
     Macro,
 
@@ -107,16 +122,18 @@ impl TryFrom<RustToken> for Highlight {
 
     fn try_from(t: RustToken) -> Result<Highlight, ()> {
         match t {
-            RustToken::Keyword => Ok(color::KEYWORD),
-            RustToken::Constant => Ok(color::CONSTANT),
+            RustToken::Keyword => Ok(*color::KEYWORD),
+            RustToken::Constant => Ok(*color::CONSTANT),
             RustToken::Macro => Ok(Color::Red.into()),
-            RustToken::Flow => Ok(color::FLOW),
-            RustToken::Type => Ok(color::TYPE),
+            RustToken::Lifetime => Ok(Color::Cyan.into()),
+            RustToken::Attribute => Ok(Color::Magenta.into()),
+            RustToken::Flow => Ok(*color::FLOW),
+            RustToken::Type => Ok(*color::TYPE),
             RustToken::Comment | RustToken::StartComment | RustToken::EndComment => {
-                Ok(color::COMMENT)
+                Ok(*color::COMMENT)
             }
-            RustToken::String => Ok(color::STRING),
-            RustToken::Number => Ok(color::NUMBER),
+            RustToken::String => Ok(*color::STRING),
+            RustToken::Number => Ok(*color::NUMBER),
             RustToken::Variable => Err(()),
         }
     }
@@ -144,13 +161,14 @@ impl std::fmt::Display for Rust {
 }
 
 highlighter!(
+    "//";
     Rust,
     RustToken,
     StartComment,
     EndComment,
     "/*",
     "*/",
-    color::COMMENT,
+    *color::COMMENT,
     Some(|s| {
         Box::new(RustDef::lexer(s).spanned().filter_map(|(t, r)| match t {
             Ok(RustDef::Definition) => Some(r),
@@ -161,3 +179,78 @@ highlighter!(
         }))
     })
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::highlighted;
+
+    /// The spans colored as strings
+    fn strings(text: &str) -> Vec<String> {
+        highlighted(&Rust, text)
+            .into_iter()
+            .filter(|(_, c)| *c == color::STRING.color)
+            .map(|(s, _)| s)
+            .collect()
+    }
+
+    #[test]
+    fn raw_strings() {
+        assert_eq!(
+            strings(r###"let s = r#"a "quote" inside"#; let t = 1;"###),
+            [r###"r#"a "quote" inside"#"###]
+        );
+        assert_eq!(strings(r###"r#"a""#"###), [r###"r#"a""#"###]);
+        assert_eq!(strings(r####"br##"a"#b"##"####), [r####"br##"a"#b"##"####]);
+        assert_eq!(strings(r####"r##""#"""##"####), [r####"r##""#"""##"####]);
+        assert_eq!(strings(r#"r"no \ escapes""#), [r#"r"no \ escapes""#]);
+
+        // each literal ends at its own closing fence
+        assert_eq!(
+            strings(r###"(r#"a"#, r#"b"#)"###),
+            [r###"r#"a"#"###, r###"r#"b"#"###]
+        );
+
+        // nor does one run on without its closing fence
+        assert!(strings(r###"r#"unclosed"###).is_empty());
+        assert_eq!(strings(r###"r#"no fence""###), [r#""no fence""#]);
+        assert_eq!(strings(r###"r##"short"#"###), [r#""short""#]);
+    }
+
+    #[test]
+    fn numbers() {
+        let spans = highlighted(
+            &Rust,
+            "let n = [1_000, 0xFF, 3.14f32, 1e-9, 42u32, 0o17, 0b1010];",
+        );
+        for n in [
+            "1_000", "0xFF", "3.14f32", "1e-9", "42u32", "0o17", "0b1010",
+        ] {
+            assert!(spans.contains(&(n.into(), color::NUMBER.color)), "{n}");
+        }
+    }
+
+    #[test]
+    fn lifetimes_and_attributes() {
+        let spans = highlighted(
+            &Rust,
+            "#[derive(Debug)]\nfn f<'a>(x: &'a str) -> char { 'a' }\n",
+        );
+        assert!(spans.contains(&("#[derive(Debug)]".into(), Some(Color::Magenta))));
+        assert_eq!(
+            spans
+                .iter()
+                .filter(|span| **span == ("'a".into(), Some(Color::Cyan)))
+                .count(),
+            2
+        );
+        // while a character literal is still a string
+        assert!(spans.contains(&("'a'".into(), color::STRING.color)));
+
+        let spans = highlighted(&Rust, "#![forbid(unsafe_code)]\n");
+        assert_eq!(
+            spans,
+            [("#![forbid(unsafe_code)]".into(), Some(Color::Magenta))]
+        );
+    }
+}
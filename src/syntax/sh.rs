@@ -92,6 +92,11 @@ enum ShellToken {
     String,
     #[regex("[[:lower:]][[:lower:][:digit:]_]*")]
     Variable,
+    #[regex(r"\$[[:alpha:]_][[:alnum:]_]*")]
+    #[regex(r"\$\{[^}]*\}")]
+    #[regex(r"\$\([^)]*\)")]
+    #[regex(r"\$[0-9@#?$!*-]")]
+    Expansion,
 }
 
 impl TryFrom<ShellToken> for Highlight {
@@ -99,12 +104,13 @@ impl TryFrom<ShellToken> for Highlight {
 
     fn try_from(t: ShellToken) -> Result<Highlight, ()> {
         match t {
-            ShellToken::Keyword => Ok(color::KEYWORD),
-            ShellToken::Flow => Ok(color::FLOW),
+            ShellToken::Keyword => Ok(*color::KEYWORD),
+            ShellToken::Flow => Ok(*color::FLOW),
             ShellToken::Command => Ok(Color::LightBlue.into()),
-            ShellToken::Comment => Ok(color::COMMENT),
+            ShellToken::Comment => Ok(*color::COMMENT),
             ShellToken::Option => Ok(Color::LightMagenta.into()),
-            ShellToken::String => Ok(color::STRING),
+            ShellToken::String => Ok(*color::STRING),
+            ShellToken::Expansion => Ok(Color::Cyan.into()),
             ShellToken::Variable => Err(()),
         }
     }
@@ -119,4 +125,23 @@ impl std::fmt::Display for Shell {
     }
 }
 
-highlighter!(Shell, ShellToken);
+highlighter!("#"; Shell, ShellToken);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::highlighted;
+
+    #[test]
+    fn expansions() {
+        let spans = highlighted(&Shell, "cp $HOME/${dir} $(pwd) $1 $@ $# \"$HOME\"\n");
+        for expansion in ["$HOME", "${dir}", "$(pwd)", "$1", "$@", "$#"] {
+            assert!(
+                spans.contains(&(expansion.into(), Some(Color::Cyan))),
+                "{expansion}"
+            );
+        }
+        // within a string, the whole string is colored as one
+        assert!(spans.contains(&("\"$HOME\"".into(), color::STRING.color)));
+    }
+}
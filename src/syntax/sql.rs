@@ -13,105 +13,107 @@ use logos::Logos;
 #[derive(Logos, Debug)]
 #[logos(skip r"[ \t\n]+")]
 enum SqlToken {
-    #[token("ADD")]
-    #[token("CONSTRAINT")]
-    #[token("ALL")]
-    #[token("ALTER")]
-    #[token("COLUMN")]
-    #[token("TABLE")]
-    #[token("AND")]
-    #[token("ANY")]
-    #[token("AS")]
-    #[token("ASC")]
-    #[token("BACKUP")]
-    #[token("DATABASE")]
-    #[token("BEGIN")]
-    #[token("BETWEEN")]
-    #[token("CHECK")]
-    #[token("CREATE")]
-    #[token("INDEX")]
-    #[token("REPLACE")]
-    #[token("VIEW")]
-    #[token("PROCEDURE")]
-    #[token("UNIQUE")]
-    #[token("DEFAULT")]
-    #[token("DELETE")]
-    #[token("INSERT")]
-    #[token("BEFORE")]
-    #[token("TRIGGER")]
-    #[token("DESC")]
-    #[token("DISTINCT")]
-    #[token("DROP")]
-    #[token("EXEC")]
-    #[token("FOREIGN")]
-    #[token("KEY")]
-    #[token("FROM")]
-    #[token("FULL")]
-    #[token("OUTER")]
-    #[token("EXISTS")]
-    #[token("INNER")]
-    #[token("JOIN")]
-    #[token("GROUP")]
-    #[token("BY")]
-    #[token("HAVING")]
-    #[token("IN")]
-    #[token("IS")]
-    #[token("NULL")]
-    #[token("NOT")]
-    #[token("LIKE")]
-    #[token("LIMIT")]
-    #[token("OR")]
-    #[token("ORDER")]
-    #[token("PRIMARY")]
-    #[token("RIGHT")]
-    #[token("ROWNUM")]
-    #[token("SELECT")]
-    #[token("INTO")]
-    #[token("TOP")]
-    #[token("SET")]
-    #[token("TRUNCATE")]
-    #[token("UNION")]
-    #[token("UPDATE")]
-    #[token("VALUES")]
-    #[token("WHERE")]
-    #[token("ON")]
-    #[token("USING")]
-    #[token("CASCADE")]
-    #[token("REFERENCES")]
-    #[token("ENGINE")]
-    #[token("MIN")]
-    #[token("MAX")]
-    #[token("CONCAT")]
-    #[token("COUNT")]
+    #[regex("[[:alpha:]_][[:alnum:]_]*")]
+    Identifier,
+    #[token("ADD", ignore(case))]
+    #[token("CONSTRAINT", ignore(case))]
+    #[token("ALL", ignore(case))]
+    #[token("ALTER", ignore(case))]
+    #[token("COLUMN", ignore(case))]
+    #[token("TABLE", ignore(case))]
+    #[token("AND", ignore(case))]
+    #[token("ANY", ignore(case))]
+    #[token("AS", ignore(case))]
+    #[token("ASC", ignore(case))]
+    #[token("BACKUP", ignore(case))]
+    #[token("DATABASE", ignore(case))]
+    #[token("BEGIN", ignore(case))]
+    #[token("BETWEEN", ignore(case))]
+    #[token("CHECK", ignore(case))]
+    #[token("CREATE", ignore(case))]
+    #[token("INDEX", ignore(case))]
+    #[token("REPLACE", ignore(case))]
+    #[token("VIEW", ignore(case))]
+    #[token("PROCEDURE", ignore(case))]
+    #[token("UNIQUE", ignore(case))]
+    #[token("DEFAULT", ignore(case))]
+    #[token("DELETE", ignore(case))]
+    #[token("INSERT", ignore(case))]
+    #[token("BEFORE", ignore(case))]
+    #[token("TRIGGER", ignore(case))]
+    #[token("DESC", ignore(case))]
+    #[token("DISTINCT", ignore(case))]
+    #[token("DROP", ignore(case))]
+    #[token("EXEC", ignore(case))]
+    #[token("FOREIGN", ignore(case))]
+    #[token("KEY", ignore(case))]
+    #[token("FROM", ignore(case))]
+    #[token("FULL", ignore(case))]
+    #[token("OUTER", ignore(case))]
+    #[token("EXISTS", ignore(case))]
+    #[token("INNER", ignore(case))]
+    #[token("JOIN", ignore(case))]
+    #[token("GROUP", ignore(case))]
+    #[token("BY", ignore(case))]
+    #[token("HAVING", ignore(case))]
+    #[token("IN", ignore(case))]
+    #[token("IS", ignore(case))]
+    #[token("NULL", ignore(case))]
+    #[token("NOT", ignore(case))]
+    #[token("LIKE", ignore(case))]
+    #[token("LIMIT", ignore(case))]
+    #[token("OR", ignore(case))]
+    #[token("ORDER", ignore(case))]
+    #[token("PRIMARY", ignore(case))]
+    #[token("RIGHT", ignore(case))]
+    #[token("ROWNUM", ignore(case))]
+    #[token("SELECT", ignore(case))]
+    #[token("INTO", ignore(case))]
+    #[token("TOP", ignore(case))]
+    #[token("SET", ignore(case))]
+    #[token("TRUNCATE", ignore(case))]
+    #[token("UNION", ignore(case))]
+    #[token("UPDATE", ignore(case))]
+    #[token("VALUES", ignore(case))]
+    #[token("WHERE", ignore(case))]
+    #[token("ON", ignore(case))]
+    #[token("USING", ignore(case))]
+    #[token("CASCADE", ignore(case))]
+    #[token("REFERENCES", ignore(case))]
+    #[token("ENGINE", ignore(case))]
+    #[token("MIN", ignore(case))]
+    #[token("MAX", ignore(case))]
+    #[token("CONCAT", ignore(case))]
+    #[token("COUNT", ignore(case))]
     Keyword,
-    #[regex(r"BIT\([0-9]+\)")]
-    #[regex(r"TINYINT\([0-9]+\)")]
-    #[token("BOOL")]
-    #[regex(r"SMALLINT\([0-9]+\)")]
-    #[regex(r"MEDIUMINT\([0-9]+\)")]
-    #[regex(r"INT\([0-9]+\)")]
-    #[regex(r"INTEGER\([0-9]+\)")]
-    #[regex(r"BIGINT\([0-9]+\)")]
-    #[regex(r"FLOAT\([0-9]+\)")]
-    #[regex(r"DOUBLE\([0-9]+\)")]
+    #[regex(r"BIT\([0-9]+\)", ignore(case))]
+    #[regex(r"TINYINT\([0-9]+\)", ignore(case))]
+    #[token("BOOL", ignore(case))]
+    #[regex(r"SMALLINT\([0-9]+\)", ignore(case))]
+    #[regex(r"MEDIUMINT\([0-9]+\)", ignore(case))]
+    #[regex(r"INT\([0-9]+\)", ignore(case))]
+    #[regex(r"INTEGER\([0-9]+\)", ignore(case))]
+    #[regex(r"BIGINT\([0-9]+\)", ignore(case))]
+    #[regex(r"FLOAT\([0-9]+\)", ignore(case))]
+    #[regex(r"DOUBLE\([0-9]+\)", ignore(case))]
     Type,
-    #[token("CASE")]
-    #[token("WHEN")]
-    #[token("IF")]
-    #[token("THEN")]
-    #[token("ELSE")]
-    #[token("ELSEIF")]
-    #[token("LOOP")]
-    #[token("CONTINUE")]
-    #[token("EXIT")]
-    #[token("FOR")]
-    #[token("FOREACH")]
-    #[token("WHILE")]
-    #[token("END")]
-    #[token("RAISE")]
-    #[token("EXCEPTION")]
-    #[token("NOTICE")]
-    #[token("RETURN")]
+    #[token("CASE", ignore(case))]
+    #[token("WHEN", ignore(case))]
+    #[token("IF", ignore(case))]
+    #[token("THEN", ignore(case))]
+    #[token("ELSE", ignore(case))]
+    #[token("ELSEIF", ignore(case))]
+    #[token("LOOP", ignore(case))]
+    #[token("CONTINUE", ignore(case))]
+    #[token("EXIT", ignore(case))]
+    #[token("FOR", ignore(case))]
+    #[token("FOREACH", ignore(case))]
+    #[token("WHILE", ignore(case))]
+    #[token("END", ignore(case))]
+    #[token("RAISE", ignore(case))]
+    #[token("EXCEPTION", ignore(case))]
+    #[token("NOTICE", ignore(case))]
+    #[token("RETURN", ignore(case))]
     Flow,
     #[regex("'([^']|\\')*'")]
     #[regex(r#"\"([^\\\"]|\\.)*\""#)]
@@ -123,10 +125,11 @@ impl TryFrom<SqlToken> for Highlight {
 
     fn try_from(t: SqlToken) -> Result<Highlight, ()> {
         match t {
-            SqlToken::Keyword => Ok(color::KEYWORD),
-            SqlToken::Type => Ok(color::TYPE),
-            SqlToken::Flow => Ok(color::FLOW),
-            SqlToken::String => Ok(color::STRING),
+            SqlToken::Keyword => Ok(*color::KEYWORD),
+            SqlToken::Type => Ok(*color::TYPE),
+            SqlToken::Flow => Ok(*color::FLOW),
+            SqlToken::String => Ok(*color::STRING),
+            SqlToken::Identifier => Err(()),
         }
     }
 }
@@ -140,4 +143,33 @@ impl std::fmt::Display for Sql {
     }
 }
 
-highlighter!(Sql, SqlToken);
+highlighter!("--"; Sql, SqlToken);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::highlighted;
+
+    #[test]
+    fn keywords_ignore_case() {
+        for select in ["select", "Select", "SELECT"] {
+            assert_eq!(
+                highlighted(&Sql, &format!("{select} * from selected\n")),
+                [
+                    (select.into(), color::KEYWORD.color),
+                    ("from".into(), color::KEYWORD.color),
+                ]
+            );
+        }
+        assert_eq!(
+            highlighted(&Sql, "Case When x Then 'y' End\n"),
+            [
+                ("Case".into(), color::FLOW.color),
+                ("When".into(), color::FLOW.color),
+                ("Then".into(), color::FLOW.color),
+                ("'y'".into(), color::STRING.color),
+                ("End".into(), color::FLOW.color),
+            ]
+        );
+    }
+}
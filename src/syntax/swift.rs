@@ -148,13 +148,13 @@ impl TryFrom<SwiftToken> for Highlight {
 
     fn try_from(t: SwiftToken) -> Result<Highlight, ()> {
         match t {
-            SwiftToken::String => Ok(color::STRING),
-            SwiftToken::Keyword => Ok(color::KEYWORD),
-            SwiftToken::Flow => Ok(color::FLOW),
-            SwiftToken::Type => Ok(color::TYPE),
+            SwiftToken::String => Ok(*color::STRING),
+            SwiftToken::Keyword => Ok(*color::KEYWORD),
+            SwiftToken::Flow => Ok(*color::FLOW),
+            SwiftToken::Type => Ok(*color::TYPE),
             SwiftToken::Identifier => Err(()),
             SwiftToken::Comment | SwiftToken::StartComment | SwiftToken::EndComment => {
-                Ok(color::COMMENT)
+                Ok(*color::COMMENT)
             }
         }
     }
@@ -180,12 +180,13 @@ impl std::fmt::Display for Swift {
 }
 
 highlighter!(
+    "//";
     Swift,
     SwiftToken,
     StartComment,
     EndComment,
     "/*",
     "*/",
-    color::COMMENT,
+    *color::COMMENT,
     underliner!(s, SwiftDef)
 );
@@ -33,7 +33,7 @@ impl TryFrom<TexToken> for Highlight {
             TexToken::Command => Ok(Color::Green.into()),
             TexToken::Punctuation => Ok(Color::Magenta.into()),
             TexToken::Math => Ok(Color::Red.into()),
-            TexToken::Comment => Ok(color::COMMENT),
+            TexToken::Comment => Ok(*color::COMMENT),
         }
     }
 }
@@ -47,4 +47,4 @@ impl std::fmt::Display for Tex {
     }
 }
 
-highlighter!(Tex, TexToken);
+highlighter!("%"; Tex, TexToken);
@@ -36,9 +36,9 @@ impl TryFrom<TomlToken> for Highlight {
     fn try_from(t: TomlToken) -> Result<Highlight, ()> {
         match t {
             TomlToken::Encloser => Ok(Color::Blue.into()),
-            TomlToken::Key => Ok(color::KEYWORD),
-            TomlToken::Value => Ok(color::STRING),
-            TomlToken::Comment => Ok(color::COMMENT),
+            TomlToken::Key => Ok(*color::KEYWORD),
+            TomlToken::Value => Ok(*color::STRING),
+            TomlToken::Comment => Ok(*color::COMMENT),
         }
     }
 }
@@ -53,6 +53,7 @@ impl std::fmt::Display for Toml {
 }
 
 highlighter!(
+    "#";
     Toml,
     TomlToken,
     Some(|s| {
@@ -6,9 +6,13 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use crate::syntax::{Commenting, Highlight, Plain, color};
-use crate::{highlighter, underliner};
+use crate::syntax::js::{TEMPLATE, template};
+use crate::syntax::{
+    Continued, Highlight, HighlightState, Highlighter, MultiCommentType, Plain, color,
+    highlight_continued,
+};
 use logos::Logos;
+use std::ops::Range;
 
 #[derive(Logos, Debug)]
 #[logos(skip r"[ \t\n]+")]
@@ -59,6 +63,8 @@ enum TypeScriptToken {
     #[regex("'([^']|\\')*'")]
     #[regex(r#"\"([^\\\"]|\\.)*\""#)]
     String,
+    #[token("`")]
+    StartTemplate,
     #[token("string")]
     #[token("number")]
     #[token("boolean")]
@@ -86,13 +92,13 @@ impl TryFrom<TypeScriptToken> for Highlight {
         match t {
             TypeScriptToken::Comment
             | TypeScriptToken::StartComment
-            | TypeScriptToken::EndComment => Ok(color::COMMENT),
-            TypeScriptToken::Keyword => Ok(color::KEYWORD),
-            TypeScriptToken::Flow | TypeScriptToken::Break => Ok(color::FLOW),
-            TypeScriptToken::String => Ok(color::STRING),
-            TypeScriptToken::Number => Ok(color::NUMBER),
-            TypeScriptToken::Type => Ok(color::TYPE),
-            TypeScriptToken::Constant => Ok(color::CONSTANT),
+            | TypeScriptToken::EndComment => Ok(*color::COMMENT),
+            TypeScriptToken::Keyword => Ok(*color::KEYWORD),
+            TypeScriptToken::Flow | TypeScriptToken::Break => Ok(*color::FLOW),
+            TypeScriptToken::String | TypeScriptToken::StartTemplate => Ok(*color::STRING),
+            TypeScriptToken::Number => Ok(*color::NUMBER),
+            TypeScriptToken::Type => Ok(*color::TYPE),
+            TypeScriptToken::Constant => Ok(*color::CONSTANT),
             TypeScriptToken::Identifier => Err(()),
         }
     }
@@ -117,13 +123,57 @@ impl std::fmt::Display for TypeScript {
     }
 }
 
-highlighter!(
-    TypeScript,
-    TypeScriptToken,
-    StartComment,
-    EndComment,
-    "/*",
-    "*/",
-    color::COMMENT,
-    underliner!(s, TypeScriptDef)
-);
+impl Plain for TypeScriptToken {
+    fn is_comment_start(&self) -> bool {
+        matches!(self, Self::StartComment)
+    }
+}
+
+impl Continued for TypeScriptToken {
+    fn continues(&self) -> Option<&'static str> {
+        matches!(self, Self::StartTemplate).then_some(TEMPLATE)
+    }
+
+    fn resume(
+        s: &str,
+        start: usize,
+        _: &'static str,
+        state: &mut HighlightState,
+        spans: &mut Vec<(Highlight, Range<usize>)>,
+    ) -> usize {
+        template::<Self>(s, start, state, spans)
+    }
+}
+
+impl Highlighter for TypeScript {
+    fn highlight<'s>(
+        &self,
+        s: &'s str,
+        state: &'s mut HighlightState,
+    ) -> Box<dyn Iterator<Item = (Highlight, Range<usize>)> + 's> {
+        Box::new(highlight_continued::<TypeScriptToken>(s, state).into_iter())
+    }
+
+    fn underline(
+        &self,
+    ) -> Option<for<'s> fn(&'s str) -> Box<dyn Iterator<Item = Range<usize>> + 's>> {
+        Some(|s| {
+            Box::new(
+                TypeScriptDef::lexer(s)
+                    .spanned()
+                    .filter_map(|(t, r)| t.ok().map(|_| r)),
+            )
+        })
+    }
+
+    fn multicomment(&self) -> Option<MultiCommentType> {
+        Some(MultiCommentType::Unidirectional(|mut state, s| {
+            highlight_continued::<TypeScriptToken>(s, &mut state);
+            state
+        }))
+    }
+
+    fn line_comment(&self) -> Option<&'static str> {
+        Some("//")
+    }
+}
@@ -38,8 +38,8 @@ impl TryFrom<XmlToken> for Highlight {
         match t {
             XmlToken::TagStart | XmlToken::TagEnd => Ok(Color::Cyan.into()),
             XmlToken::FieldName => Ok(Color::Blue.into()),
-            XmlToken::String => Ok(color::STRING),
-            XmlToken::StartComment | XmlToken::EndComment => Ok(color::COMMENT),
+            XmlToken::String => Ok(*color::STRING),
+            XmlToken::StartComment | XmlToken::EndComment => Ok(*color::COMMENT),
         }
     }
 }
@@ -60,5 +60,5 @@ highlighter!(
     EndComment,
     "<!--",
     "-->",
-    color::COMMENT
+    *color::COMMENT
 );
@@ -49,12 +49,12 @@ impl TryFrom<YamlToken> for Highlight {
 
     fn try_from(t: YamlToken) -> Result<Highlight, ()> {
         match t {
-            YamlToken::Comment => Ok(color::COMMENT),
+            YamlToken::Comment => Ok(*color::COMMENT),
             YamlToken::Symbol => Ok(Color::Yellow.into()),
-            YamlToken::String => Ok(color::STRING),
+            YamlToken::String => Ok(*color::STRING),
             YamlToken::Name => Ok(Color::Magenta.into()),
-            YamlToken::Number => Ok(color::NUMBER),
-            YamlToken::Boolean => Ok(color::KEYWORD),
+            YamlToken::Number => Ok(*color::NUMBER),
+            YamlToken::Boolean => Ok(*color::KEYWORD),
         }
     }
 }
@@ -68,4 +68,4 @@ impl std::fmt::Display for Yaml {
     }
 }
 
-highlighter!(Yaml, YamlToken);
+highlighter!("#"; Yaml, YamlToken);
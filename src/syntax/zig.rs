@@ -117,16 +117,16 @@ impl TryFrom<ZigToken> for Highlight {
         use crate::syntax::Modifier;
 
         match t {
-            ZigToken::Keyword => Ok(color::KEYWORD),
+            ZigToken::Keyword => Ok(*color::KEYWORD),
             ZigToken::StructUnion => Ok(Highlight {
                 modifier: Modifier::Underlined,
-                ..color::KEYWORD
+                ..*color::KEYWORD
             }),
-            ZigToken::Flow => Ok(color::FLOW),
-            ZigToken::String => Ok(color::STRING),
+            ZigToken::Flow => Ok(*color::FLOW),
+            ZigToken::String => Ok(*color::STRING),
             ZigToken::BuiltinFunction => Ok(Color::Cyan.into()),
-            ZigToken::Comment => Ok(color::COMMENT),
-            ZigToken::Type => Ok(color::TYPE),
+            ZigToken::Comment => Ok(*color::COMMENT),
+            ZigToken::Type => Ok(*color::TYPE),
             ZigToken::Identifier => Err(()),
         }
     }
@@ -148,4 +148,4 @@ impl std::fmt::Display for Zig {
     }
 }
 
-highlighter!(Zig, ZigToken, underliner!(s, ZigDef));
+highlighter!("//"; Zig, ZigToken, underliner!(s, ZigDef));
@@ -98,3 +98,72 @@ fn truncate_cow(s: Cow<'_, str>, mut columns: usize) -> Cow<'_, str> {
         }
     }
 }
+
+/// Given a line's characters and their display widths,
+/// returns the display columns at which each soft-wrapped row starts
+///
+/// Rows are broken after whitespace where possible,
+/// and rows after the first are narrowed by the given indent.
+pub fn wrap_points(
+    chars: impl IntoIterator<Item = (char, usize)>,
+    width: usize,
+    indent: usize,
+) -> Vec<usize> {
+    let mut points = vec![0];
+    let mut row_start = 0;
+    let mut row_width = width.max(1);
+    let mut word_start = None;
+    let mut col = 0;
+
+    for (c, w) in chars {
+        // whitespace may hang past the end of a row
+        while w > 0 && !c.is_whitespace() && col + w > row_start + row_width && col > row_start {
+            row_start = match word_start {
+                Some(start) if start > row_start => start,
+                _ => col,
+            };
+            points.push(row_start);
+            row_width = width.saturating_sub(indent).max(1);
+            word_start = None;
+        }
+        col += w;
+        if c.is_whitespace() {
+            word_start = Some(col);
+        }
+    }
+
+    points
+}
+
+/// Splits line into rows at the given display columns,
+/// the first of which should be 0
+pub fn wrap_line<'l>(line: Line<'l>, points: &[usize]) -> Vec<Line<'l>> {
+    use unicode_width::UnicodeWidthChar;
+
+    let style = line.style;
+    let mut breaks = points.iter().skip(1).copied().peekable();
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut col = 0;
+
+    for span in line.spans {
+        let mut text = String::new();
+        for c in span.content.chars() {
+            let width = c.width().unwrap_or(0);
+            if width > 0 && breaks.next_if(|b| *b <= col).is_some() {
+                if !text.is_empty() {
+                    row.push(Span::styled(std::mem::take(&mut text), span.style));
+                }
+                rows.push(Line::from(std::mem::take(&mut row)).style(style));
+            }
+            text.push(c);
+            col += width;
+        }
+        if !text.is_empty() {
+            row.push(Span::styled(text, span.style));
+        }
+    }
+    rows.push(Line::from(row).style(style));
+
+    rows
+}